@@ -2,7 +2,7 @@
 
 extern crate test;
 
-use min_max_heap::MinMaxHeap;
+use min_max_heap::{DaryMinMaxHeap, IntervalHeap, MinMaxHeap};
 use test::Bencher;
 
 #[bench]
@@ -38,3 +38,69 @@ fn pop_min_seq(b: &mut Bencher) {
     });
 }
 
+#[bench]
+fn interval_push_seq(b: &mut Bencher) {
+    b.iter(|| {
+        let n = 1000;
+        let mut heap = IntervalHeap::with_capacity(n);
+        for i in 0..n {
+            heap.push(i);
+        }
+    });
+}
+
+#[bench]
+fn interval_pop_max_seq(b: &mut Bencher) {
+    b.iter(|| {
+        let n = 1000;
+        let mut heap: IntervalHeap<_> = (0..n).collect();
+        for _ in 0..n {
+            heap.pop_max();
+        }
+    });
+}
+
+#[bench]
+fn interval_pop_min_seq(b: &mut Bencher) {
+    b.iter(|| {
+        let n = 1000;
+        let mut heap: IntervalHeap<_> = (0..n).collect();
+        for _ in 0..n {
+            heap.pop_min();
+        }
+    });
+}
+
+#[bench]
+fn dary4_pop_min_seq(b: &mut Bencher) {
+    b.iter(|| {
+        let n = 1000;
+        let mut heap: DaryMinMaxHeap<_, 4> = (0..n).collect();
+        for _ in 0..n {
+            heap.pop_min();
+        }
+    });
+}
+
+#[bench]
+fn dary4_pop_max_seq(b: &mut Bencher) {
+    b.iter(|| {
+        let n = 1000;
+        let mut heap: DaryMinMaxHeap<_, 4> = (0..n).collect();
+        for _ in 0..n {
+            heap.pop_max();
+        }
+    });
+}
+
+#[bench]
+fn from_sorted_iter_asc(b: &mut Bencher) {
+    let vec: Vec<u64> = (0..10_000).collect();
+    b.iter(|| MinMaxHeap::from_sorted_iter_asc(vec.clone()));
+}
+
+#[bench]
+fn from_vec_sorted(b: &mut Bencher) {
+    let vec: Vec<u64> = (0..10_000).collect();
+    b.iter(|| MinMaxHeap::from(vec.clone()));
+}
@@ -0,0 +1,736 @@
+use alloc::alloc::Global;
+use alloc::vec::{self, Vec};
+use core::alloc::Allocator;
+use core::iter::FusedIterator;
+use core::ops::{Deref, DerefMut};
+use core::fmt;
+
+use super::{Compare, DoubleEndedHeap, Iter, IterAsc, IterDesc, MinMaxHeap, Natural,
+            find_max_slice, push_pop_max_slice, push_pop_min_slice, push_slice,
+            rebuild_slice, replace_max_slice, replace_min_slice, sift_max_slice,
+            trickle_down_extreme_slice};
+use super::frontier::Frontier;
+
+/// A min-max-heap whose elements live in memory from an [`Allocator`]
+/// `A`, such as a per-request arena.
+///
+/// This is a separate type, rather than an allocator parameter on
+/// [`MinMaxHeap`], because `Vec<T, A>` and `Allocator` are unstable: a
+/// parameter defaulting to [`Global`] would put them in the signature
+/// of the stable type, and in every one of its `impl`s. Instead this
+/// mirrors `MinMaxHeap`’s pushing, peeking, popping, replacing,
+/// draining, and sorting methods, running the same sifting code on its
+/// vector, and a heap that uses `Global` converts into a `MinMaxHeap`
+/// with [`into_heap`](#method.into_heap) for the rest.
+///
+/// Requires the `allocator_api` feature, which needs a nightly compiler.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(allocator_api)]
+///
+/// use min_max_heap::AllocMinMaxHeap;
+/// use std::alloc::System;
+///
+/// let mut heap = AllocMinMaxHeap::new_in(System);
+/// heap.extend(vec![3, 1, 4, 1, 5]);
+///
+/// assert_eq!(heap.pop_min(), Some(1));
+/// assert_eq!(heap.pop_max(), Some(5));
+/// assert_eq!(heap.into_vec_asc(), vec![1, 3, 4]);
+/// ```
+///
+/// [`Allocator`]: https://doc.rust-lang.org/std/alloc/trait.Allocator.html
+/// [`Global`]: https://doc.rust-lang.org/std/alloc/struct.Global.html
+/// [`MinMaxHeap`]: struct.MinMaxHeap.html
+pub struct AllocMinMaxHeap<T, A: Allocator, C = Natural> {
+    data: Vec<T, A>,
+    cmp:  C,
+}
+
+impl<T: Ord, A: Allocator> AllocMinMaxHeap<T, A> {
+    /// Creates a new, empty `AllocMinMaxHeap` that allocates from
+    /// `alloc`.
+    ///
+    /// *O*(1).
+    pub fn new_in(alloc: A) -> Self {
+        AllocMinMaxHeap::with_comparator_in(Natural, alloc)
+    }
+
+    /// Creates a new, empty `AllocMinMaxHeap` with space allocated from
+    /// `alloc` to hold `len` elements.
+    ///
+    /// *O*(1).
+    pub fn with_capacity_in(len: usize, alloc: A) -> Self {
+        AllocMinMaxHeap::with_capacity_and_comparator_in(len, Natural, alloc)
+    }
+}
+
+impl<T, A: Allocator, C> AllocMinMaxHeap<T, A, C> {
+    /// Creates a new, empty `AllocMinMaxHeap` ordered by `cmp` that
+    /// allocates from `alloc`.
+    ///
+    /// *O*(1).
+    pub fn with_comparator_in(cmp: C, alloc: A) -> Self {
+        AllocMinMaxHeap {
+            data: Vec::new_in(alloc),
+            cmp,
+        }
+    }
+
+    /// Creates a new, empty `AllocMinMaxHeap` ordered by `cmp`, with
+    /// space allocated from `alloc` to hold `len` elements.
+    ///
+    /// *O*(1).
+    pub fn with_capacity_and_comparator_in(len: usize, cmp: C, alloc: A) -> Self {
+        AllocMinMaxHeap {
+            data: Vec::with_capacity_in(len, alloc),
+            cmp,
+        }
+    }
+
+    /// Returns a reference to the comparator.
+    ///
+    /// *O*(1).
+    pub fn comparator(&self) -> &C {
+        &self.cmp
+    }
+
+    /// The allocator that the heap allocates from.
+    ///
+    /// *O*(1).
+    pub fn allocator(&self) -> &A {
+        self.data.allocator()
+    }
+
+    /// The number of elements in the heap.
+    ///
+    /// *O*(1).
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Is the heap empty?
+    ///
+    /// *O*(1).
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// The number of elements the heap can hold without reallocating.
+    ///
+    /// *O*(1).
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    /// Reserves space for at least `additional` more elements.
+    ///
+    /// *O*(*n*) if it reallocates.
+    pub fn reserve(&mut self, additional: usize) {
+        self.data.reserve(additional);
+    }
+
+    /// Reserves space for exactly `additional` more elements.
+    ///
+    /// *O*(*n*) if it reallocates.
+    pub fn reserve_exact(&mut self, additional: usize) {
+        self.data.reserve_exact(additional);
+    }
+
+    /// Discards extra capacity.
+    ///
+    /// *O*(*n*) if it reallocates.
+    pub fn shrink_to_fit(&mut self) {
+        self.data.shrink_to_fit();
+    }
+
+    /// Drops all elements from the heap.
+    ///
+    /// *O*(*n*).
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// Returns an iterator over the elements of the heap in arbitrary
+    /// order.
+    ///
+    /// *O*(1) on creation, and *O*(1) for each `next()` operation.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter(self.data.iter())
+    }
+
+    /// Returns the elements, in heap order, as a vector in the heap’s
+    /// allocator.
+    ///
+    /// *O*(1).
+    pub fn into_vec(self) -> Vec<T, A> {
+        self.data
+    }
+
+    /// Returns a draining iterator over the elements in arbitrary order.
+    ///
+    /// *O*(1) on creation, and *O*(1) for each `next()` operation.
+    pub fn drain(&mut self) -> vec::Drain<'_, T, A> {
+        self.data.drain(..)
+    }
+
+    /// Returns a draining iterator over the elements in ascending
+    /// (min-first) order.
+    ///
+    /// *O*(1) on creation, and *O*(log *n*) for each `next()` operation.
+    pub fn drain_asc(&mut self) -> AllocDrainAsc<'_, T, A, C> {
+        AllocDrainAsc(self)
+    }
+
+    /// Returns a draining iterator over the elements in descending
+    /// (max-first) order.
+    ///
+    /// *O*(1) on creation, and *O*(log *n*) for each `next()` operation.
+    pub fn drain_desc(&mut self) -> AllocDrainDesc<'_, T, A, C> {
+        AllocDrainDesc(self)
+    }
+}
+
+impl<T, C> AllocMinMaxHeap<T, Global, C> {
+    /// Converts into a `MinMaxHeap`, without moving the elements.
+    ///
+    /// *O*(1).
+    pub fn into_heap(self) -> MinMaxHeap<T, C> {
+        MinMaxHeap(self.data, self.cmp)
+    }
+}
+
+impl<T, A: Allocator, C: Compare<T>> AllocMinMaxHeap<T, A, C> {
+    /// Creates a heap from the elements of `vec`, reusing its storage
+    /// and allocator, ordered by `cmp`.
+    ///
+    /// *O*(*n*).
+    pub fn from_vec_with_comparator(mut vec: Vec<T, A>, cmp: C) -> Self {
+        rebuild_slice(&mut vec, &cmp);
+        AllocMinMaxHeap { data: vec, cmp }
+    }
+
+    /// Adds an element to the heap.
+    ///
+    /// Amortized *O*(log *n*); worst-case *O*(*n*) when the backing
+    /// vector needs to grow.
+    pub fn push(&mut self, element: T) {
+        self.data.push(element);
+        push_slice(&mut self.data, &self.cmp);
+    }
+
+    /// Gets a reference to the minimum element, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_min(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    /// Returns a mutable reference to the minimum element, if any. Once
+    /// this reference is dropped, the heap is adjusted if necessary.
+    ///
+    /// *O*(1) for the peek; *O*(log *n*) when the reference is dropped.
+    pub fn peek_min_mut(&mut self) -> Option<AllocPeekMinMut<'_, T, A, C>> {
+        if self.data.is_empty() {
+            None
+        } else {
+            Some(AllocPeekMinMut { heap: self, sift: false })
+        }
+    }
+
+    /// Gets a reference to the maximum element, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_max(&self) -> Option<&T> {
+        find_max_slice(&self.data, &self.cmp).map(|max| &self.data[max])
+    }
+
+    /// Returns a mutable reference to the maximum element, if any. Once
+    /// this reference is dropped, the heap is adjusted if necessary.
+    ///
+    /// *O*(1) for the peek; *O*(log *n*) when the reference is dropped.
+    pub fn peek_max_mut(&mut self) -> Option<AllocPeekMaxMut<'_, T, A, C>> {
+        find_max_slice(&self.data, &self.cmp).map(move |max| AllocPeekMaxMut {
+            heap: self,
+            max_index: max,
+            sift: false,
+        })
+    }
+
+    /// Returns a borrowing iterator over the elements in ascending
+    /// (min-first) order.
+    ///
+    /// *O*(1) on creation, and *O*(log *n*) for each `next()` operation.
+    pub fn iter_asc(&self) -> IterAsc<'_, T, C> {
+        IterAsc(Frontier::new(&self.data, &self.cmp, true))
+    }
+
+    /// Returns a borrowing iterator over the elements in descending
+    /// (max-first) order.
+    ///
+    /// *O*(1) on creation, and *O*(log *n*) for each `next()` operation.
+    pub fn iter_desc(&self) -> IterDesc<'_, T, C> {
+        IterDesc(Frontier::new(&self.data, &self.cmp, false))
+    }
+
+    /// Removes the minimum element, if any.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_min(&mut self) -> Option<T> {
+        if self.data.is_empty() {
+            None
+        } else {
+            Some(self.remove(0))
+        }
+    }
+
+    /// Removes the maximum element, if any.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_max(&mut self) -> Option<T> {
+        let max = find_max_slice(&self.data, &self.cmp)?;
+        Some(self.remove(max))
+    }
+
+    /// Pushes an element, then pops the minimum element, without
+    /// growing the heap.
+    ///
+    /// See [`MinMaxHeap::push_pop_min`].
+    ///
+    /// *O*(log *n*).
+    ///
+    /// [`MinMaxHeap::push_pop_min`]: struct.MinMaxHeap.html#method.push_pop_min
+    pub fn push_pop_min(&mut self, element: T) -> T {
+        push_pop_min_slice(&mut self.data, element, &self.cmp)
+    }
+
+    /// Pushes an element, then pops the maximum element, without
+    /// growing the heap.
+    ///
+    /// See [`MinMaxHeap::push_pop_max`].
+    ///
+    /// *O*(log *n*).
+    ///
+    /// [`MinMaxHeap::push_pop_max`]: struct.MinMaxHeap.html#method.push_pop_max
+    pub fn push_pop_max(&mut self, element: T) -> T {
+        push_pop_max_slice(&mut self.data, element, &self.cmp)
+    }
+
+    /// Pops the minimum element and pushes a new element, without
+    /// growing the heap, returning the old minimum.
+    ///
+    /// See [`MinMaxHeap::replace_min`].
+    ///
+    /// *O*(log *n*).
+    ///
+    /// [`MinMaxHeap::replace_min`]: struct.MinMaxHeap.html#method.replace_min
+    pub fn replace_min(&mut self, element: T) -> Option<T> {
+        match replace_min_slice(&mut self.data, element, &self.cmp) {
+            Ok(min)      => Some(min),
+            Err(element) => { self.data.push(element); None }
+        }
+    }
+
+    /// Pops the maximum element and pushes a new element, without
+    /// growing the heap, returning the old maximum.
+    ///
+    /// See [`MinMaxHeap::replace_max`].
+    ///
+    /// *O*(log *n*).
+    ///
+    /// [`MinMaxHeap::replace_max`]: struct.MinMaxHeap.html#method.replace_max
+    pub fn replace_max(&mut self, element: T) -> Option<T> {
+        match replace_max_slice(&mut self.data, element, &self.cmp) {
+            Ok(max)      => Some(max),
+            Err(element) => { self.data.push(element); None }
+        }
+    }
+
+    /// Retains only the elements for which `f` returns `true`, visiting
+    /// them in arbitrary order.
+    ///
+    /// *O*(*n*).
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.data.retain(f);
+        rebuild_slice(&mut self.data, &self.cmp);
+    }
+
+    /// Returns an ascending (sorted) vector, reusing the heap’s storage.
+    ///
+    /// *O*(*n* log *n*).
+    pub fn into_vec_asc(self) -> Vec<T, A> {
+        let AllocMinMaxHeap { mut data, cmp } = self;
+        let n = data.len().saturating_sub(1);
+        MinMaxHeap::move_max_to_tail(&mut data, n, &cmp);
+        data
+    }
+
+    /// Returns a descending (sorted) vector, reusing the heap’s storage.
+    ///
+    /// *O*(*n* log *n*).
+    pub fn into_vec_desc(self) -> Vec<T, A> {
+        let AllocMinMaxHeap { mut data, cmp } = self;
+        let n = data.len().saturating_sub(1);
+        MinMaxHeap::move_min_to_tail(&mut data, n, &cmp);
+        data
+    }
+
+    /// Removes the element at `pos`, the minimum or the maximum.
+    fn remove(&mut self, pos: usize) -> T {
+        let result = self.data.swap_remove(pos);
+        trickle_down_extreme_slice(&mut self.data, pos, &self.cmp);
+        result
+    }
+}
+
+impl<T: Ord, A: Allocator> From<Vec<T, A>> for AllocMinMaxHeap<T, A> {
+    /// Heapifies the vector in place, in *O*(*n*) time, keeping its
+    /// allocator.
+    fn from(vec: Vec<T, A>) -> Self {
+        AllocMinMaxHeap::from_vec_with_comparator(vec, Natural)
+    }
+}
+
+impl<T, A: Allocator, C: Compare<T>> DoubleEndedHeap for AllocMinMaxHeap<T, A, C> {
+    type Item = T;
+
+    fn push(&mut self, element: T) { AllocMinMaxHeap::push(self, element) }
+    fn peek_min(&self) -> Option<&T> { AllocMinMaxHeap::peek_min(self) }
+    fn peek_max(&self) -> Option<&T> { AllocMinMaxHeap::peek_max(self) }
+    fn pop_min(&mut self) -> Option<T> { AllocMinMaxHeap::pop_min(self) }
+    fn pop_max(&mut self) -> Option<T> { AllocMinMaxHeap::pop_max(self) }
+    fn len(&self) -> usize { AllocMinMaxHeap::len(self) }
+}
+
+impl<T, A: Allocator, C: Compare<T>> Extend<T> for AllocMinMaxHeap<T, A, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for element in iter {
+            self.push(element);
+        }
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone, C: Clone> Clone for AllocMinMaxHeap<T, A, C> {
+    fn clone(&self) -> Self {
+        AllocMinMaxHeap {
+            data: self.data.clone(),
+            cmp:  self.cmp.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator, C> fmt::Debug for AllocMinMaxHeap<T, A, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AllocMinMaxHeap")
+         .field(&self.data.as_slice())
+         .finish()
+    }
+}
+
+impl<'a, T, A: Allocator, C> IntoIterator for &'a AllocMinMaxHeap<T, A, C> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter { self.iter() }
+}
+
+/// A mutable reference to the minimum element of an
+/// [`AllocMinMaxHeap`], which restores the heap when dropped.
+///
+/// This type is created with
+/// [`AllocMinMaxHeap::peek_min_mut`](struct.AllocMinMaxHeap.html#method.peek_min_mut).
+///
+/// [`AllocMinMaxHeap`]: struct.AllocMinMaxHeap.html
+pub struct AllocPeekMinMut<'a, T: 'a, A: 'a + Allocator, C: 'a + Compare<T> = Natural> {
+    heap: &'a mut AllocMinMaxHeap<T, A, C>,
+    sift: bool,
+}
+
+impl<T: fmt::Debug, A: Allocator, C: Compare<T>> fmt::Debug for AllocPeekMinMut<'_, T, A, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("AllocPeekMinMut")
+         .field(&**self)
+         .finish()
+    }
+}
+
+impl<'a, T, A: Allocator, C: Compare<T>> Drop for AllocPeekMinMut<'a, T, A, C> {
+    fn drop(&mut self) {
+        if self.sift {
+            trickle_down_extreme_slice(&mut self.heap.data, 0, &self.heap.cmp);
+        }
+    }
+}
+
+impl<'a, T, A: Allocator, C: Compare<T>> Deref for AllocPeekMinMut<'a, T, A, C> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.heap.data[0]
+    }
+}
+
+impl<'a, T, A: Allocator, C: Compare<T>> DerefMut for AllocPeekMinMut<'a, T, A, C> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.sift = true;
+        &mut self.heap.data[0]
+    }
+}
+
+impl<'a, T, A: Allocator, C: Compare<T>> AllocPeekMinMut<'a, T, A, C> {
+    /// Removes the peeked value from the heap and returns it.
+    pub fn pop(mut self) -> T {
+        // Sift is unnecessary since remove() already reorders heap
+        self.sift = false;
+        self.heap.remove(0)
+    }
+}
+
+/// A mutable reference to the maximum element of an
+/// [`AllocMinMaxHeap`], which restores the heap when dropped.
+///
+/// This type is created with
+/// [`AllocMinMaxHeap::peek_max_mut`](struct.AllocMinMaxHeap.html#method.peek_max_mut).
+///
+/// [`AllocMinMaxHeap`]: struct.AllocMinMaxHeap.html
+pub struct AllocPeekMaxMut<'a, T: 'a, A: 'a + Allocator, C: 'a + Compare<T> = Natural> {
+    heap: &'a mut AllocMinMaxHeap<T, A, C>,
+    max_index: usize,
+    sift: bool,
+}
+
+impl<T: fmt::Debug, A: Allocator, C: Compare<T>> fmt::Debug for AllocPeekMaxMut<'_, T, A, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("AllocPeekMaxMut")
+         .field(&**self)
+         .finish()
+    }
+}
+
+impl<'a, T, A: Allocator, C: Compare<T>> Drop for AllocPeekMaxMut<'a, T, A, C> {
+    fn drop(&mut self) {
+        if self.sift {
+            sift_max_slice(&mut self.heap.data, self.max_index, &self.heap.cmp);
+        }
+    }
+}
+
+impl<'a, T, A: Allocator, C: Compare<T>> Deref for AllocPeekMaxMut<'a, T, A, C> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.heap.data[self.max_index]
+    }
+}
+
+impl<'a, T, A: Allocator, C: Compare<T>> DerefMut for AllocPeekMaxMut<'a, T, A, C> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.sift = true;
+        &mut self.heap.data[self.max_index]
+    }
+}
+
+impl<'a, T, A: Allocator, C: Compare<T>> AllocPeekMaxMut<'a, T, A, C> {
+    /// Removes the peeked value from the heap and returns it.
+    pub fn pop(mut self) -> T {
+        // Sift is unnecessary since remove() already reorders heap
+        self.sift = false;
+        self.heap.remove(self.max_index)
+    }
+}
+
+/// A draining iterator over the elements of an [`AllocMinMaxHeap`] in
+/// ascending (min-first) order.
+///
+/// Each `next()` and `next_back()` is *O*(log *n*). This type is created
+/// with
+/// [`AllocMinMaxHeap::drain_asc`](struct.AllocMinMaxHeap.html#method.drain_asc).
+///
+/// [`AllocMinMaxHeap`]: struct.AllocMinMaxHeap.html
+pub struct AllocDrainAsc<'a, T: 'a, A: 'a + Allocator, C: 'a = Natural>(
+    &'a mut AllocMinMaxHeap<T, A, C>);
+
+/// A draining iterator over the elements of an [`AllocMinMaxHeap`] in
+/// descending (max-first) order.
+///
+/// Each `next()` and `next_back()` is *O*(log *n*). This type is created
+/// with
+/// [`AllocMinMaxHeap::drain_desc`](struct.AllocMinMaxHeap.html#method.drain_desc).
+///
+/// [`AllocMinMaxHeap`]: struct.AllocMinMaxHeap.html
+pub struct AllocDrainDesc<'a, T: 'a, A: 'a + Allocator, C: 'a = Natural>(
+    &'a mut AllocMinMaxHeap<T, A, C>);
+
+impl<'a, T: fmt::Debug, A: Allocator, C> fmt::Debug for AllocDrainAsc<'a, T, A, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AllocDrainAsc")
+         .field(&self.0.data.as_slice())
+         .finish()
+    }
+}
+
+impl<'a, T: fmt::Debug, A: Allocator, C> fmt::Debug for AllocDrainDesc<'a, T, A, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AllocDrainDesc")
+         .field(&self.0.data.as_slice())
+         .finish()
+    }
+}
+
+impl<'a, T, A: Allocator, C> Drop for AllocDrainAsc<'a, T, A, C> {
+    fn drop(&mut self) {
+        self.0.data.clear();
+    }
+}
+
+impl<'a, T, A: Allocator, C> Drop for AllocDrainDesc<'a, T, A, C> {
+    fn drop(&mut self) {
+        self.0.data.clear();
+    }
+}
+
+impl<'a, T, A: Allocator, C: Compare<T>> Iterator for AllocDrainAsc<'a, T, A, C> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.0.pop_min()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len(), Some(self.0.len()))
+    }
+}
+
+impl<'a, T, A: Allocator, C: Compare<T>> Iterator for AllocDrainDesc<'a, T, A, C> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.0.pop_max()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len(), Some(self.0.len()))
+    }
+}
+
+impl<'a, T, A: Allocator, C: Compare<T>> DoubleEndedIterator for AllocDrainAsc<'a, T, A, C> {
+    fn next_back(&mut self) -> Option<T> {
+        self.0.pop_max()
+    }
+}
+
+impl<'a, T, A: Allocator, C: Compare<T>> DoubleEndedIterator for AllocDrainDesc<'a, T, A, C> {
+    fn next_back(&mut self) -> Option<T> {
+        self.0.pop_min()
+    }
+}
+
+impl<'a, T, A: Allocator, C: Compare<T>> ExactSizeIterator for AllocDrainAsc<'a, T, A, C> { }
+impl<'a, T, A: Allocator, C: Compare<T>> FusedIterator for AllocDrainAsc<'a, T, A, C> { }
+impl<'a, T, A: Allocator, C: Compare<T>> ExactSizeIterator for AllocDrainDesc<'a, T, A, C> { }
+impl<'a, T, A: Allocator, C: Compare<T>> FusedIterator for AllocDrainDesc<'a, T, A, C> { }
+
+// The counting allocator needs an `unsafe impl`.
+#[cfg(all(test, not(feature = "safe")))]
+mod test {
+    use super::*;
+    use super::super::test_model;
+    use std::alloc::{AllocError, Layout, System};
+    use std::cell::Cell;
+    use std::ptr::NonNull;
+
+    /// Counts the allocations it passes on to the system allocator.
+    struct Counting<'a>(&'a Cell<usize>);
+
+    unsafe impl Allocator for Counting<'_> {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            self.0.set(self.0.get() + 1);
+            System.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            System.deallocate(ptr, layout)
+        }
+    }
+
+    #[test]
+    fn random() {
+        let count = Cell::new(0);
+        let mut heap = AllocMinMaxHeap::with_capacity_in(64, Counting(&count));
+        assert_eq!(1, count.get());
+
+        let model = test_model::random(&mut heap, 3000, 64, |heap| {
+            MinMaxHeap(heap.data.to_vec(), Natural).assert_valid();
+        });
+
+        assert_eq!(1, count.get());
+        assert_eq!(model.into_vec_desc(), heap.into_vec_desc().to_vec());
+    }
+
+    #[test]
+    fn peek_mut_and_replace() {
+        let count = Cell::new(0);
+        let mut vec = Vec::with_capacity_in(16, Counting(&count));
+        vec.extend(0 .. 10);
+        let mut heap = AllocMinMaxHeap::from(vec);
+        assert_eq!(1, count.get());
+
+        *heap.peek_min_mut().unwrap() = 20;
+        *heap.peek_max_mut().unwrap() = -1;
+        assert_eq!(Some(&-1), heap.peek_min());
+        assert_eq!(Some(&9), heap.peek_max());
+        assert_eq!(Some(-1), heap.peek_min_mut().map(AllocPeekMinMut::pop));
+        assert_eq!(Some(9), heap.peek_max_mut().map(AllocPeekMaxMut::pop));
+
+        assert_eq!(Some(1), heap.replace_min(30));
+        assert_eq!(Some(30), heap.replace_max(0));
+        assert_eq!(-5, heap.push_pop_min(-5));
+        assert_eq!(0, heap.push_pop_min(5));
+        assert_eq!(15, heap.push_pop_max(15));
+        assert_eq!(8, heap.push_pop_max(4));
+        heap.retain(|&x| x != 5);
+        MinMaxHeap(heap.data.to_vec(), Natural).assert_valid();
+
+        assert_eq!(vec![2, 3, 4, 4, 6, 7], heap.iter_asc().copied().collect::<Vec<_>>());
+        assert_eq!(vec![7, 6, 4, 4, 3, 2], heap.iter_desc().copied().collect::<Vec<_>>());
+        assert_eq!(1, count.get());
+    }
+
+    #[test]
+    fn drain_sorted() {
+        let mut heap = AllocMinMaxHeap::new_in(System);
+        heap.extend((0 .. 20).map(|i| (i * 7) % 20));
+
+        let mut drain = heap.drain_asc();
+        assert_eq!((20, Some(20)), drain.size_hint());
+        assert_eq!(Some(0), drain.next());
+        assert_eq!(Some(19), drain.next_back());
+        assert_eq!(vec![1, 2, 3], drain.by_ref().take(3).collect::<Vec<_>>());
+        drop(drain);
+        assert!(heap.is_empty());
+
+        heap.extend(0 .. 5);
+        assert_eq!(vec![4, 3, 2, 1, 0], heap.drain_desc().collect::<Vec<_>>());
+
+        heap.extend(0 .. 5);
+        let mut all: Vec<_> = heap.drain().collect();
+        all.sort_unstable();
+        assert_eq!(vec![0, 1, 2, 3, 4], all);
+
+        heap.extend(vec![3, 1, 4, 1, 5, 9, 2, 6]);
+        assert_eq!(vec![1, 1, 2, 3, 4, 5, 6, 9], heap.clone().into_vec_asc().to_vec());
+        assert_eq!(vec![9, 6, 5, 4, 3, 2, 1, 1], heap.into_vec_desc().to_vec());
+    }
+
+    #[test]
+    fn into_heap() {
+        let mut heap = AllocMinMaxHeap::new_in(Global);
+        heap.extend((0 .. 10).map(|i| (i * 7) % 10));
+
+        let heap = heap.into_heap();
+        heap.assert_valid();
+        assert_eq!((0 .. 10).collect::<Vec<_>>(), heap.into_vec_asc());
+    }
+}
@@ -0,0 +1,53 @@
+use alloc::vec::Vec;
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use super::{Compare, MinMaxHeap};
+
+// An arbitrary heap is an arbitrary `Vec`, heapified, so fuzzers can
+// only ever build valid heaps.
+
+impl<'a, T, C> Arbitrary<'a> for MinMaxHeap<T, C>
+    where T: Arbitrary<'a>,
+          C: Compare<T> + Default
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let vec = Vec::arbitrary(u)?;
+        Ok(MinMaxHeap::from_vec_with_comparator(vec, C::default()))
+    }
+
+    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
+        let vec = Vec::arbitrary_take_rest(u)?;
+        Ok(MinMaxHeap::from_vec_with_comparator(vec, C::default()))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        Vec::<T>::size_hint(depth)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate rand;
+
+    use super::*;
+    use self::rand::Rng;
+
+    #[test]
+    fn always_valid() {
+        let mut rng = rand::thread_rng();
+
+        for len in 0 .. 200 {
+            let bytes: Vec<u8> = (0 .. len).map(|_| rng.gen()).collect();
+
+            let heap = MinMaxHeap::<u8>::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+            heap.assert_valid();
+
+            let heap = MinMaxHeap::<u16>::arbitrary_take_rest(Unstructured::new(&bytes)).unwrap();
+            heap.assert_valid();
+            let mut vec = Vec::<u16>::arbitrary_take_rest(Unstructured::new(&bytes)).unwrap();
+            vec.sort();
+            assert_eq!(vec, heap.into_vec_asc());
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use core::mem::{self, MaybeUninit};
+use core::{fmt, ptr, slice};
+
+use super::{Iter, Natural, find_max_slice};
+use super::hole::Hole;
+
+/// A min-max-heap of at most `N` elements, stored inline without
+/// allocating.
+///
+/// The elements live in an array of `N` possibly-uninitialized slots, of
+/// which the first [`len`] are initialized and in heap order. Pushing
+/// onto a full heap hands the element back instead of growing.
+///
+/// # Examples
+///
+/// ```
+/// use min_max_heap::ArrayMinMaxHeap;
+///
+/// let mut heap = ArrayMinMaxHeap::<u32, 3>::new();
+/// assert_eq!(heap.try_push(7), Ok(()));
+/// assert_eq!(heap.try_push(2), Ok(()));
+/// assert_eq!(heap.try_push(5), Ok(()));
+/// assert_eq!(heap.try_push(9), Err(9));
+///
+/// assert_eq!(heap.pop_min(), Some(2));
+/// assert_eq!(heap.pop_max(), Some(7));
+/// ```
+///
+/// [`len`]: struct.ArrayMinMaxHeap.html#method.len
+pub struct ArrayMinMaxHeap<T, const N: usize> {
+    data: [MaybeUninit<T>; N],
+    len:  usize,
+}
+
+impl<T, const N: usize> Default for ArrayMinMaxHeap<T, N> {
+    fn default() -> Self {
+        ArrayMinMaxHeap::new()
+    }
+}
+
+impl<T, const N: usize> ArrayMinMaxHeap<T, N> {
+    /// Creates a new, empty `ArrayMinMaxHeap`.
+    ///
+    /// *O*(1).
+    pub fn new() -> Self {
+        ArrayMinMaxHeap {
+            // SAFETY: an array of `MaybeUninit` needs no initialization
+            data: unsafe { MaybeUninit::uninit().assume_init() },
+            len:  0,
+        }
+    }
+
+    /// The number of elements the heap can hold, `N`.
+    ///
+    /// *O*(1).
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// The number of elements in the heap.
+    ///
+    /// *O*(1).
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Is the heap empty?
+    ///
+    /// *O*(1).
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Does the heap hold `N` elements?
+    ///
+    /// *O*(1).
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Drops all elements from the heap.
+    ///
+    /// *O*(*n*).
+    pub fn clear(&mut self) {
+        let elements: *mut [T] = self.as_mut_slice();
+        // Forget the elements before dropping them, in case a drop panics.
+        self.len = 0;
+        // SAFETY: the elements were initialized, and are no longer counted
+        unsafe {
+            ptr::drop_in_place(elements);
+        }
+    }
+
+    /// Returns an iterator over the elements of the heap in arbitrary
+    /// order.
+    ///
+    /// *O*(1) on creation, and *O*(1) for each `next()` operation.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter(self.as_slice().iter())
+    }
+
+    /// The elements of the heap, in heap order.
+    ///
+    /// *O*(1).
+    pub fn as_slice(&self) -> &[T] {
+        // SAFETY: the first `self.len` slots are initialized
+        unsafe { slice::from_raw_parts(self.data.as_ptr() as *const T, self.len) }
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        // SAFETY: the first `self.len` slots are initialized
+        unsafe { slice::from_raw_parts_mut(self.data.as_mut_ptr() as *mut T, self.len) }
+    }
+}
+
+impl<T: Ord, const N: usize> ArrayMinMaxHeap<T, N> {
+    /// Adds an element to the heap, or returns it if the heap is full.
+    ///
+    /// *O*(log *n*).
+    pub fn try_push(&mut self, element: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(element);
+        }
+
+        let pos = self.len;
+        self.data[pos] = MaybeUninit::new(element);
+        self.len += 1;
+
+        // SAFETY: `pos` is a valid index in the slice
+        unchecked! {
+            Hole::new(self.as_mut_slice(), pos).bubble_up(&Natural);
+        }
+
+        Ok(())
+    }
+
+    /// Gets a reference to the minimum element, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_min(&self) -> Option<&T> {
+        self.as_slice().first()
+    }
+
+    /// Gets a reference to the maximum element, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_max(&self) -> Option<&T> {
+        let slice = self.as_slice();
+        find_max_slice(slice, &Natural).map(|max| &slice[max])
+    }
+
+    /// Removes the minimum element, if any.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_min(&mut self) -> Option<T> {
+        let mut item = self.pop_last()?;
+
+        if let Some(min) = self.as_mut_slice().first_mut() {
+            mem::swap(&mut item, min);
+            // SAFETY: the slice is not empty
+            unchecked! {
+                Hole::new(self.as_mut_slice(), 0).trickle_down_min(&Natural);
+            }
+        }
+
+        Some(item)
+    }
+
+    /// Removes the maximum element, if any.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_max(&mut self) -> Option<T> {
+        let max = find_max_slice(self.as_slice(), &Natural)?;
+        let mut item = self.pop_last().unwrap();
+
+        if let Some(max_element) = self.as_mut_slice().get_mut(max) {
+            mem::swap(&mut item, max_element);
+            // SAFETY: `max` is a valid index in the slice
+            unchecked! {
+                Hole::new(self.as_mut_slice(), max).trickle_down_max(&Natural);
+            }
+        }
+
+        Some(item)
+    }
+
+    fn pop_last(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        // SAFETY: the slot was initialized, and is no longer counted
+        Some(unsafe { self.data[self.len].as_ptr().read() })
+    }
+}
+
+impl<T, const N: usize> Drop for ArrayMinMaxHeap<T, N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for ArrayMinMaxHeap<T, N> {
+    fn clone(&self) -> Self {
+        let mut result = ArrayMinMaxHeap::new();
+        for element in self.as_slice() {
+            // Already in heap order, so no sifting is needed.
+            result.data[result.len] = MaybeUninit::new(element.clone());
+            result.len += 1;
+        }
+        result
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for ArrayMinMaxHeap<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ArrayMinMaxHeap")
+         .field(&self.as_slice())
+         .finish()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a ArrayMinMaxHeap<T, N> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter { self.iter() }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use std::rc::Rc;
+
+    use super::*;
+    use super::super::MinMaxHeap;
+    use super::super::test_model::{self, ModelHeap};
+
+    impl<const N: usize> ModelHeap for ArrayMinMaxHeap<u32, N> {
+        fn push(&mut self, element: u32) { assert_eq!(Ok(()), self.try_push(element)) }
+        fn peek_min(&self) -> Option<u32> { ArrayMinMaxHeap::peek_min(self).copied() }
+        fn peek_max(&self) -> Option<u32> { ArrayMinMaxHeap::peek_max(self).copied() }
+        fn pop_min(&mut self) -> Option<u32> { ArrayMinMaxHeap::pop_min(self) }
+        fn pop_max(&mut self) -> Option<u32> { ArrayMinMaxHeap::pop_max(self) }
+        fn len(&self) -> usize { ArrayMinMaxHeap::len(self) }
+    }
+
+    #[test]
+    fn random() {
+        let mut heap = ArrayMinMaxHeap::<u32, 16>::new();
+        test_model::random(&mut heap, 2000, 16, |heap| {
+            MinMaxHeap(heap.as_slice().to_vec(), Natural).assert_valid();
+        });
+
+        while !heap.is_full() {
+            heap.try_push(0).unwrap();
+        }
+        assert_eq!(Err(7), heap.try_push(7));
+    }
+
+    #[test]
+    fn zero_capacity() {
+        let mut heap = ArrayMinMaxHeap::<u8, 0>::new();
+        assert!(heap.is_full());
+        assert_eq!(Err(1), heap.try_push(1));
+        assert_eq!(None, heap.pop_max());
+    }
+
+    #[test]
+    fn drops_elements() {
+        let counter = Rc::new(());
+        let mut heap = ArrayMinMaxHeap::<_, 4>::new();
+        for _ in 0 .. 4 {
+            heap.try_push(Rc::clone(&counter)).unwrap();
+        }
+
+        let copy = heap.clone();
+        assert_eq!(9, Rc::strong_count(&counter));
+        drop(heap);
+        assert_eq!(5, Rc::strong_count(&counter));
+        drop(copy);
+        assert_eq!(1, Rc::strong_count(&counter));
+    }
+}
@@ -0,0 +1,71 @@
+use alloc::vec::Vec;
+
+use bincode::de::{BorrowDecoder, Decoder};
+use bincode::enc::Encoder;
+use bincode::error::{DecodeError, EncodeError};
+use bincode::{BorrowDecode, Decode, Encode};
+
+use super::{Compare, MinMaxHeap};
+
+// A heap encodes exactly as the `Vec` of its elements in heap order, and
+// decoding rebuilds it, as with serde, rather than trusting that order.
+
+impl<T: Encode, C> Encode for MinMaxHeap<T, C> {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        self.0.encode(encoder)
+    }
+}
+
+impl<Context, T, C> Decode<Context> for MinMaxHeap<T, C>
+    where T: Decode<Context>,
+          C: Compare<T> + Default
+{
+    fn decode<D: Decoder<Context = Context>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        let vec = Vec::decode(decoder)?;
+        Ok(MinMaxHeap::from_vec_with_comparator(vec, C::default()))
+    }
+}
+
+impl<'de, Context, T, C> BorrowDecode<'de, Context> for MinMaxHeap<T, C>
+    where T: BorrowDecode<'de, Context>,
+          C: Compare<T> + Default
+{
+    fn borrow_decode<D>(decoder: &mut D) -> Result<Self, DecodeError>
+            where D: BorrowDecoder<'de, Context = Context> {
+        let vec = Vec::borrow_decode(decoder)?;
+        Ok(MinMaxHeap::from_vec_with_comparator(vec, C::default()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bincode::config;
+
+    #[test]
+    fn round_trip() {
+        let heap: MinMaxHeap<u32> = (0 .. 100).map(|i| i * 37 % 101).collect();
+        let bytes = bincode::encode_to_vec(&heap, config::standard()).unwrap();
+        assert_eq!(bincode::encode_to_vec(heap.clone().into_vec(), config::standard()).unwrap(),
+                   bytes);
+
+        let (back, read): (MinMaxHeap<u32>, _) =
+            bincode::decode_from_slice(&bytes, config::standard()).unwrap();
+        assert_eq!(bytes.len(), read);
+        assert_eq!(heap.into_vec(), back.into_vec());
+    }
+
+    #[test]
+    fn rebuilds() {
+        let bytes = bincode::encode_to_vec(vec![3u32, 5, 9, 1, 2], config::standard()).unwrap();
+        let (heap, _): (MinMaxHeap<u32>, _) =
+            bincode::decode_from_slice(&bytes, config::standard()).unwrap();
+        heap.assert_valid();
+        assert_eq!(vec![1, 2, 3, 5, 9], heap.into_vec_asc());
+
+        let bytes = bincode::encode_to_vec(vec!["b", "c", "a"], config::standard()).unwrap();
+        let (heap, _): (MinMaxHeap<&str>, _) =
+            bincode::borrow_decode_from_slice(&bytes, config::standard()).unwrap();
+        assert_eq!(Some(&"a"), heap.peek_min());
+    }
+}
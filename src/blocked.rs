@@ -0,0 +1,490 @@
+use alloc::vec::Vec;
+use core::iter::FromIterator;
+use core::mem;
+
+use super::{DoubleEndedHeap, Iter};
+
+/// The number of bytes a block is sized to fit, a common page size.
+const BLOCK_BYTES: usize = 4096;
+
+/// A min-max-heap laid out for locality on very large heaps.
+///
+/// A `MinMaxHeap` stores its tree level by level, so walking from the
+/// root to a leaf touches a different page at almost every level once the
+/// heap is large. `BlockedMinMaxHeap` instead cuts the tree into complete
+/// subtrees of *h* levels, called blocks, and stores each block
+/// contiguously, like Kamp’s B-heap. The heap grows one block at a time,
+/// so the storage is still a single vector without gaps. A walk from the
+/// root to a leaf then touches only one block per *h* levels.
+///
+/// By default, *h* is chosen so that a block fills a 4 KiB page; it can
+/// be set with [`with_block_height`]. The methods are those of
+/// [`MinMaxHeap`], with the same costs.
+///
+/// # Examples
+///
+/// ```
+/// use min_max_heap::BlockedMinMaxHeap;
+///
+/// let mut heap: BlockedMinMaxHeap<_> = (0 .. 10_000).collect();
+///
+/// assert_eq!(heap.pop_min(), Some(0));
+/// assert_eq!(heap.pop_max(), Some(9_999));
+/// assert_eq!(heap.len(), 9_998);
+/// ```
+///
+/// [`MinMaxHeap`]: struct.MinMaxHeap.html
+/// [`with_block_height`]: struct.BlockedMinMaxHeap.html#method.with_block_height
+#[derive(Clone, Debug)]
+pub struct BlockedMinMaxHeap<T> {
+    data:   Vec<T>,
+    height: u32,
+}
+
+impl<T> Default for BlockedMinMaxHeap<T> {
+    fn default() -> Self {
+        BlockedMinMaxHeap::new()
+    }
+}
+
+impl<T> BlockedMinMaxHeap<T> {
+    /// Creates a new, empty `BlockedMinMaxHeap` whose blocks fit in a
+    /// page.
+    ///
+    /// *O*(1).
+    pub fn new() -> Self {
+        // The largest `h` with `2^h - 1` elements in `BLOCK_BYTES`.
+        let per_block = BLOCK_BYTES / mem::size_of::<T>().max(1);
+        let height = (per_block + 1).next_power_of_two().trailing_zeros();
+        let height = if (per_block + 1).is_power_of_two() { height } else { height - 1 };
+        BlockedMinMaxHeap::with_block_height((height as usize).clamp(1, 16))
+    }
+
+    /// Creates a new, empty `BlockedMinMaxHeap` whose blocks are
+    /// subtrees of `height` levels, holding `2^height - 1` elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `height` is between 1 and 16.
+    ///
+    /// *O*(1).
+    pub fn with_block_height(height: usize) -> Self {
+        assert!((1 ..= 16).contains(&height),
+                "BlockedMinMaxHeap: block height must be between 1 and 16");
+        BlockedMinMaxHeap {
+            data:   Vec::new(),
+            height: height as u32,
+        }
+    }
+
+    /// The height of the heap’s blocks, in levels.
+    ///
+    /// *O*(1).
+    pub fn block_height(&self) -> usize {
+        self.height as usize
+    }
+
+    /// The number of elements in the heap.
+    ///
+    /// *O*(1).
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Is the heap empty?
+    ///
+    /// *O*(1).
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Drops all elements from the heap.
+    ///
+    /// *O*(*n*).
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// Returns an iterator over the elements of the heap in arbitrary
+    /// order.
+    ///
+    /// *O*(1) on creation, and *O*(1) for each `next()` operation.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter(self.data.iter())
+    }
+
+    /// Returns a vector of the elements in arbitrary order.
+    ///
+    /// *O*(1).
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+
+    // Node `pos` is at `pos % block_len` in block `pos / block_len`,
+    // where each block is numbered in level order. The leaves of a block
+    // are at `leaf_start ..`, and the `2^h` children of the blocks are
+    // numbered `block * 2^h + 1 ..`, two per leaf, in level order.
+
+    fn block_len(&self) -> usize {
+        (1 << self.height) - 1
+    }
+
+    fn leaf_start(&self) -> usize {
+        (1 << (self.height - 1)) - 1
+    }
+
+    fn parent(&self, pos: usize) -> usize {
+        let (block, offset) = (pos / self.block_len(), pos % self.block_len());
+        if offset > 0 {
+            block * self.block_len() + (offset - 1) / 2
+        } else {
+            let parent_block = (block - 1) >> self.height;
+            let leaf = ((block - 1) & ((1 << self.height) - 1)) / 2;
+            parent_block * self.block_len() + self.leaf_start() + leaf
+        }
+    }
+
+    fn children(&self, pos: usize) -> [usize; 2] {
+        let (block, offset) = (pos / self.block_len(), pos % self.block_len());
+        if offset < self.leaf_start() {
+            let first = block * self.block_len() + 2 * offset + 1;
+            [first, first + 1]
+        } else {
+            // These saturate rather than overflow, which is out of any
+            // heap’s bounds all the same.
+            let leaf = offset - self.leaf_start();
+            let first_block = block.saturating_mul(1 << self.height).saturating_add(1 + 2 * leaf);
+            [first_block.saturating_mul(self.block_len()),
+             first_block.saturating_add(1).saturating_mul(self.block_len())]
+        }
+    }
+
+    // The blocks at depth `d` of the tree of blocks start at block
+    // `(2^(h d) - 1) / (2^h - 1)`, so block `b` is at depth
+    // `floor(log2(b (2^h - 1) + 1) / h)`, and a node is `h` levels deeper
+    // for each block above its own.
+    fn is_min_level(&self, pos: usize) -> bool {
+        let (block, offset) = (pos / self.block_len(), pos % self.block_len());
+        let block_depth = log2(block * self.block_len() + 1) / self.height;
+        (block_depth * self.height + log2(offset + 1)) & 1 == 0
+    }
+}
+
+impl<T: Ord> BlockedMinMaxHeap<T> {
+    /// Adds an element to the heap.
+    ///
+    /// *O*(log *n*).
+    pub fn push(&mut self, element: T) {
+        let pos = self.data.len();
+        self.data.push(element);
+        self.bubble_up(pos, self.is_min_level(pos));
+    }
+
+    /// Gets a reference to the minimum element, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_min(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    /// Gets a reference to the maximum element, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_max(&self) -> Option<&T> {
+        self.find_max().map(|max| &self.data[max])
+    }
+
+    /// Removes the minimum element, if any.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_min(&mut self) -> Option<T> {
+        self.remove(0)
+    }
+
+    /// Removes the maximum element, if any.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_max(&mut self) -> Option<T> {
+        let max = self.find_max()?;
+        self.remove(max)
+    }
+
+    /// Returns an ascending (sorted) vector.
+    ///
+    /// *O*(*n* log *n*).
+    pub fn into_vec_asc(mut self) -> Vec<T> {
+        let mut result = Vec::with_capacity(self.len());
+        while let Some(element) = self.pop_min() {
+            result.push(element);
+        }
+        result
+    }
+
+    /// Returns a descending (sorted) vector.
+    ///
+    /// *O*(*n* log *n*).
+    pub fn into_vec_desc(mut self) -> Vec<T> {
+        let mut result = Vec::with_capacity(self.len());
+        while let Some(element) = self.pop_max() {
+            result.push(element);
+        }
+        result
+    }
+
+    fn find_max(&self) -> Option<usize> {
+        match self.data.len() {
+            0 => None,
+            1 => Some(0),
+            len => {
+                let [first, second] = self.children(0);
+                if second < len && self.data[second] > self.data[first] {
+                    Some(second)
+                } else {
+                    Some(first)
+                }
+            }
+        }
+    }
+
+    /// Removes the element at `pos`, which must be the minimum or the
+    /// maximum, and restores the heap.
+    fn remove(&mut self, pos: usize) -> Option<T> {
+        if pos >= self.data.len() {
+            return None;
+        }
+
+        // The last element is always a leaf, since every node comes
+        // before its children.
+        let result = self.data.swap_remove(pos);
+        if pos < self.data.len() {
+            // Only the root is on a min level.
+            self.trickle_down(pos, pos == 0);
+        }
+        Some(result)
+    }
+
+    /// Arranges the elements into a heap, bottom-up.
+    ///
+    /// *O*(*n*).
+    fn rebuild(&mut self) {
+        // Every node comes before its children, so going backward, the
+        // subtrees below each node are heaps by the time it trickles
+        // down into them.
+        for pos in (0 .. self.data.len()).rev() {
+            let min = self.is_min_level(pos);
+            self.trickle_down(pos, min);
+        }
+    }
+
+    /// Does the element at `i` belong closer to the root than the one at
+    /// `j`, on min levels if `min` and on max levels otherwise?
+    fn before(&self, i: usize, j: usize, min: bool) -> bool {
+        if min { self.data[i] < self.data[j] } else { self.data[i] > self.data[j] }
+    }
+
+    /// Moves the element at `pos`, which is on a min level if `min`,
+    /// toward the root.
+    fn bubble_up(&mut self, pos: usize, min: bool) {
+        if pos == 0 {
+            return;
+        }
+
+        let parent = self.parent(pos);
+
+        if self.before(parent, pos, min) {
+            self.data.swap(pos, parent);
+            self.bubble_up_by(parent, !min);
+        } else {
+            self.bubble_up_by(pos, min);
+        }
+    }
+
+    fn bubble_up_by(&mut self, mut pos: usize, min: bool) {
+        while pos > 2 {
+            let grandparent = self.parent(self.parent(pos));
+            if !self.before(pos, grandparent, min) {
+                break;
+            }
+            self.data.swap(pos, grandparent);
+            pos = grandparent;
+        }
+    }
+
+    fn trickle_down(&mut self, mut pos: usize, min: bool) {
+        let len = self.data.len();
+
+        loop {
+            let children = self.children(pos);
+            if children[0] >= len {
+                return;
+            }
+
+            // The extreme of the children and grandchildren, and whether
+            // it is a grandchild.
+            let mut best = children[0];
+            let mut grandchild = false;
+            for &child in children.iter().filter(|&&child| child < len) {
+                if self.before(child, best, min) {
+                    best = child;
+                    grandchild = false;
+                }
+                for &i in self.children(child).iter().filter(|&&i| i < len) {
+                    if self.before(i, best, min) {
+                        best = i;
+                        grandchild = true;
+                    }
+                }
+            }
+
+            if !self.before(best, pos, min) {
+                return;
+            }
+            self.data.swap(pos, best);
+
+            if !grandchild {
+                return;
+            }
+
+            let parent = self.parent(best);
+            if self.before(parent, best, min) {
+                self.data.swap(parent, best);
+            }
+            pos = best;
+        }
+    }
+}
+
+impl<T: Ord> DoubleEndedHeap for BlockedMinMaxHeap<T> {
+    type Item = T;
+
+    fn push(&mut self, element: T) { BlockedMinMaxHeap::push(self, element) }
+    fn peek_min(&self) -> Option<&T> { BlockedMinMaxHeap::peek_min(self) }
+    fn peek_max(&self) -> Option<&T> { BlockedMinMaxHeap::peek_max(self) }
+    fn pop_min(&mut self) -> Option<T> { BlockedMinMaxHeap::pop_min(self) }
+    fn pop_max(&mut self) -> Option<T> { BlockedMinMaxHeap::pop_max(self) }
+    fn len(&self) -> usize { BlockedMinMaxHeap::len(self) }
+}
+
+impl<T: Ord> Extend<T> for BlockedMinMaxHeap<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for element in iter {
+            self.push(element);
+        }
+    }
+}
+
+impl<T: Ord> FromIterator<T> for BlockedMinMaxHeap<T> {
+    /// Collects the elements into a vector and then heapifies them all
+    /// at once, in *O*(*n*) time.
+    fn from_iter<I>(iter: I) -> Self
+            where I: IntoIterator<Item = T> {
+        BlockedMinMaxHeap::from(iter.into_iter().collect::<Vec<T>>())
+    }
+}
+
+impl<T: Ord> From<Vec<T>> for BlockedMinMaxHeap<T> {
+    /// Heapifies the vector in place, in *O*(*n*) time, with blocks that
+    /// fit in a page.
+    fn from(vec: Vec<T>) -> Self {
+        let mut heap = BlockedMinMaxHeap { data: vec, ..BlockedMinMaxHeap::new() };
+        heap.rebuild();
+        heap
+    }
+}
+
+impl<'a, T> IntoIterator for &'a BlockedMinMaxHeap<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter { self.iter() }
+}
+
+/// The floor of the base-2 logarithm of `n`, which must be positive.
+fn log2(n: usize) -> u32 {
+    usize::BITS - 1 - n.leading_zeros()
+}
+
+#[cfg(test)]
+mod test {
+    extern crate rand;
+
+    use super::*;
+    use super::super::test_model;
+    use self::rand::Rng;
+
+    fn check<T: Ord>(heap: &BlockedMinMaxHeap<T>) {
+        for pos in 1 .. heap.len() {
+            let mut ancestor = pos;
+            while ancestor > 0 {
+                ancestor = heap.parent(ancestor);
+                if heap.is_min_level(ancestor) {
+                    assert!(heap.data[ancestor] <= heap.data[pos]);
+                } else {
+                    assert!(heap.data[ancestor] >= heap.data[pos]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn layout() {
+        for height in 1 .. 6 {
+            let heap = BlockedMinMaxHeap::<u8>::with_block_height(height);
+            for pos in 0 .. 5000 {
+                for &child in &heap.children(pos) {
+                    assert!(child > pos);
+                    assert_eq!(pos, heap.parent(child));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn min_levels() {
+        for height in 1 .. 6 {
+            let heap = BlockedMinMaxHeap::<u8>::with_block_height(height);
+            let mut min_level = vec![true];
+            for pos in 1 .. 5000 {
+                min_level.push(!min_level[heap.parent(pos)]);
+                assert_eq!(min_level[pos], heap.is_min_level(pos), "height {}, pos {}", height, pos);
+            }
+        }
+    }
+
+    #[test]
+    fn from_vec() {
+        let mut rng = rand::thread_rng();
+
+        for &len in &[0, 1, 2, 3, 100, 5000] {
+            let vec: Vec<u32> = (0 .. len).map(|_| rng.gen_range(0 .. 1000)).collect();
+            let heap = BlockedMinMaxHeap::from(vec.clone());
+            check(&heap);
+
+            let mut expected = vec.clone();
+            expected.sort();
+            assert_eq!(expected, heap.into_vec_asc());
+
+            for height in 1 .. 5 {
+                let mut heap = BlockedMinMaxHeap::with_block_height(height);
+                heap.data = vec.clone();
+                heap.rebuild();
+                check(&heap);
+            }
+        }
+    }
+
+    #[test]
+    fn default_height() {
+        assert_eq!(10, BlockedMinMaxHeap::<u32>::new().block_height());
+        assert_eq!(9, BlockedMinMaxHeap::<u64>::new().block_height());
+        assert_eq!(1, BlockedMinMaxHeap::<[u8; 5000]>::new().block_height());
+    }
+
+    #[test]
+    fn random() {
+        for height in 1 .. 5 {
+            let mut heap = BlockedMinMaxHeap::with_block_height(height);
+            let model = test_model::random(&mut heap, 2000, usize::MAX, check);
+            assert_eq!(model.into_vec_asc(), heap.into_vec_asc());
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use alloc::vec::Vec;
+
+use borsh::io::{Read, Result, Write};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use super::{Compare, MinMaxHeap};
+
+// A heap encodes exactly as the `Vec` of its elements in heap order, and
+// decoding rebuilds it, as with serde, rather than trusting that order.
+
+impl<T: BorshSerialize, C> BorshSerialize for MinMaxHeap<T, C> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.0.serialize(writer)
+    }
+}
+
+impl<T, C> BorshDeserialize for MinMaxHeap<T, C>
+    where T: BorshDeserialize,
+          C: Compare<T> + Default
+{
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let vec = Vec::deserialize_reader(reader)?;
+        Ok(MinMaxHeap::from_vec_with_comparator(vec, C::default()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let heap: MinMaxHeap<u32> = (0 .. 100).map(|i| i * 37 % 101).collect();
+        let bytes = borsh::to_vec(&heap).unwrap();
+        assert_eq!(borsh::to_vec(&heap.clone().into_vec()).unwrap(), bytes);
+
+        let back: MinMaxHeap<u32> = borsh::from_slice(&bytes).unwrap();
+        assert_eq!(heap.into_vec(), back.into_vec());
+    }
+
+    #[test]
+    fn rebuilds() {
+        let bytes = borsh::to_vec(&vec![3u32, 5, 9, 1, 2]).unwrap();
+        let heap: MinMaxHeap<u32> = borsh::from_slice(&bytes).unwrap();
+        heap.assert_valid();
+        assert_eq!(vec![1, 2, 3, 5, 9], heap.into_vec_asc());
+    }
+}
@@ -0,0 +1,270 @@
+use alloc::vec::Vec;
+
+use super::{Compare, Iter, MinMaxHeap, Natural};
+
+/// What a [`BoundedMinMaxHeap`] does when an element is pushed while it
+/// is full.
+///
+/// [`BoundedMinMaxHeap`]: struct.BoundedMinMaxHeap.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Eviction {
+    /// Evict the maximum element, which may be the one being pushed. The
+    /// heap retains the smallest elements it has seen.
+    EvictMax,
+    /// Evict the minimum element, which may be the one being pushed. The
+    /// heap retains the largest elements it has seen.
+    EvictMin,
+    /// Reject the element being pushed, leaving the heap unchanged. The
+    /// heap retains the first elements it was given.
+    Reject,
+}
+
+/// A min-max-heap with a maximum length, and a policy for what to do
+/// when an element is pushed beyond it.
+///
+/// # Examples
+///
+/// ```
+/// use min_max_heap::{BoundedMinMaxHeap, Eviction};
+///
+/// let mut heap = BoundedMinMaxHeap::new(3, Eviction::EvictMin);
+///
+/// assert_eq!(heap.try_push(4), None);
+/// assert_eq!(heap.try_push(8), None);
+/// assert_eq!(heap.try_push(6), None);
+/// assert_eq!(heap.try_push(7), Some(4));
+/// assert_eq!(heap.try_push(1), Some(1));
+///
+/// assert_eq!(heap.into_vec_asc(), [6, 7, 8]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct BoundedMinMaxHeap<T, C = Natural> {
+    heap:     MinMaxHeap<T, C>,
+    bound:    usize,
+    eviction: Eviction,
+}
+
+impl<T: Ord> BoundedMinMaxHeap<T> {
+    /// Creates a new, empty `BoundedMinMaxHeap` that holds at most
+    /// `bound` elements, applying `eviction` when full.
+    ///
+    /// *O*(1).
+    pub fn new(bound: usize, eviction: Eviction) -> Self {
+        BoundedMinMaxHeap::with_comparator(bound, eviction, Natural)
+    }
+}
+
+impl<T, C> BoundedMinMaxHeap<T, C> {
+    /// Creates a new, empty `BoundedMinMaxHeap` ordered by `cmp` that
+    /// holds at most `bound` elements, applying `eviction` when full.
+    ///
+    /// *O*(1).
+    pub fn with_comparator(bound: usize, eviction: Eviction, cmp: C) -> Self {
+        BoundedMinMaxHeap {
+            heap: MinMaxHeap::with_comparator(cmp),
+            bound,
+            eviction,
+        }
+    }
+
+    /// The maximum number of elements the heap holds.
+    ///
+    /// *O*(1).
+    pub fn bound(&self) -> usize {
+        self.bound
+    }
+
+    /// The policy applied when pushing onto a full heap.
+    ///
+    /// *O*(1).
+    pub fn eviction(&self) -> Eviction {
+        self.eviction
+    }
+
+    /// Changes the policy applied when pushing onto a full heap.
+    ///
+    /// *O*(1).
+    pub fn set_eviction(&mut self, eviction: Eviction) {
+        self.eviction = eviction;
+    }
+
+    /// The number of elements in the heap.
+    ///
+    /// *O*(1).
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Is the heap empty?
+    ///
+    /// *O*(1).
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Does the heap hold `bound` elements?
+    ///
+    /// *O*(1).
+    pub fn is_full(&self) -> bool {
+        self.heap.len() >= self.bound
+    }
+
+    /// Drops all elements from the heap.
+    ///
+    /// *O*(*n*).
+    pub fn clear(&mut self) {
+        self.heap.clear();
+    }
+
+    /// Returns an iterator over the elements of the heap in arbitrary
+    /// order.
+    ///
+    /// *O*(1) on creation, and *O*(1) for each `next()` operation.
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.heap.iter()
+    }
+
+    /// Converts into the underlying, unbounded `MinMaxHeap`.
+    ///
+    /// *O*(1).
+    pub fn into_heap(self) -> MinMaxHeap<T, C> {
+        self.heap
+    }
+}
+
+impl<T, C: Compare<T>> BoundedMinMaxHeap<T, C> {
+    /// Pushes an element, applying the eviction policy if the heap is
+    /// full, and returns the element that was evicted or rejected, if
+    /// any.
+    ///
+    /// *O*(log *n*).
+    pub fn try_push(&mut self, element: T) -> Option<T> {
+        match self.eviction {
+            Eviction::EvictMax => self.heap.push_capped_max(self.bound, element),
+            Eviction::EvictMin => self.heap.push_capped_min(self.bound, element),
+            Eviction::Reject   => {
+                if self.is_full() {
+                    Some(element)
+                } else {
+                    self.heap.push(element);
+                    None
+                }
+            }
+        }
+    }
+
+    /// Pushes an element, applying the eviction policy if the heap is
+    /// full, and drops the element that was evicted or rejected, if any.
+    ///
+    /// *O*(log *n*).
+    pub fn push(&mut self, element: T) {
+        self.try_push(element);
+    }
+
+    /// Gets a reference to the minimum element, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_min(&self) -> Option<&T> {
+        self.heap.peek_min()
+    }
+
+    /// Gets a reference to the maximum element, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_max(&self) -> Option<&T> {
+        self.heap.peek_max()
+    }
+
+    /// Removes the minimum element, if any.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_min(&mut self) -> Option<T> {
+        self.heap.pop_min()
+    }
+
+    /// Removes the maximum element, if any.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_max(&mut self) -> Option<T> {
+        self.heap.pop_max()
+    }
+
+    /// Returns an ascending (sorted) vector, reusing the heap’s storage.
+    ///
+    /// *O*(*n* log *n*).
+    pub fn into_vec_asc(self) -> Vec<T> {
+        self.heap.into_vec_asc()
+    }
+
+    /// Returns a descending (sorted) vector, reusing the heap’s storage.
+    ///
+    /// *O*(*n* log *n*).
+    pub fn into_vec_desc(self) -> Vec<T> {
+        self.heap.into_vec_desc()
+    }
+}
+
+impl<T, C: Compare<T>> Extend<T> for BoundedMinMaxHeap<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for element in iter {
+            self.push(element);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate rand;
+
+    use super::*;
+    use self::rand::Rng;
+
+    fn check(eviction: Eviction) {
+        let mut rng = rand::thread_rng();
+
+        for bound in 0 .. 8 {
+            let mut heap = BoundedMinMaxHeap::new(bound, eviction);
+            let mut model = Vec::new();
+
+            for _ in 0 .. 40 {
+                let x = rng.gen_range(0 .. 20);
+                model.push(x);
+                model.sort();
+
+                let expected = if model.len() <= bound {
+                    None
+                } else {
+                    match eviction {
+                        Eviction::EvictMax => model.pop(),
+                        Eviction::EvictMin => Some(model.remove(0)),
+                        Eviction::Reject   => {
+                            let pos = model.iter().position(|&y| y == x).unwrap();
+                            Some(model.remove(pos))
+                        }
+                    }
+                };
+
+                assert_eq!(expected, heap.try_push(x));
+                assert_eq!(model.len(), heap.len());
+                assert!(heap.len() <= bound);
+            }
+
+            assert_eq!(model, heap.into_vec_asc());
+        }
+    }
+
+    #[test]
+    fn evict_max() {
+        check(Eviction::EvictMax);
+    }
+
+    #[test]
+    fn evict_min() {
+        check(Eviction::EvictMin);
+    }
+
+    #[test]
+    fn reject() {
+        check(Eviction::Reject);
+    }
+}
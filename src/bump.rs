@@ -0,0 +1,297 @@
+use core::fmt;
+
+use bumpalo::Bump;
+use bumpalo::collections::Vec as BumpVec;
+
+use super::{Compare, DoubleEndedHeap, Iter, MinMaxHeap, Natural, find_max_slice, push_slice,
+            trickle_down_extreme_slice};
+
+/// A min-max-heap whose elements live in a [`Bump`] arena.
+///
+/// Allocating from an arena is cheap, and the arena frees everything it
+/// holds at once when it is reset or dropped, which suits building and
+/// throwing away many short-lived heaps. Like other arena collections,
+/// the heap does not run its elements’ destructors when the arena is
+/// reset.
+///
+/// Requires the `bumpalo` feature.
+///
+/// # Examples
+///
+/// ```
+/// use bumpalo::Bump;
+/// use min_max_heap::BumpMinMaxHeap;
+///
+/// let bump = Bump::new();
+/// let mut heap = BumpMinMaxHeap::new_in(&bump);
+/// heap.extend(vec![3, 1, 4, 1, 5]);
+///
+/// assert_eq!(heap.pop_min(), Some(1));
+/// assert_eq!(heap.pop_max(), Some(5));
+/// assert_eq!(heap.into_bump_slice_asc(), &[1, 3, 4]);
+/// ```
+///
+/// [`Bump`]: https://docs.rs/bumpalo/3/bumpalo/struct.Bump.html
+pub struct BumpMinMaxHeap<'bump, T, C = Natural> {
+    data: BumpVec<'bump, T>,
+    cmp:  C,
+}
+
+impl<'bump, T: Ord> BumpMinMaxHeap<'bump, T> {
+    /// Creates a new, empty `BumpMinMaxHeap` in `bump`.
+    ///
+    /// *O*(1).
+    pub fn new_in(bump: &'bump Bump) -> Self {
+        BumpMinMaxHeap::with_comparator_in(Natural, bump)
+    }
+
+    /// Creates a new, empty `BumpMinMaxHeap` with space in `bump` to
+    /// hold `len` elements.
+    ///
+    /// *O*(1).
+    pub fn with_capacity_in(len: usize, bump: &'bump Bump) -> Self {
+        BumpMinMaxHeap::with_capacity_and_comparator_in(len, Natural, bump)
+    }
+}
+
+impl<'bump, T, C> BumpMinMaxHeap<'bump, T, C> {
+    /// Creates a new, empty `BumpMinMaxHeap` in `bump`, ordered by
+    /// `cmp`.
+    ///
+    /// *O*(1).
+    pub fn with_comparator_in(cmp: C, bump: &'bump Bump) -> Self {
+        BumpMinMaxHeap {
+            data: BumpVec::new_in(bump),
+            cmp,
+        }
+    }
+
+    /// Creates a new, empty `BumpMinMaxHeap` in `bump`, ordered by
+    /// `cmp`, with space to hold `len` elements.
+    ///
+    /// *O*(1).
+    pub fn with_capacity_and_comparator_in(len: usize, cmp: C, bump: &'bump Bump) -> Self {
+        BumpMinMaxHeap {
+            data: BumpVec::with_capacity_in(len, bump),
+            cmp,
+        }
+    }
+
+    /// The arena that the heap allocates from.
+    ///
+    /// *O*(1).
+    pub fn bump(&self) -> &'bump Bump {
+        self.data.bump()
+    }
+
+    /// The number of elements in the heap.
+    ///
+    /// *O*(1).
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Is the heap empty?
+    ///
+    /// *O*(1).
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// The number of elements the heap can hold without reallocating.
+    ///
+    /// *O*(1).
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    /// Reserves space for at least `additional` more elements.
+    ///
+    /// *O*(*n*) if it reallocates.
+    pub fn reserve(&mut self, additional: usize) {
+        self.data.reserve(additional);
+    }
+
+    /// Drops all elements from the heap.
+    ///
+    /// *O*(*n*).
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// Returns an iterator over the elements of the heap in arbitrary
+    /// order.
+    ///
+    /// *O*(1) on creation, and *O*(1) for each `next()` operation.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter(self.data.iter())
+    }
+
+    /// Returns the elements, in heap order, as a vector in the arena.
+    ///
+    /// *O*(1).
+    pub fn into_vec(self) -> BumpVec<'bump, T> {
+        self.data
+    }
+
+    /// Copies the elements, in heap order, into a `MinMaxHeap` on the
+    /// global heap.
+    ///
+    /// *O*(*n*).
+    pub fn to_heap(&self) -> MinMaxHeap<T, C>
+        where T: Clone,
+              C: Clone
+    {
+        MinMaxHeap(self.data.to_vec(), self.cmp.clone())
+    }
+}
+
+impl<'bump, T, C: Compare<T>> BumpMinMaxHeap<'bump, T, C> {
+    /// Adds an element to the heap.
+    ///
+    /// Growing copies the elements to a larger block of the arena, and
+    /// the arena keeps the old block until it is reset.
+    ///
+    /// Amortized *O*(log *n*).
+    pub fn push(&mut self, element: T) {
+        self.data.push(element);
+        push_slice(&mut self.data, &self.cmp);
+    }
+
+    /// Gets a reference to the minimum element, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_min(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    /// Gets a reference to the maximum element, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_max(&self) -> Option<&T> {
+        find_max_slice(&self.data, &self.cmp).map(|max| &self.data[max])
+    }
+
+    /// Removes the minimum element, if any.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_min(&mut self) -> Option<T> {
+        if self.data.is_empty() {
+            None
+        } else {
+            Some(self.remove(0))
+        }
+    }
+
+    /// Removes the maximum element, if any.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_max(&mut self) -> Option<T> {
+        let max = find_max_slice(&self.data, &self.cmp)?;
+        Some(self.remove(max))
+    }
+
+    /// Returns an ascending (sorted) slice that lives as long as the
+    /// arena.
+    ///
+    /// *O*(*n* log *n*).
+    pub fn into_bump_slice_asc(self) -> &'bump [T] {
+        let BumpMinMaxHeap { mut data, cmp } = self;
+        data.sort_by(|a, b| cmp.compare(a, b));
+        data.into_bump_slice()
+    }
+
+    /// Returns a descending (sorted) slice that lives as long as the
+    /// arena.
+    ///
+    /// *O*(*n* log *n*).
+    pub fn into_bump_slice_desc(self) -> &'bump [T] {
+        let BumpMinMaxHeap { mut data, cmp } = self;
+        data.sort_by(|a, b| cmp.compare(b, a));
+        data.into_bump_slice()
+    }
+
+    /// Removes the element at `pos`, the minimum or the maximum.
+    fn remove(&mut self, pos: usize) -> T {
+        let result = self.data.swap_remove(pos);
+        trickle_down_extreme_slice(&mut self.data, pos, &self.cmp);
+        result
+    }
+}
+
+impl<'bump, T, C: Compare<T>> DoubleEndedHeap for BumpMinMaxHeap<'bump, T, C> {
+    type Item = T;
+
+    fn push(&mut self, element: T) { BumpMinMaxHeap::push(self, element) }
+    fn peek_min(&self) -> Option<&T> { BumpMinMaxHeap::peek_min(self) }
+    fn peek_max(&self) -> Option<&T> { BumpMinMaxHeap::peek_max(self) }
+    fn pop_min(&mut self) -> Option<T> { BumpMinMaxHeap::pop_min(self) }
+    fn pop_max(&mut self) -> Option<T> { BumpMinMaxHeap::pop_max(self) }
+    fn len(&self) -> usize { BumpMinMaxHeap::len(self) }
+}
+
+impl<'bump, T, C: Compare<T>> Extend<T> for BumpMinMaxHeap<'bump, T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for element in iter {
+            self.push(element);
+        }
+    }
+}
+
+impl<'bump, T: Clone, C: Clone> Clone for BumpMinMaxHeap<'bump, T, C> {
+    fn clone(&self) -> Self {
+        BumpMinMaxHeap {
+            data: self.data.clone(),
+            cmp:  self.cmp.clone(),
+        }
+    }
+}
+
+impl<'bump, T: fmt::Debug, C> fmt::Debug for BumpMinMaxHeap<'bump, T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("BumpMinMaxHeap")
+         .field(&self.data.as_slice())
+         .finish()
+    }
+}
+
+impl<'a, 'bump, T, C> IntoIterator for &'a BumpMinMaxHeap<'bump, T, C> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter { self.iter() }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::test_model;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn random() {
+        let bump = Bump::new();
+        let mut heap = BumpMinMaxHeap::new_in(&bump);
+        let model = test_model::random(&mut heap, 3000, usize::MAX, |heap| {
+            heap.to_heap().assert_valid();
+        });
+        assert_eq!(model.into_vec_desc(), heap.into_bump_slice_desc());
+    }
+
+    #[test]
+    fn many_in_one_arena() {
+        let mut bump = Bump::new();
+
+        for round in 0 .. 3 {
+            let slices: Vec<&[u32]> = (0 .. 10).map(|i| {
+                let mut heap = BumpMinMaxHeap::with_capacity_in(8, &bump);
+                heap.extend((0 .. 8).map(|j| (i + j * 5 + round) % 8));
+                heap.into_bump_slice_asc()
+            }).collect();
+
+            for slice in slices {
+                assert_eq!(&[0, 1, 2, 3, 4, 5, 6, 7], slice);
+            }
+            bump.reset();
+        }
+    }
+}
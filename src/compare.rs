@@ -0,0 +1,350 @@
+use core::cmp::Ordering;
+use core::fmt;
+
+/// An ordering on elements of type `T`, used by a [`MinMaxHeap`] in
+/// place of `Ord`.
+///
+/// A comparator must be a total order, and it must not change while
+/// elements are in the heap. Like a broken `Ord` implementation, a
+/// comparator that violates these rules may cause the heap to behave
+/// incorrectly, but it cannot cause undefined behavior.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use std::cmp::Ordering;
+/// use min_max_heap::{Compare, MinMaxHeap};
+///
+/// // Orders strings by length, which `String`’s own `Ord` does not.
+/// struct ByLength;
+///
+/// impl Compare<String> for ByLength {
+///     fn compare(&self, a: &String, b: &String) -> Ordering {
+///         a.len().cmp(&b.len())
+///     }
+/// }
+///
+/// let mut heap = MinMaxHeap::with_comparator(ByLength);
+/// heap.push("three".to_owned());
+/// heap.push("a".to_owned());
+/// heap.push("to".to_owned());
+///
+/// assert_eq!(heap.peek_min().map(String::as_str), Some("a"));
+/// assert_eq!(heap.peek_max().map(String::as_str), Some("three"));
+/// # }
+/// ```
+///
+/// [`MinMaxHeap`]: struct.MinMaxHeap.html
+pub trait Compare<T: ?Sized> {
+    /// Compares two elements.
+    fn compare(&self, a: &T, b: &T) -> Ordering;
+
+    /// Does `a` compare less than `b`?
+    fn compares_lt(&self, a: &T, b: &T) -> bool {
+        self.compare(a, b) == Ordering::Less
+    }
+
+    /// Does `a` compare less than or equal to `b`?
+    fn compares_le(&self, a: &T, b: &T) -> bool {
+        self.compare(a, b) != Ordering::Greater
+    }
+
+    /// Does `a` compare greater than `b`?
+    fn compares_gt(&self, a: &T, b: &T) -> bool {
+        self.compare(a, b) == Ordering::Greater
+    }
+
+    /// Does `a` compare greater than or equal to `b`?
+    fn compares_ge(&self, a: &T, b: &T) -> bool {
+        self.compare(a, b) != Ordering::Less
+    }
+
+    /// Does `a` compare equal to `b`?
+    fn compares_eq(&self, a: &T, b: &T) -> bool {
+        self.compare(a, b) == Ordering::Equal
+    }
+}
+
+/// The natural ordering of a type, as given by its `Ord` implementation.
+///
+/// This is the default comparator of a [`MinMaxHeap`].
+///
+/// [`MinMaxHeap`]: struct.MinMaxHeap.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Natural;
+
+impl<T: Ord + ?Sized> Compare<T> for Natural {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+/// A comparator that calls a comparison function.
+///
+/// This is the comparator of heaps made with
+/// [`MinMaxHeap::new_by`](struct.MinMaxHeap.html#method.new_by).
+#[derive(Clone, Copy)]
+pub struct FnComparator<F>(pub F);
+
+impl<F> fmt::Debug for FnComparator<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("FnComparator")
+    }
+}
+
+impl<T: ?Sized, F: Fn(&T, &T) -> Ordering> Compare<T> for FnComparator<F> {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        (self.0)(a, b)
+    }
+}
+
+/// A comparator that orders elements by a key extracted from each.
+///
+/// This is the comparator of heaps made with
+/// [`MinMaxHeap::new_by_key`](struct.MinMaxHeap.html#method.new_by_key).
+/// The key function is called on both elements for every comparison, so
+/// it should be cheap.
+#[derive(Clone, Copy)]
+pub struct KeyComparator<F>(pub F);
+
+impl<F> fmt::Debug for KeyComparator<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("KeyComparator")
+    }
+}
+
+impl<T: ?Sized, K: Ord, F: Fn(&T) -> K> Compare<T> for KeyComparator<F> {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        (self.0)(a).cmp(&(self.0)(b))
+    }
+}
+
+/// What a [`PartialComparator`] does with elements that are not
+/// comparable, such as a floating-point NaN.
+///
+/// [`PartialComparator`]: struct.PartialComparator.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NanPolicy {
+    /// Refuse such elements: [`MinMaxHeap::push_partial`] returns them,
+    /// and comparing them panics.
+    ///
+    /// [`MinMaxHeap::push_partial`]: struct.MinMaxHeap.html#method.push_partial
+    Reject,
+    /// Order such elements after all others.
+    AsMax,
+    /// Order such elements before all others.
+    AsMin,
+}
+
+/// A comparator for types that are only `PartialOrd`, such as `f64`.
+///
+/// Comparable elements are ordered by `partial_cmp`. An element that is
+/// not comparable to itself, like a NaN, is handled according to the
+/// [`NanPolicy`], so that the result is a total order. (Like
+/// `partial_cmp`, this treats `-0.0` and `0.0` as equal.) Types with
+/// other incomparable pairs, such as sets ordered by inclusion, do not
+/// have a total order this way and should use a comparator of their own.
+///
+/// This is the comparator of heaps made with
+/// [`MinMaxHeap::new_partial`](struct.MinMaxHeap.html#method.new_partial).
+///
+/// [`NanPolicy`]: enum.NanPolicy.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PartialComparator(pub NanPolicy);
+
+impl PartialComparator {
+    /// Does this comparator accept `element`? Only elements that are not
+    /// comparable to themselves are refused, and only by
+    /// `NanPolicy::Reject`.
+    pub fn accepts<T: PartialOrd + ?Sized>(&self, element: &T) -> bool {
+        self.0 != NanPolicy::Reject || !is_nan(element)
+    }
+}
+
+fn is_nan<T: PartialOrd + ?Sized>(element: &T) -> bool {
+    element.partial_cmp(element).is_none()
+}
+
+impl<T: PartialOrd + ?Sized> Compare<T> for PartialComparator {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        if let Some(ordering) = a.partial_cmp(b) {
+            return ordering;
+        }
+
+        match self.0 {
+            NanPolicy::Reject => panic!("PartialComparator: incomparable element"),
+            NanPolicy::AsMax  => is_nan(a).cmp(&is_nan(b)),
+            NanPolicy::AsMin  => is_nan(b).cmp(&is_nan(a)),
+        }
+    }
+}
+
+/// Orders pairs by their first component alone, so that the second
+/// need not be `Ord`.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct ByFirst;
+
+#[cfg(feature = "std")]
+impl<K: Ord, V> Compare<(K, V)> for ByFirst {
+    fn compare(&self, a: &(K, V), b: &(K, V)) -> Ordering {
+        a.0.cmp(&b.0)
+    }
+}
+
+impl<T: ?Sized, C: Compare<T> + ?Sized> Compare<T> for &C {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        (**self).compare(a, b)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    extern crate rand;
+
+    use super::*;
+    use alloc::vec::Vec;
+    use super::super::MinMaxHeap;
+    use self::rand::Rng;
+
+    // A type with no `Ord` of its own.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Point(f64, f64);
+
+    struct ByDistance;
+
+    impl Compare<Point> for ByDistance {
+        fn compare(&self, a: &Point, b: &Point) -> Ordering {
+            let da = a.0 * a.0 + a.1 * a.1;
+            let db = b.0 * b.0 + b.1 * b.1;
+            da.partial_cmp(&db).unwrap()
+        }
+    }
+
+    #[derive(Clone)]
+    struct Descending;
+
+    impl Compare<u32> for Descending {
+        fn compare(&self, a: &u32, b: &u32) -> Ordering {
+            b.cmp(a)
+        }
+    }
+
+    #[test]
+    fn points() {
+        let mut heap = MinMaxHeap::with_comparator(ByDistance);
+        heap.push(Point(3.0, 4.0));
+        heap.push(Point(0.0, 1.0));
+        heap.push(Point(-6.0, 0.0));
+        heap.push(Point(1.0, 1.0));
+
+        assert_eq!(Some(&Point(0.0, 1.0)), heap.peek_min());
+        assert_eq!(Some(&Point(-6.0, 0.0)), heap.peek_max());
+        assert_eq!(vec![Point(0.0, 1.0), Point(1.0, 1.0), Point(3.0, 4.0), Point(-6.0, 0.0)],
+                   heap.into_vec_asc());
+    }
+
+    #[test]
+    fn descending() {
+        let mut rng = rand::thread_rng();
+
+        for len in 0 .. 50 {
+            let vec: Vec<u32> = (0 .. len).map(|_| rng.gen_range(0 .. 20)).collect();
+            let mut heap = MinMaxHeap::from_vec_with_comparator(vec.clone(), Descending);
+            heap.assert_valid();
+
+            let mut expected = vec.clone();
+            expected.sort_by(|a, b| b.cmp(a));
+
+            assert_eq!(expected.iter().collect::<Vec<_>>(),
+                       heap.iter_asc().collect::<Vec<_>>());
+            assert_eq!(expected.iter().rev().collect::<Vec<_>>(),
+                       heap.iter_desc().collect::<Vec<_>>());
+            assert_eq!(expected.first(), heap.peek_min());
+            assert_eq!(expected.last(), heap.peek_max());
+
+            if let Some(&x) = expected.first() {
+                assert_eq!(vec.iter().filter(|&&y| y == x).count(), heap.count(&x));
+            }
+
+            let upper = heap.split_off_max(len as usize / 3);
+            upper.assert_valid();
+            heap.assert_valid();
+
+            let mut drained: Vec<_> = heap.drain_asc().collect();
+            drained.extend(upper.into_vec_asc());
+            assert_eq!(expected, drained);
+        }
+    }
+
+    #[test]
+    fn closure() {
+        #[derive(Debug, PartialEq)]
+        struct Task { name: &'static str, priority: u8 }
+
+        let mut heap = MinMaxHeap::new_by(|a: &Task, b: &Task| a.priority.cmp(&b.priority));
+        heap.push(Task { name: "write", priority: 2 });
+        heap.push(Task { name: "test", priority: 9 });
+        heap.push(Task { name: "lint", priority: 1 });
+
+        assert_eq!(Some("lint"), heap.pop_min().map(|t| t.name));
+        assert_eq!(Some("test"), heap.pop_max().map(|t| t.name));
+
+        let heap = MinMaxHeap::from_vec_by(vec![3, 1, 4, 1, 5], |a: &i32, b: &i32| b.cmp(a));
+        assert_eq!(vec![5, 4, 3, 1, 1], heap.into_vec_asc());
+    }
+
+    #[test]
+    fn key() {
+        let words = vec!["kiwi", "fig", "banana", "apple"];
+        let heap = MinMaxHeap::from_vec_by_key(words, |w: &&str| w.len());
+        heap.assert_valid();
+        assert_eq!(Some(&"fig"), heap.peek_min());
+        assert_eq!(Some(&"banana"), heap.peek_max());
+
+        let mut heap = MinMaxHeap::new_by_key(|p: &Point| (p.0 * 10.0) as i64);
+        heap.extend(vec![Point(0.5, 0.0), Point(-1.0, 3.0), Point(2.0, 1.0)]);
+        assert_eq!(Some(Point(-1.0, 3.0)), heap.pop_min());
+        assert_eq!(Some(Point(2.0, 1.0)), heap.pop_max());
+    }
+
+    #[test]
+    fn borrowed_comparator() {
+        let mut heap = MinMaxHeap::with_comparator(&Descending);
+        heap.extend(vec![1, 5, 3]);
+        assert_eq!(Some(5), heap.pop_min());
+        assert_eq!(Some(1), heap.pop_max());
+    }
+
+    #[test]
+    fn partial() {
+        let values = vec![2.5, f64::NAN, -1.0, 0.0, f64::NAN, 7.0];
+
+        let heap = MinMaxHeap::from_vec_partial(values.clone(), NanPolicy::AsMax);
+        heap.assert_valid();
+        let asc = heap.into_vec_asc();
+        assert_eq!(vec![-1.0, 0.0, 2.5, 7.0], asc[.. 4].to_vec());
+        assert!(asc[4].is_nan() && asc[5].is_nan());
+
+        let mut heap = MinMaxHeap::from_vec_partial(values.clone(), NanPolicy::AsMin);
+        heap.assert_valid();
+        assert!(heap.pop_min().unwrap().is_nan());
+        assert!(heap.pop_min().unwrap().is_nan());
+        assert_eq!(Some(-1.0), heap.pop_min());
+        assert_eq!(Some(7.0), heap.pop_max());
+
+        let mut heap = MinMaxHeap::new_partial(NanPolicy::Reject);
+        for &x in &values {
+            let result = heap.push_partial(x);
+            assert_eq!(x.is_nan(), result.is_err());
+        }
+        assert_eq!(vec![-1.0, 0.0, 2.5, 7.0], heap.into_vec_asc());
+    }
+
+    #[test]
+    #[should_panic]
+    fn partial_reject_from_vec() {
+        MinMaxHeap::from_vec_partial(vec![1.0, f64::NAN], NanPolicy::Reject);
+    }
+}
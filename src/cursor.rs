@@ -0,0 +1,286 @@
+use super::index::*;
+
+/// A read-only position in the implicit tree of a min-max-heap.
+///
+/// The root is on a min level, and levels alternate from there down:
+/// each element on a min level is no greater than any of its
+/// descendants, and each element on a max level is no less than any of
+/// its descendants. A cursor can move between related nodes and read
+/// their elements, which is enough to implement searches that prune
+/// whole subtrees.
+///
+/// This type is created with
+/// [`MinMaxHeap::cursor_min`](struct.MinMaxHeap.html#method.cursor_min)
+/// and
+/// [`MinMaxHeap::cursor_max`](struct.MinMaxHeap.html#method.cursor_max).
+///
+/// # Examples
+///
+/// ```
+/// use min_max_heap::{Cursor, MinMaxHeap};
+///
+/// // Does the heap contain `target`, skipping subtrees that cannot?
+/// fn contains(cursor: Cursor<'_, i32>, target: i32) -> bool {
+///     let element = *cursor.element();
+///     if element == target {
+///         return true;
+///     }
+///     if cursor.is_min_level() && element > target ||
+///        cursor.is_max_level() && element < target {
+///         return false;
+///     }
+///     cursor.children().any(|child| contains(child, target))
+/// }
+///
+/// let heap: MinMaxHeap<_> = (0 .. 50).filter(|x| x % 3 != 0).collect();
+/// let root = heap.cursor_min().unwrap();
+/// assert!(contains(root, 7));
+/// assert!(!contains(root, 9));
+/// ```
+pub struct Cursor<'a, T: 'a> {
+    data: &'a [T],
+    pos:  usize,
+}
+
+impl<'a, T> Cursor<'a, T> {
+    pub(crate) fn new(data: &'a [T], pos: usize) -> Self {
+        debug_assert!(pos < data.len());
+        Cursor { data, pos }
+    }
+
+    fn to(&self, pos: usize) -> Option<Self> {
+        if pos < self.data.len() {
+            Some(Cursor::new(self.data, pos))
+        } else {
+            None
+        }
+    }
+
+    /// The element at the cursor.
+    ///
+    /// *O*(1).
+    pub fn element(&self) -> &'a T {
+        &self.data[self.pos]
+    }
+
+    /// The position of the cursor in level order, where the root is 0
+    /// and the children of position *i* are 2*i* + 1 and 2*i* + 2.
+    ///
+    /// *O*(1).
+    pub fn index(&self) -> usize {
+        self.pos
+    }
+
+    /// Is the cursor at the root?
+    ///
+    /// *O*(1).
+    pub fn is_root(&self) -> bool {
+        !self.pos.has_parent()
+    }
+
+    /// Is the cursor on a min level?
+    ///
+    /// *O*(1).
+    pub fn is_min_level(&self) -> bool {
+        self.pos.is_min_level()
+    }
+
+    /// Is the cursor on a max level?
+    ///
+    /// *O*(1).
+    pub fn is_max_level(&self) -> bool {
+        !self.pos.is_min_level()
+    }
+
+    /// The number of elements in the subtree rooted at the cursor,
+    /// including its own.
+    ///
+    /// *O*(log *n*).
+    pub fn subtree_len(&self) -> usize {
+        self.pos.subtree_len(self.data.len())
+    }
+
+    /// Moves to the parent, if any.
+    ///
+    /// *O*(1).
+    pub fn parent(&self) -> Option<Self> {
+        if self.pos.has_parent() {
+            self.to(self.pos.parent())
+        } else {
+            None
+        }
+    }
+
+    /// Moves to the grandparent, if any.
+    ///
+    /// *O*(1).
+    pub fn grandparent(&self) -> Option<Self> {
+        if self.pos.has_grandparent() {
+            self.to(self.pos.grandparent())
+        } else {
+            None
+        }
+    }
+
+    /// Moves to the left child, if any.
+    ///
+    /// *O*(1).
+    pub fn left(&self) -> Option<Self> {
+        self.to(self.pos.child1())
+    }
+
+    /// Moves to the right child, if any.
+    ///
+    /// *O*(1).
+    pub fn right(&self) -> Option<Self> {
+        self.to(self.pos.child2())
+    }
+
+    /// Returns an iterator over the cursor’s children, left to right.
+    ///
+    /// *O*(1).
+    pub fn children(&self) -> Cursors<'a, T> {
+        Cursors::new(self.data, self.pos.child1(), 2)
+    }
+
+    /// Returns an iterator over the cursor’s grandchildren, left to
+    /// right.
+    ///
+    /// *O*(1).
+    pub fn grandchildren(&self) -> Cursors<'a, T> {
+        Cursors::new(self.data, self.pos.grandchild1(), 4)
+    }
+}
+
+impl<'a, T> Clone for Cursor<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for Cursor<'a, T> { }
+
+impl<'a, T: core::fmt::Debug> core::fmt::Debug for Cursor<'a, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Cursor")
+         .field("index", &self.pos)
+         .field("element", self.element())
+         .finish()
+    }
+}
+
+/// An iterator over cursors at consecutive positions, such as the
+/// children or grandchildren of a node.
+///
+/// This type is created with
+/// [`Cursor::children`](struct.Cursor.html#method.children) and
+/// [`Cursor::grandchildren`](struct.Cursor.html#method.grandchildren).
+#[derive(Clone, Debug)]
+pub struct Cursors<'a, T: 'a> {
+    data: &'a [T],
+    next: usize,
+    end:  usize,
+}
+
+impl<'a, T> Cursors<'a, T> {
+    fn new(data: &'a [T], first: usize, count: usize) -> Self {
+        let end = first.saturating_add(count).min(data.len());
+        Cursors { data, next: first.min(end), end }
+    }
+}
+
+impl<'a, T> Iterator for Cursors<'a, T> {
+    type Item = Cursor<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next < self.end {
+            let result = Cursor::new(self.data, self.next);
+            self.next += 1;
+            Some(result)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.next;
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Cursors<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.next < self.end {
+            self.end -= 1;
+            Some(Cursor::new(self.data, self.end))
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Cursors<'a, T> { }
+
+#[cfg(test)]
+mod test {
+    use super::super::MinMaxHeap;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn navigate() {
+        let heap: MinMaxHeap<_> = (0 .. 10).collect();
+
+        let root = heap.cursor_min().unwrap();
+        assert!(root.is_root());
+        assert!(root.is_min_level());
+        assert_eq!(&0, root.element());
+        assert_eq!(10, root.subtree_len());
+        assert!(root.parent().is_none());
+
+        let max = heap.cursor_max().unwrap();
+        assert_eq!(&9, max.element());
+        assert!(max.is_max_level());
+        assert_eq!(0, max.parent().unwrap().index());
+
+        assert_eq!(2, root.children().len());
+        assert_eq!(4, root.grandchildren().len());
+
+        let left = root.left().unwrap();
+        assert_eq!(vec![3, 4], left.children().map(|c| c.index()).collect::<Vec<_>>());
+        assert_eq!(vec![7, 8, 9], left.grandchildren().map(|c| c.index()).collect::<Vec<_>>());
+        assert_eq!(0, left.children().next().unwrap().grandparent().unwrap().index());
+
+        let right = root.right().unwrap();
+        assert_eq!(2, right.children().len());
+        assert_eq!(0, right.grandchildren().len());
+        assert_eq!(None, right.left().unwrap().left().map(|c| c.index()));
+    }
+
+    #[test]
+    fn every_node() {
+        for len in 0 .. 40 {
+            let heap: MinMaxHeap<_> = (0 .. len).rev().collect();
+
+            let mut visited = Vec::new();
+            let mut stack: Vec<_> = heap.cursor_min().into_iter().collect();
+            while let Some(cursor) = stack.pop() {
+                visited.push(*cursor.element());
+                for child in cursor.children() {
+                    assert_eq!(cursor.index(), child.parent().unwrap().index());
+                    assert_eq!(cursor.is_min_level(), child.is_max_level());
+                    stack.push(child);
+                }
+            }
+
+            visited.sort();
+            assert_eq!((0 .. len).collect::<Vec<_>>(), visited);
+        }
+    }
+
+    #[test]
+    fn empty() {
+        let heap = MinMaxHeap::<i32>::new();
+        assert!(heap.cursor_min().is_none());
+        assert!(heap.cursor_max().is_none());
+    }
+}
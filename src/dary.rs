@@ -0,0 +1,458 @@
+use alloc::vec::Vec;
+use core::iter::FromIterator;
+
+use super::{DoubleEndedHeap, Iter};
+
+/// A min-max-heap in which every node has `D` children.
+///
+/// A wider tree is shallower, so pushes compare fewer elements, and the
+/// children of a node share fewer cache lines. Pops compare more
+/// elements at each level, since they must look at up to `D` children
+/// and `D`² grandchildren. For large heaps, `D = 4` is often faster than
+/// the binary [`MinMaxHeap`]; measuring is the only way to be sure.
+///
+/// `D` must be at least 2, and `DaryMinMaxHeap<T, 2>` orders its
+/// elements exactly as `MinMaxHeap<T>` does.
+///
+/// # Examples
+///
+/// ```
+/// use min_max_heap::DaryMinMaxHeap;
+///
+/// let mut heap = DaryMinMaxHeap::<_, 4>::new();
+/// heap.extend(vec![6, 2, 9, 4, 7, 1, 8]);
+///
+/// assert_eq!(heap.pop_min(), Some(1));
+/// assert_eq!(heap.pop_max(), Some(9));
+/// assert_eq!(heap.into_vec_asc(), [2, 4, 6, 7, 8]);
+/// ```
+///
+/// [`MinMaxHeap`]: struct.MinMaxHeap.html
+#[derive(Clone, Debug)]
+pub struct DaryMinMaxHeap<T, const D: usize>(Vec<T>);
+
+fn parent<const D: usize>(pos: usize) -> usize {
+    (pos - 1) / D
+}
+
+fn first_child<const D: usize>(pos: usize) -> usize {
+    D * pos + 1
+}
+
+// Level `d` starts at `(D^d - 1) / (D - 1)`, so `pos` is at depth
+// `floor(log_D(pos (D - 1) + 1))`.
+fn is_min_level<const D: usize>(pos: usize) -> bool {
+    let scaled = pos * (D - 1) + 1;
+    let depth = if D.is_power_of_two() {
+        log2(scaled) / log2(D)
+    } else {
+        let mut depth = 0;
+        let mut power = D;
+        while power <= scaled {
+            depth += 1;
+            match power.checked_mul(D) {
+                Some(next) => power = next,
+                None => break,
+            }
+        }
+        depth
+    };
+    depth % 2 == 0
+}
+
+/// The floor of the base-2 logarithm of `n`, which must be positive.
+fn log2(n: usize) -> u32 {
+    usize::BITS - 1 - n.leading_zeros()
+}
+
+impl<T, const D: usize> Default for DaryMinMaxHeap<T, D> {
+    fn default() -> Self {
+        DaryMinMaxHeap::new()
+    }
+}
+
+impl<T, const D: usize> DaryMinMaxHeap<T, D> {
+    /// Creates a new, empty `DaryMinMaxHeap`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `D` is less than 2.
+    ///
+    /// *O*(1).
+    pub fn new() -> Self {
+        DaryMinMaxHeap::with_capacity(0)
+    }
+
+    /// Creates a new, empty `DaryMinMaxHeap` with space allocated to hold
+    /// `len` elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `D` is less than 2.
+    ///
+    /// *O*(*n*).
+    pub fn with_capacity(len: usize) -> Self {
+        assert!(D >= 2, "DaryMinMaxHeap: arity must be at least 2");
+        DaryMinMaxHeap(Vec::with_capacity(len))
+    }
+
+    /// The number of elements in the heap.
+    ///
+    /// *O*(1).
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Is the heap empty?
+    ///
+    /// *O*(1).
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Drops all elements from the heap.
+    ///
+    /// *O*(*n*).
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Returns an iterator over the elements of the heap in arbitrary
+    /// order.
+    ///
+    /// *O*(1) on creation, and *O*(1) for each `next()` operation.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter(self.0.iter())
+    }
+
+    /// Returns a vector of the elements in arbitrary order.
+    ///
+    /// *O*(1).
+    pub fn into_vec(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T: Ord, const D: usize> DaryMinMaxHeap<T, D> {
+    /// Adds an element to the heap.
+    ///
+    /// *O*(log *n*).
+    pub fn push(&mut self, element: T) {
+        let pos = self.0.len();
+        self.0.push(element);
+        self.bubble_up(pos, is_min_level::<D>(pos));
+    }
+
+    /// Gets a reference to the minimum element, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_min(&self) -> Option<&T> {
+        self.0.first()
+    }
+
+    /// Gets a reference to the maximum element, if any.
+    ///
+    /// *O*(*D*).
+    pub fn peek_max(&self) -> Option<&T> {
+        self.find_max().map(|max| &self.0[max])
+    }
+
+    /// Removes the minimum element, if any.
+    ///
+    /// *O*(*D*² log *n*).
+    pub fn pop_min(&mut self) -> Option<T> {
+        self.remove(0)
+    }
+
+    /// Removes the maximum element, if any.
+    ///
+    /// *O*(*D*² log *n*).
+    pub fn pop_max(&mut self) -> Option<T> {
+        let max = self.find_max()?;
+        self.remove(max)
+    }
+
+    /// Returns an ascending (sorted) vector.
+    ///
+    /// *O*(*n* log *n*).
+    pub fn into_vec_asc(mut self) -> Vec<T> {
+        let mut result = Vec::with_capacity(self.len());
+        while let Some(element) = self.pop_min() {
+            result.push(element);
+        }
+        result
+    }
+
+    /// Returns a descending (sorted) vector.
+    ///
+    /// *O*(*n* log *n*).
+    pub fn into_vec_desc(mut self) -> Vec<T> {
+        let mut result = Vec::with_capacity(self.len());
+        while let Some(element) = self.pop_max() {
+            result.push(element);
+        }
+        result
+    }
+
+    fn find_max(&self) -> Option<usize> {
+        match self.0.len() {
+            0 => None,
+            1 => Some(0),
+            len => {
+                let children = 1 .. len.min(D + 1);
+                children.max_by(|&i, &j| self.0[i].cmp(&self.0[j]))
+            }
+        }
+    }
+
+    /// Removes the element at `pos`, which must be the minimum or the
+    /// maximum, and restores the heap.
+    fn remove(&mut self, pos: usize) -> Option<T> {
+        if pos >= self.0.len() {
+            return None;
+        }
+
+        let result = self.0.swap_remove(pos);
+        if pos < self.0.len() {
+            // Only the root is on a min level.
+            self.trickle_down(pos, pos == 0);
+        }
+        Some(result)
+    }
+
+    /// Moves the element at `pos`, which is on a min level if `min`,
+    /// toward the root.
+    fn bubble_up(&mut self, pos: usize, min: bool) {
+        if pos == 0 {
+            return;
+        }
+
+        let parent = parent::<D>(pos);
+
+        // An element on a min level that exceeds its parent belongs on
+        // the max levels above, and vice versa.
+        if Self::before(&self.0, parent, pos, min) {
+            self.0.swap(pos, parent);
+            self.bubble_up_by(parent, !min);
+        } else {
+            self.bubble_up_by(pos, min);
+        }
+    }
+
+    /// Moves the element at `pos` up through its grandparents, toward
+    /// the root on min levels if `min` and toward the root’s children
+    /// otherwise.
+    fn bubble_up_by(&mut self, mut pos: usize, min: bool) {
+        while pos > D {
+            let grandparent = parent::<D>(parent::<D>(pos));
+            if !Self::before(&self.0, pos, grandparent, min) {
+                break;
+            }
+            self.0.swap(pos, grandparent);
+            pos = grandparent;
+        }
+    }
+
+    /// Does the element at `i` belong closer to the root than the one at
+    /// `j`, on min levels if `min` and on max levels otherwise?
+    fn before(data: &[T], i: usize, j: usize, min: bool) -> bool {
+        if min { data[i] < data[j] } else { data[i] > data[j] }
+    }
+
+    /// Moves the element at `pos`, which is on a min level if `min` and
+    /// on a max level otherwise, down through its descendants.
+    fn trickle_down(&mut self, mut pos: usize, min: bool) {
+        let len = self.0.len();
+        let before = |data: &[T], i, j| Self::before(data, i, j, min);
+
+        loop {
+            let first = first_child::<D>(pos);
+            if first >= len {
+                return;
+            }
+
+            // The extreme of the children and grandchildren.
+            let mut best = first;
+            for child in first .. len.min(first + D) {
+                if before(&self.0, child, best) {
+                    best = child;
+                }
+                let grandchild = first_child::<D>(child);
+                for i in grandchild .. len.min(grandchild + D) {
+                    if before(&self.0, i, best) {
+                        best = i;
+                    }
+                }
+            }
+
+            if !before(&self.0, best, pos) {
+                return;
+            }
+            self.0.swap(pos, best);
+
+            if best < first + D {
+                // A child is a leaf of the opposite kind of level.
+                return;
+            }
+
+            let parent = parent::<D>(best);
+            if before(&self.0, parent, best) {
+                self.0.swap(parent, best);
+            }
+            pos = best;
+        }
+    }
+}
+
+impl<T: Ord, const D: usize> DoubleEndedHeap for DaryMinMaxHeap<T, D> {
+    type Item = T;
+
+    fn push(&mut self, element: T) { DaryMinMaxHeap::push(self, element) }
+    fn peek_min(&self) -> Option<&T> { DaryMinMaxHeap::peek_min(self) }
+    fn peek_max(&self) -> Option<&T> { DaryMinMaxHeap::peek_max(self) }
+    fn pop_min(&mut self) -> Option<T> { DaryMinMaxHeap::pop_min(self) }
+    fn pop_max(&mut self) -> Option<T> { DaryMinMaxHeap::pop_max(self) }
+    fn len(&self) -> usize { DaryMinMaxHeap::len(self) }
+}
+
+impl<T: Ord, const D: usize> Extend<T> for DaryMinMaxHeap<T, D> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for element in iter {
+            self.push(element);
+        }
+    }
+}
+
+impl<T: Ord, const D: usize> FromIterator<T> for DaryMinMaxHeap<T, D> {
+    fn from_iter<I>(iter: I) -> Self
+            where I: IntoIterator<Item = T> {
+        DaryMinMaxHeap::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<T: Ord, const D: usize> From<Vec<T>> for DaryMinMaxHeap<T, D> {
+    fn from(vec: Vec<T>) -> Self {
+        let mut heap = DaryMinMaxHeap::with_capacity(0);
+        heap.0 = vec;
+        if heap.len() > 1 {
+            // Trickle down the internal nodes a level at a time, from the
+            // last level with children up to the root.
+            let last_parent = parent::<D>(heap.len() - 1);
+            let mut start = 0;
+            let mut min = true;
+            while first_child::<D>(start) <= last_parent {
+                start = first_child::<D>(start);
+                min = !min;
+            }
+
+            let mut end = last_parent + 1;
+            loop {
+                for pos in (start .. end).rev() {
+                    heap.trickle_down(pos, min);
+                }
+                if start == 0 {
+                    break;
+                }
+                end = start;
+                start = parent::<D>(start);
+                min = !min;
+            }
+        }
+        heap
+    }
+}
+
+impl<'a, T, const D: usize> IntoIterator for &'a DaryMinMaxHeap<T, D> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter { self.iter() }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate rand;
+
+    use super::*;
+    use super::super::test_model;
+    use self::rand::Rng;
+
+    fn check<T: Ord, const D: usize>(heap: &DaryMinMaxHeap<T, D>) {
+        let data = &heap.0;
+        for pos in 1 .. data.len() {
+            let mut ancestor = pos;
+            while ancestor > 0 {
+                ancestor = parent::<D>(ancestor);
+                if is_min_level::<D>(ancestor) {
+                    assert!(data[ancestor] <= data[pos]);
+                } else {
+                    assert!(data[ancestor] >= data[pos]);
+                }
+            }
+        }
+    }
+
+    fn min_levels<const D: usize>() {
+        let mut min_level = vec![true];
+        for pos in 1 .. 5000 {
+            min_level.push(!min_level[parent::<D>(pos)]);
+            assert_eq!(min_level[pos], is_min_level::<D>(pos), "D = {}, pos {}", D, pos);
+        }
+    }
+
+    #[test]
+    fn min_levels_all() {
+        min_levels::<2>();
+        min_levels::<3>();
+        min_levels::<4>();
+        min_levels::<5>();
+        min_levels::<8>();
+    }
+
+    fn random<const D: usize>() {
+        let mut heap = DaryMinMaxHeap::<_, D>::new();
+        let model = test_model::random(&mut heap, 3000, usize::MAX, check);
+        assert_eq!(model.into_vec_asc(), heap.into_vec_asc());
+    }
+
+    #[test]
+    fn random_2() {
+        random::<2>();
+    }
+
+    #[test]
+    fn random_3() {
+        random::<3>();
+    }
+
+    #[test]
+    fn random_4() {
+        random::<4>();
+    }
+
+    #[test]
+    fn random_8() {
+        random::<8>();
+    }
+
+    #[test]
+    fn from_vec() {
+        let mut rng = rand::thread_rng();
+
+        for len in 0 .. 100 {
+            let mut vec: Vec<u32> = (0 .. len).map(|_| rng.gen_range(0 .. 50)).collect();
+            let heap3 = DaryMinMaxHeap::<_, 3>::from(vec.clone());
+            let heap4 = DaryMinMaxHeap::<_, 4>::from(vec.clone());
+            check(&heap3);
+            check(&heap4);
+            vec.sort();
+            assert_eq!(vec, heap3.into_vec_asc());
+            assert_eq!(vec, heap4.into_vec_asc());
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn unary() {
+        DaryMinMaxHeap::<u8, 1>::new();
+    }
+}
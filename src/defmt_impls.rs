@@ -0,0 +1,13 @@
+use defmt::{Format, Formatter};
+
+use super::MinMaxHeap;
+
+// Logs the length and then the elements in heap order, which is all an
+// embedded target can show without allocating to sort them.
+
+impl<T: Format, C> Format for MinMaxHeap<T, C> {
+    fn format(&self, fmt: Formatter<'_>) {
+        defmt::write!(fmt, "MinMaxHeap {{ len: {=usize}, elements: {=[?]} }}",
+                      self.len(), self.0.as_slice());
+    }
+}
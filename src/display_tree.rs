@@ -0,0 +1,100 @@
+use alloc::string::String;
+use core::fmt;
+
+use super::index::*;
+
+/// Renders the implicit tree of a min-max-heap as indented ASCII text.
+///
+/// Each line shows one element, formatted with `Debug`, and whether it
+/// lies on a min level or a max level. Children are listed below their
+/// parent, left first.
+///
+/// This type is created with
+/// [`MinMaxHeap::display_tree`](struct.MinMaxHeap.html#method.display_tree).
+///
+/// # Examples
+///
+/// ```
+/// use min_max_heap::MinMaxHeap;
+///
+/// let heap: MinMaxHeap<_> = (1 .. 6).collect();
+///
+/// assert_eq!(heap.display_tree().to_string(), "\
+/// min 1
+/// +-- max 5
+/// |   +-- min 2
+/// |   `-- min 3
+/// `-- max 4
+/// ");
+/// ```
+pub struct DisplayTree<'a, T: 'a>(pub(crate) &'a [T]);
+
+impl<'a, T: fmt::Debug> DisplayTree<'a, T> {
+    fn node(&self, f: &mut fmt::Formatter<'_>, pos: usize,
+            prefix: &mut String) -> fmt::Result {
+        let level = if pos.is_min_level() { "min" } else { "max" };
+        writeln!(f, "{} {:?}", level, self.0[pos])?;
+
+        for child in &[pos.child1(), pos.child2()] {
+            let child = *child;
+            if child >= self.0.len() {
+                break;
+            }
+
+            let last = child == pos.child2() || pos.child2() >= self.0.len();
+            let saved = prefix.len();
+
+            write!(f, "{}{}", prefix, if last { "`-- " } else { "+-- " })?;
+            prefix.push_str(if last { "    " } else { "|   " });
+            self.node(f, child, prefix)?;
+            prefix.truncate(saved);
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a, T: fmt::Debug> fmt::Display for DisplayTree<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            writeln!(f, "(empty)")
+        } else {
+            self.node(f, 0, &mut String::new())
+        }
+    }
+}
+
+impl<'a, T: fmt::Debug> fmt::Debug for DisplayTree<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::MinMaxHeap;
+    use alloc::string::ToString;
+
+    #[test]
+    fn empty() {
+        let heap = MinMaxHeap::<i32>::new();
+        assert_eq!("(empty)\n", heap.display_tree().to_string());
+    }
+
+    #[test]
+    fn deep() {
+        let heap: MinMaxHeap<_> = (0 .. 10).collect();
+        assert_eq!(heap.display_tree().to_string(), "\
+min 0
++-- max 9
+|   +-- min 1
+|   |   +-- max 7
+|   |   `-- max 6
+|   `-- min 2
+|       `-- max 5
+`-- max 8
+    +-- min 3
+    `-- min 4
+");
+    }
+}
@@ -0,0 +1,103 @@
+use alloc::string::String;
+use core::fmt::{self, Write};
+
+use super::MinMaxHeap;
+use super::index::*;
+
+impl<T: fmt::Debug, C> MinMaxHeap<T, C> {
+    /// Renders the implicit tree in the Graphviz DOT language.
+    ///
+    /// Each node is labeled with its element, formatted with `Debug`.
+    /// Nodes on min levels are filled light blue and nodes on max levels
+    /// light salmon.
+    ///
+    /// Requires the `dot` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let heap: MinMaxHeap<_> = vec![2, 1].into_iter().collect();
+    /// assert_eq!(heap.to_dot(), "\
+    /// digraph MinMaxHeap {
+    ///     node [style=filled];
+    ///     n0 [label=\"1\", fillcolor=lightblue];
+    ///     n1 [label=\"2\", fillcolor=lightsalmon];
+    ///     n0 -> n1;
+    /// }
+    /// ");
+    /// ```
+    ///
+    /// *O*(*n*).
+    pub fn to_dot(&self) -> String {
+        let mut result = String::new();
+        let mut label = String::new();
+
+        result.push_str("digraph MinMaxHeap {\n");
+        result.push_str("    node [style=filled];\n");
+
+        for (pos, element) in self.0.iter().enumerate() {
+            label.clear();
+            write!(label, "{:?}", element).unwrap();
+
+            let color = if pos.is_min_level() { "lightblue" } else { "lightsalmon" };
+            writeln!(result, "    n{} [label=\"{}\", fillcolor={}];",
+                     pos, escape(&label), color).unwrap();
+        }
+
+        for pos in 1 .. self.0.len() {
+            writeln!(result, "    n{} -> n{};", pos.parent(), pos).unwrap();
+        }
+
+        result.push_str("}\n");
+        result
+    }
+}
+
+fn escape(label: &str) -> String {
+    let mut result = String::with_capacity(label.len());
+
+    for c in label.chars() {
+        match c {
+            '"' | '\\' => {
+                result.push('\\');
+                result.push(c);
+            }
+            '\n' => result.push_str("\\n"),
+            _ => result.push(c),
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        let heap = MinMaxHeap::<i32>::new();
+        assert_eq!("digraph MinMaxHeap {\n    node [style=filled];\n}\n",
+                   heap.to_dot());
+    }
+
+    #[test]
+    fn edges_and_colors() {
+        let heap: MinMaxHeap<_> = (0 .. 7).collect();
+        let dot = heap.to_dot();
+
+        for pos in 1 .. 7 {
+            assert!(dot.contains(&format!("n{} -> n{};", (pos - 1) / 2, pos)));
+        }
+        assert_eq!(5, dot.matches("lightblue").count());
+        assert_eq!(2, dot.matches("lightsalmon").count());
+    }
+
+    #[test]
+    fn escaping() {
+        let heap: MinMaxHeap<_> = vec!["a\"b\\c"].into_iter().collect();
+        assert!(heap.to_dot().contains(r#"label="\"a\\\"b\\\\c\"""#));
+    }
+}
@@ -0,0 +1,55 @@
+/// The operations that every double-ended priority queue in this crate
+/// supports, for code that works with any of them.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use min_max_heap::{DoubleEndedHeap, IntervalHeap, MinMaxHeap};
+///
+/// // Keeps the `k` largest elements seen.
+/// fn top_k<H: DoubleEndedHeap<Item = u32>>(heap: &mut H, k: usize, xs: &[u32]) {
+///     for &x in xs {
+///         heap.push(x);
+///         if heap.len() > k {
+///             heap.pop_min();
+///         }
+///     }
+/// }
+///
+/// let mut a = MinMaxHeap::new();
+/// let mut b = IntervalHeap::new();
+/// top_k(&mut a, 2, &[5, 1, 8, 3]);
+/// top_k(&mut b, 2, &[5, 1, 8, 3]);
+///
+/// assert_eq!(a.into_vec_asc(), [5, 8]);
+/// assert_eq!(b.into_vec_asc(), [5, 8]);
+/// # }
+/// ```
+pub trait DoubleEndedHeap {
+    /// The type of the elements.
+    type Item;
+
+    /// Adds an element to the heap.
+    fn push(&mut self, element: Self::Item);
+
+    /// Gets a reference to the minimum element, if any.
+    fn peek_min(&self) -> Option<&Self::Item>;
+
+    /// Gets a reference to the maximum element, if any.
+    fn peek_max(&self) -> Option<&Self::Item>;
+
+    /// Removes the minimum element, if any.
+    fn pop_min(&mut self) -> Option<Self::Item>;
+
+    /// Removes the maximum element, if any.
+    fn pop_max(&mut self) -> Option<Self::Item>;
+
+    /// The number of elements in the heap.
+    fn len(&self) -> usize;
+
+    /// Is the heap empty?
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -0,0 +1,259 @@
+use std::vec::Vec;
+use std::fmt;
+use std::time::Instant;
+
+use super::MinMaxHeap;
+use super::compare::ByFirst;
+
+/// A queue of items that fall due at deadlines, such as a timer wheel’s
+/// pending timers or a cache’s expiry schedule.
+///
+/// Deadlines are `Instant`s by default, but any `Ord` timestamp works.
+/// [`pop_expired`](#method.pop_expired) removes everything due by a
+/// given time, and [`next_deadline`](#method.next_deadline) tells how
+/// long to sleep. A queue made with [`with_limit`](#method.with_limit)
+/// holds at most that many items, dropping the one furthest in the
+/// future when it overflows, which a double-ended heap can do cheaply.
+///
+/// # Examples
+///
+/// ```
+/// use min_max_heap::ExpiryHeap;
+///
+/// let mut timers = ExpiryHeap::with_limit(3);
+/// timers.insert(30, "flush");
+/// timers.insert(10, "ping");
+/// timers.insert(20, "retry");
+///
+/// // Over the limit, so the latest deadline goes.
+/// assert_eq!(timers.insert(15, "poll"), Some((30, "flush")));
+/// assert_eq!(timers.next_deadline(), Some(&10));
+///
+/// let due: Vec<_> = timers.pop_expired(15).collect();
+/// assert_eq!(due, [(10, "ping"), (15, "poll")]);
+/// assert_eq!(timers.len(), 1);
+/// ```
+#[derive(Clone)]
+pub struct ExpiryHeap<T, D = Instant> {
+    heap:  MinMaxHeap<(D, T), ByFirst>,
+    limit: usize,
+}
+
+impl<T, D: Ord> Default for ExpiryHeap<T, D> {
+    fn default() -> Self {
+        ExpiryHeap::new()
+    }
+}
+
+impl<T, D: Ord> ExpiryHeap<T, D> {
+    /// Creates a new, empty `ExpiryHeap` with no limit on its length.
+    ///
+    /// *O*(1).
+    pub fn new() -> Self {
+        ExpiryHeap::with_limit(usize::MAX)
+    }
+
+    /// Creates a new, empty `ExpiryHeap` that holds at most `limit`
+    /// items.
+    ///
+    /// *O*(1).
+    pub fn with_limit(limit: usize) -> Self {
+        ExpiryHeap {
+            heap: MinMaxHeap::with_comparator(ByFirst),
+            limit,
+        }
+    }
+
+    /// The maximum number of items the heap holds.
+    ///
+    /// *O*(1).
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// The number of items in the heap.
+    ///
+    /// *O*(1).
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Is the heap empty?
+    ///
+    /// *O*(1).
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Drops all items from the heap.
+    ///
+    /// *O*(*n*).
+    pub fn clear(&mut self) {
+        self.heap.clear();
+    }
+
+    /// Adds an item that falls due at `deadline`. If that takes the heap
+    /// over its limit, removes and returns the item with the latest
+    /// deadline, which may be the one just added.
+    ///
+    /// *O*(log *n*).
+    pub fn insert(&mut self, deadline: D, item: T) -> Option<(D, T)> {
+        self.heap.push_capped_max(self.limit, (deadline, item))
+    }
+
+    /// The earliest deadline, if any.
+    ///
+    /// *O*(1).
+    pub fn next_deadline(&self) -> Option<&D> {
+        self.heap.peek_min().map(|entry| &entry.0)
+    }
+
+    /// The latest deadline, if any.
+    ///
+    /// *O*(1).
+    pub fn last_deadline(&self) -> Option<&D> {
+        self.heap.peek_max().map(|entry| &entry.0)
+    }
+
+    /// Gets the item with the earliest deadline, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_next(&self) -> Option<(&D, &T)> {
+        self.heap.peek_min().map(|entry| (&entry.0, &entry.1))
+    }
+
+    /// Removes the item with the earliest deadline, if any, whether or
+    /// not it is due.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_next(&mut self) -> Option<(D, T)> {
+        self.heap.pop_min()
+    }
+
+    /// Removes the item with the latest deadline, if any.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_last(&mut self) -> Option<(D, T)> {
+        self.heap.pop_max()
+    }
+
+    /// Returns an iterator that removes the items whose deadlines are at
+    /// or before `now`, earliest first.
+    ///
+    /// Items are removed as the iterator reaches them, so if it is
+    /// dropped early, the rest stay in the heap.
+    ///
+    /// *O*(1) on creation, and *O*(log *n*) for each `next()` operation.
+    pub fn pop_expired(&mut self, now: D) -> Expired<'_, T, D> {
+        Expired { heap: self, now }
+    }
+}
+
+impl<T: fmt::Debug, D: fmt::Debug> fmt::Debug for ExpiryHeap<T, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExpiryHeap")
+         .field("entries", &self.heap.iter().collect::<Vec<_>>())
+         .field("limit", &self.limit)
+         .finish()
+    }
+}
+
+/// An iterator that removes the due items from an [`ExpiryHeap`],
+/// earliest first.
+///
+/// This type is created with
+/// [`ExpiryHeap::pop_expired`](struct.ExpiryHeap.html#method.pop_expired).
+///
+/// [`ExpiryHeap`]: struct.ExpiryHeap.html
+#[derive(Debug)]
+pub struct Expired<'a, T: 'a, D: 'a = Instant> {
+    heap: &'a mut ExpiryHeap<T, D>,
+    now:  D,
+}
+
+impl<'a, T, D: Ord> Iterator for Expired<'a, T, D> {
+    type Item = (D, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.heap.next_deadline() {
+            Some(deadline) if *deadline <= self.now => self.heap.pop_next(),
+            _ => None,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.heap.len()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate rand;
+
+    use super::*;
+    use self::rand::Rng;
+    use std::time::Duration;
+
+    #[test]
+    fn random() {
+        let mut rng = rand::thread_rng();
+        let mut heap = ExpiryHeap::with_limit(50);
+        let mut model: Vec<(u32, u32)> = Vec::new();
+        let mut now = 0;
+
+        for i in 0 .. 2000 {
+            let deadline = now + rng.gen_range(0 .. 100);
+            let evicted = heap.insert(deadline, i);
+
+            model.push((deadline, i));
+            model.sort_by_key(|entry| entry.0);
+            if model.len() > 50 {
+                let last = model.pop().unwrap();
+                assert_eq!(Some(last.0), evicted.map(|entry| entry.0));
+            } else {
+                assert_eq!(None, evicted);
+            }
+
+            if rng.gen_range(0 .. 3) == 0 {
+                now += rng.gen_range(0 .. 20);
+                let due: Vec<_> = heap.pop_expired(now).map(|entry| entry.0).collect();
+                let split = model.iter().position(|entry| entry.0 > now).unwrap_or(model.len());
+                let expected: Vec<_> = model.drain(.. split).map(|entry| entry.0).collect();
+                assert_eq!(expected, due);
+            }
+
+            assert_eq!(model.len(), heap.len());
+            assert_eq!(model.first().map(|entry| &entry.0), heap.next_deadline());
+            assert_eq!(model.last().map(|entry| &entry.0), heap.last_deadline());
+        }
+    }
+
+    #[test]
+    fn instants() {
+        let start = Instant::now();
+        let mut heap = ExpiryHeap::new();
+        heap.insert(start + Duration::from_secs(5), 'b');
+        heap.insert(start + Duration::from_secs(1), 'a');
+        heap.insert(start + Duration::from_secs(60), 'c');
+
+        assert_eq!(Some(&(start + Duration::from_secs(1))), heap.next_deadline());
+
+        let due: Vec<_> = heap.pop_expired(start + Duration::from_secs(10))
+                              .map(|entry| entry.1)
+                              .collect();
+        assert_eq!(vec!['a', 'b'], due);
+        assert_eq!(Some('c'), heap.pop_next().map(|entry| entry.1));
+    }
+
+    #[test]
+    fn early_drop() {
+        let mut heap = ExpiryHeap::new();
+        for i in 0 .. 10 {
+            heap.insert(i, i);
+        }
+
+        assert_eq!(Some((0, 0)), heap.pop_expired(5).next());
+        assert_eq!(9, heap.len());
+        assert_eq!(Some(&1), heap.next_deadline());
+    }
+}
@@ -0,0 +1,151 @@
+use core::cmp::Ordering;
+
+use super::MinMaxHeap;
+use super::compare::Compare;
+use super::index::*;
+
+/// Visits the elements of a min-max heap in sorted order without
+/// modifying it.
+///
+/// The frontier holds entries that together cover every element not yet
+/// visited. A *subtree* entry stands for a node and all its descendants,
+/// and is only created for nodes on min levels (when ascending) or max
+/// levels (when descending), so its element bounds the whole subtree. A
+/// *single* entry stands for just its node. The next element in order is
+/// therefore always the extreme entry of the frontier.
+pub struct Frontier<'a, T: 'a, C: 'a> {
+    data:      &'a [T],
+    queue:     MinMaxHeap<Entry<'a, T>, ByElement<'a, C>>,
+    ascending: bool,
+    remaining: usize,
+}
+
+struct Entry<'a, T: 'a> {
+    element: &'a T,
+    pos:     usize,
+    subtree: bool,
+}
+
+/// Orders entries by their elements.
+struct ByElement<'a, C: 'a>(&'a C);
+
+// Written out rather than derived, which would needlessly require
+// `T: Clone` and `C: Clone`.
+
+impl<'a, T, C> Clone for Frontier<'a, T, C> {
+    fn clone(&self) -> Self {
+        Frontier {
+            data:      self.data,
+            queue:     self.queue.clone(),
+            ascending: self.ascending,
+            remaining: self.remaining,
+        }
+    }
+}
+
+impl<'a, T> Clone for Entry<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for Entry<'a, T> { }
+
+impl<'a, C> Clone for ByElement<'a, C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, C> Copy for ByElement<'a, C> { }
+
+impl<'a, T, C: Compare<T>> Frontier<'a, T, C> {
+    pub fn new(data: &'a [T], cmp: &'a C, ascending: bool) -> Self {
+        let mut result = Frontier {
+            data,
+            queue: MinMaxHeap::with_comparator(ByElement(cmp)),
+            ascending,
+            remaining: data.len(),
+        };
+
+        if ascending {
+            result.add(0, true);
+        } else {
+            result.add(0, false);
+            result.add(0.child1(), true);
+            result.add(0.child2(), true);
+        }
+
+        result
+    }
+
+    fn add(&mut self, pos: usize, subtree: bool) {
+        if let Some(element) = self.data.get(pos) {
+            self.queue.push(Entry { element, pos, subtree });
+        }
+    }
+
+    fn expand(&mut self, pos: usize) {
+        self.add(pos.child1(), false);
+        self.add(pos.child2(), false);
+        self.add(pos.grandchild1(), true);
+        self.add(pos.grandchild2(), true);
+        self.add(pos.grandchild3(), true);
+        self.add(pos.grandchild4(), true);
+    }
+}
+
+impl<'a, T, C: Compare<T>> Iterator for Frontier<'a, T, C> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let entry = if self.ascending {
+            self.queue.pop_min()
+        } else {
+            self.queue.pop_max()
+        }?;
+
+        if entry.subtree {
+            self.expand(entry.pos);
+        }
+
+        self.remaining -= 1;
+        Some(entry.element)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T, C: Compare<T>> Compare<Entry<'a, T>> for ByElement<'a, C> {
+    fn compare(&self, a: &Entry<'a, T>, b: &Entry<'a, T>) -> Ordering {
+        self.0.compare(a.element, b.element)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn sorted() {
+        for len in 0 .. 100 {
+            let heap: MinMaxHeap<_> = (0 .. len).rev().collect();
+
+            let asc: Vec<_> = Frontier::new(&heap.0, &heap.1, true).cloned().collect();
+            assert_eq!((0 .. len).collect::<Vec<_>>(), asc);
+
+            let desc: Vec<_> = Frontier::new(&heap.0, &heap.1, false).cloned().collect();
+            assert_eq!((0 .. len).rev().collect::<Vec<_>>(), desc);
+        }
+    }
+
+    #[test]
+    fn duplicates() {
+        let heap = MinMaxHeap::from(vec![2, 1, 2, 1, 3, 3, 1, 2]);
+        let asc: Vec<_> = Frontier::new(&heap.0, &heap.1, true).cloned().collect();
+        assert_eq!(vec![1, 1, 1, 2, 2, 2, 3, 3], asc);
+    }
+}
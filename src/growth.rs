@@ -0,0 +1,290 @@
+use alloc::vec::Vec;
+
+use super::{Compare, Iter, MinMaxHeap, Natural};
+
+/// How a [`GrowthMinMaxHeap`] grows its storage when it is full.
+///
+/// [`GrowthMinMaxHeap`]: struct.GrowthMinMaxHeap.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Growth {
+    /// Grow as a `Vec` does, at least doubling the capacity. Pushes take
+    /// amortized *O*(log *n*) time, but up to half of the capacity may go
+    /// unused.
+    Double,
+    /// Grow by exactly as much as needed. Nothing is over-allocated, but
+    /// a push onto a full heap takes *O*(*n*) time to copy the elements.
+    Exact,
+    /// Grow the capacity by the given percentage, or by as much as
+    /// needed if that is more. For example, `Percent(50)` grows by a
+    /// factor of 1.5, leaving at most a third of the capacity unused.
+    Percent(usize),
+    /// Grow the capacity to a multiple of the given number of elements,
+    /// leaving fewer than that many unused. A chunk of 0 is treated as
+    /// 1.
+    Chunk(usize),
+}
+
+impl Growth {
+    /// The capacity to grow to from `capacity` to hold `needed` elements,
+    /// or `None` to let the `Vec` decide.
+    fn target(self, capacity: usize, needed: usize) -> Option<usize> {
+        match self {
+            Growth::Double     => None,
+            Growth::Exact      => Some(needed),
+            Growth::Percent(p) => {
+                let grown = capacity.saturating_add(capacity.saturating_mul(p) / 100);
+                Some(grown.max(needed))
+            }
+            Growth::Chunk(c)   => {
+                let c = c.max(1);
+                Some(needed.saturating_add(c - 1) / c * c)
+            }
+        }
+    }
+}
+
+/// A min-max-heap that grows its storage according to a [`Growth`]
+/// policy, rather than always as a `Vec` does, so that how much memory
+/// it over-allocates is predictable.
+///
+/// The policy applies when pushing or extending; the allocator may
+/// still round the capacity up.
+///
+/// # Examples
+///
+/// ```
+/// use min_max_heap::{Growth, GrowthMinMaxHeap};
+///
+/// let mut heap = GrowthMinMaxHeap::new(Growth::Chunk(16));
+///
+/// heap.extend(0 .. 20);
+/// assert_eq!(heap.capacity(), 32);
+///
+/// assert_eq!(heap.pop_min(), Some(0));
+/// assert_eq!(heap.pop_max(), Some(19));
+/// ```
+///
+/// [`Growth`]: enum.Growth.html
+#[derive(Clone, Debug)]
+pub struct GrowthMinMaxHeap<T, C = Natural> {
+    heap:   MinMaxHeap<T, C>,
+    growth: Growth,
+}
+
+impl<T: Ord> GrowthMinMaxHeap<T> {
+    /// Creates a new, empty `GrowthMinMaxHeap` that grows according to
+    /// `growth`.
+    ///
+    /// *O*(1).
+    pub fn new(growth: Growth) -> Self {
+        GrowthMinMaxHeap::with_comparator(growth, Natural)
+    }
+}
+
+impl<T, C> GrowthMinMaxHeap<T, C> {
+    /// Creates a new, empty `GrowthMinMaxHeap` ordered by `cmp` that
+    /// grows according to `growth`.
+    ///
+    /// *O*(1).
+    pub fn with_comparator(growth: Growth, cmp: C) -> Self {
+        GrowthMinMaxHeap::from_heap(growth, MinMaxHeap::with_comparator(cmp))
+    }
+
+    /// Wraps an existing heap, which keeps its current capacity, so that
+    /// it grows according to `growth` from now on.
+    ///
+    /// *O*(1).
+    pub fn from_heap(growth: Growth, heap: MinMaxHeap<T, C>) -> Self {
+        GrowthMinMaxHeap { heap, growth }
+    }
+
+    /// The policy applied when the heap grows.
+    ///
+    /// *O*(1).
+    pub fn growth(&self) -> Growth {
+        self.growth
+    }
+
+    /// Changes the policy applied when the heap grows.
+    ///
+    /// *O*(1).
+    pub fn set_growth(&mut self, growth: Growth) {
+        self.growth = growth;
+    }
+
+    /// The number of elements in the heap.
+    ///
+    /// *O*(1).
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Is the heap empty?
+    ///
+    /// *O*(1).
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// The number of elements the heap can hold without reallocating.
+    ///
+    /// *O*(1).
+    pub fn capacity(&self) -> usize {
+        self.heap.capacity()
+    }
+
+    /// Makes room for at least `additional` more elements, growing
+    /// according to the policy if the heap does not have room already.
+    ///
+    /// *O*(*n*).
+    pub fn reserve(&mut self, additional: usize) {
+        let capacity = self.heap.capacity();
+        let needed = self.heap.len().saturating_add(additional);
+
+        if needed > capacity {
+            match self.growth.target(capacity, needed) {
+                Some(target) => self.heap.reserve_exact(target - self.heap.len()),
+                None         => self.heap.reserve(additional),
+            }
+        }
+    }
+
+    /// Discards extra capacity.
+    ///
+    /// *O*(*n*).
+    pub fn shrink_to_fit(&mut self) {
+        self.heap.shrink_to_fit();
+    }
+
+    /// Drops all elements from the heap.
+    ///
+    /// *O*(*n*).
+    pub fn clear(&mut self) {
+        self.heap.clear();
+    }
+
+    /// Returns an iterator over the elements of the heap in arbitrary
+    /// order.
+    ///
+    /// *O*(1) on creation, and *O*(1) for each `next()` operation.
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.heap.iter()
+    }
+
+    /// Converts into the underlying `MinMaxHeap`, which grows as a `Vec`
+    /// does.
+    ///
+    /// *O*(1).
+    pub fn into_heap(self) -> MinMaxHeap<T, C> {
+        self.heap
+    }
+}
+
+impl<T, C: Compare<T>> GrowthMinMaxHeap<T, C> {
+    /// Adds an element to the heap, growing according to the policy if
+    /// the heap is full.
+    ///
+    /// *O*(log *n*), except when the heap grows, which takes *O*(*n*).
+    pub fn push(&mut self, element: T) {
+        self.reserve(1);
+        self.heap.push(element);
+    }
+
+    /// Gets a reference to the minimum element, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_min(&self) -> Option<&T> {
+        self.heap.peek_min()
+    }
+
+    /// Gets a reference to the maximum element, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_max(&self) -> Option<&T> {
+        self.heap.peek_max()
+    }
+
+    /// Removes the minimum element, if any.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_min(&mut self) -> Option<T> {
+        self.heap.pop_min()
+    }
+
+    /// Removes the maximum element, if any.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_max(&mut self) -> Option<T> {
+        self.heap.pop_max()
+    }
+
+    /// Returns an ascending (sorted) vector, reusing the heap’s storage.
+    ///
+    /// *O*(*n* log *n*).
+    pub fn into_vec_asc(self) -> Vec<T> {
+        self.heap.into_vec_asc()
+    }
+
+    /// Returns a descending (sorted) vector, reusing the heap’s storage.
+    ///
+    /// *O*(*n* log *n*).
+    pub fn into_vec_desc(self) -> Vec<T> {
+        self.heap.into_vec_desc()
+    }
+}
+
+impl<T, C: Compare<T>> Extend<T> for GrowthMinMaxHeap<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for element in iter {
+            self.push(element);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn capacities(growth: Growth) -> Vec<usize> {
+        let mut heap = GrowthMinMaxHeap::new(growth);
+        let mut result = Vec::new();
+
+        for i in 0 .. 40 {
+            heap.push(i);
+            if result.last() != Some(&heap.capacity()) {
+                result.push(heap.capacity());
+            }
+        }
+
+        assert_eq!((0 .. 40).collect::<Vec<_>>(), heap.into_vec_asc());
+        result
+    }
+
+    #[test]
+    fn exact() {
+        assert_eq!((1 ..= 40).collect::<Vec<_>>(), capacities(Growth::Exact));
+    }
+
+    #[test]
+    fn percent() {
+        assert_eq!(vec![1, 2, 3, 4, 6, 9, 13, 19, 28, 42], capacities(Growth::Percent(50)));
+    }
+
+    #[test]
+    fn chunk() {
+        assert_eq!(vec![16, 32, 48], capacities(Growth::Chunk(16)));
+        assert_eq!(capacities(Growth::Exact), capacities(Growth::Chunk(0)));
+    }
+
+    #[test]
+    fn extend() {
+        let mut heap = GrowthMinMaxHeap::new(Growth::Exact);
+        heap.extend(0 .. 10);
+        assert_eq!(10, heap.capacity());
+        heap.extend(vec![20, 10]);
+        assert_eq!(12, heap.capacity());
+        assert_eq!(Some(&20), heap.peek_max());
+    }
+}
@@ -1,13 +1,27 @@
-use std::{mem, ptr};
-use std::mem::ManuallyDrop;
+use core::{mem, ptr};
+use core::mem::ManuallyDrop;
 
+use super::compare::Compare;
 use super::index::*;
+use super::stats;
 
 // From std::collections::BinaryHeap:
-pub struct Hole<'a, T: 'a> {
+pub struct Hole<'a, T: 'a, K: Track<T> = ()> {
     data: &'a mut [T],
     elt: ManuallyDrop<T>,
     pos: usize,
+    track: K,
+}
+
+/// Told where each element lands as a `Hole` moves elements about, for
+/// heaps that keep track of their elements’ positions.
+pub trait Track<T> {
+    fn moved(&mut self, element: &T, pos: usize);
+}
+
+impl<T> Track<T> for () {
+    #[inline]
+    fn moved(&mut self, _: &T, _: usize) {}
 }
 
 enum Generation {
@@ -20,13 +34,24 @@ impl<'a, T> Hole<'a, T> {
     ///
     /// Caller must ensure that `pos` is a valid index in `data`.
     pub unsafe fn new(data: &'a mut [T], pos: usize) -> Self {
+        Hole::with_track(data, pos, ())
+    }
+}
+
+impl<'a, T, K: Track<T>> Hole<'a, T, K> {
+    /// Create a new Hole at index `pos` that tells `track` where the
+    /// elements it moves end up.
+    ///
+    /// Caller must ensure that `pos` is a valid index in `data`.
+    pub unsafe fn with_track(data: &'a mut [T], pos: usize, track: K) -> Self {
         debug_assert!(pos < data.len());
+        stats::sift();
         let elt = ptr::read(data.get_unchecked(pos));
-        Hole { data, elt: ManuallyDrop::new(elt), pos }
+        Hole { data, elt: ManuallyDrop::new(elt), pos, track }
     }
 
     #[inline]
-    fn pos(&self) -> usize {
+    pub fn pos(&self) -> usize {
         self.pos
     }
 
@@ -37,7 +62,7 @@ impl<'a, T> Hole<'a, T> {
     }
 
     #[inline]
-    pub fn get_parent(&mut self) -> Option<HoleSwap<'a, '_, T>> {
+    pub fn get_parent(&mut self) -> Option<HoleSwap<'a, '_, T, K>> {
         if self.pos().has_parent() {
             // SAFETY: parent is a valid index and not equal to `pos`
             Some(unsafe { HoleSwap::new(self, self.pos().parent()) })
@@ -47,7 +72,7 @@ impl<'a, T> Hole<'a, T> {
     }
 
     #[inline]
-    fn get_grandparent(&mut self) -> Option<HoleSwap<'a, '_, T>> {
+    fn get_grandparent(&mut self) -> Option<HoleSwap<'a, '_, T, K>> {
         if self.pos().has_grandparent() {
             // SAFETY: grandparent is a valid index and not equal to `pos`
             Some(unsafe { HoleSwap::new(self, self.pos().grandparent()) })
@@ -63,7 +88,7 @@ impl<'a, T> Hole<'a, T> {
 
     #[inline]
     fn best_child_or_grandchild<F>(&mut self, f: F)
-        -> Option<(HoleSwap<'a, '_, T>, Generation)>
+        -> Option<(HoleSwap<'a, '_, T, K>, Generation)>
     where
         F: Fn(&T, &T) -> bool,
     {
@@ -76,7 +101,7 @@ impl<'a, T> Hole<'a, T> {
         {
             let mut check = |index, generation| {
                 data.get(index).map(|candidate| {
-                    if f(candidate, element) {
+                    if stats::compared(f(candidate, element)) {
                         best = Some((index, generation));
                         element = candidate;
                     }
@@ -108,7 +133,7 @@ impl<'a, T> Hole<'a, T> {
                 Generation::Grandchild => {
                     // SAFETY: `pos` has a parent since it has a grandparent
                     let mut parent = unsafe { HoleSwap::new(self, self.pos().parent()) };
-                    if f(parent.other_element(), parent.hole_element()) {
+                    if stats::compared(f(parent.other_element(), parent.hole_element())) {
                         parent.swap_with();
                     }
                 }
@@ -118,30 +143,48 @@ impl<'a, T> Hole<'a, T> {
     }
 }
 
-impl<'a, T: Ord> Hole<'a, T> {
-    pub fn bubble_up(&mut self) {
+impl<'a, T, K: Track<T>> Hole<'a, T, K> {
+    pub fn bubble_up<C: Compare<T>>(&mut self, cmp: &C) {
         if self.on_min_level() {
             match self.get_parent() {
-                Some(parent) if parent.hole_element() > parent.other_element() => {
+                Some(parent) if stats::compared(cmp.compares_gt(parent.hole_element(),
+                                                                parent.other_element())) => {
                     parent.move_to();
-                    self.bubble_up_max();
+                    self.bubble_up_max(cmp);
                 }
-                _ => self.bubble_up_min(),
+                _ => self.bubble_up_min(cmp),
             }
         } else {
             match self.get_parent() {
-                Some(parent) if parent.hole_element() < parent.other_element() => {
+                Some(parent) if stats::compared(cmp.compares_lt(parent.hole_element(),
+                                                                parent.other_element())) => {
                     parent.move_to();
-                    self.bubble_up_min();
+                    self.bubble_up_min(cmp);
                 }
-                _ => self.bubble_up_max(),
+                _ => self.bubble_up_max(cmp),
+            }
+        }
+    }
+
+    /// Moves the element to the top of the max levels (if `max`) or the
+    /// min levels without comparing it, for an element known to be at
+    /// least (or at most) every other element in the heap.
+    #[cfg(feature = "alloc")]
+    pub fn bubble_up_extreme(&mut self, max: bool) {
+        if self.on_min_level() == max {
+            if let Some(parent) = self.get_parent() {
+                parent.move_to();
             }
         }
+
+        while let Some(grandparent) = self.get_grandparent() {
+            grandparent.move_to();
+        }
     }
 
     fn bubble_up_grandparent<F>(&mut self, f: F) where F: Fn(&T, &T) -> bool {
         while let Some(grandparent) = self.get_grandparent() {
-            if f(grandparent.hole_element(), grandparent.other_element()) {
+            if stats::compared(f(grandparent.hole_element(), grandparent.other_element())) {
                 grandparent.move_to();
             } else {
                 return;
@@ -149,53 +192,54 @@ impl<'a, T: Ord> Hole<'a, T> {
         }
     }
 
-    fn bubble_up_min(&mut self) {
-        self.bubble_up_grandparent(PartialOrd::lt);
+    fn bubble_up_min<C: Compare<T>>(&mut self, cmp: &C) {
+        self.bubble_up_grandparent(|a, b| cmp.compares_lt(a, b));
     }
 
-    fn bubble_up_max(&mut self) {
-        self.bubble_up_grandparent(PartialOrd::gt);
+    fn bubble_up_max<C: Compare<T>>(&mut self, cmp: &C) {
+        self.bubble_up_grandparent(|a, b| cmp.compares_gt(a, b));
     }
 
-    pub fn trickle_down(&mut self) {
+    pub fn trickle_down<C: Compare<T>>(&mut self, cmp: &C) {
         if self.on_min_level() {
-            self.trickle_down_min();
+            self.trickle_down_min(cmp);
         } else {
-            self.trickle_down_max();
+            self.trickle_down_max(cmp);
         }
     }
 
-    pub fn trickle_down_min(&mut self) {
-        self.trickle_down_best(PartialOrd::lt);
+    pub fn trickle_down_min<C: Compare<T>>(&mut self, cmp: &C) {
+        self.trickle_down_best(|a, b| cmp.compares_lt(a, b));
     }
 
-    pub fn trickle_down_max(&mut self) {
-        self.trickle_down_best(PartialOrd::gt);
+    pub fn trickle_down_max<C: Compare<T>>(&mut self, cmp: &C) {
+        self.trickle_down_best(|a, b| cmp.compares_gt(a, b));
     }
 }
 
-impl<'a, T> Drop for Hole<'a, T> {
+impl<'a, T, K: Track<T>> Drop for Hole<'a, T, K> {
     fn drop(&mut self) {
         unsafe {
             // SAFETY: `elt` is being moved into the hole
             let elt = ptr::read(&*self.elt);
             // SAFETY: `pos` is a valid index in `data` and is a hole
             ptr::write(self.data.get_unchecked_mut(self.pos()), elt);
+            self.track.moved(self.data.get_unchecked(self.pos()), self.pos());
         }
     }
 }
 
 /// A hole, along with a potential new position to move it to.
 /// This replaces some unsafe blocks with safety requirements on the constructor.
-pub struct HoleSwap<'a, 'b, T> {
-    hole: &'b mut Hole<'a, T>,
+pub struct HoleSwap<'a, 'b, T, K: Track<T> = ()> {
+    hole: &'b mut Hole<'a, T, K>,
     index: usize,
 }
 
-impl<'a, 'b, T> HoleSwap<'a, 'b, T> {
+impl<'a, 'b, T, K: Track<T>> HoleSwap<'a, 'b, T, K> {
     /// Caller must ensure that `index` is a valid index in `data`
     /// and not equal to `pos`.
-    unsafe fn new(hole: &'b mut Hole<'a, T>, index: usize) -> Self {
+    unsafe fn new(hole: &'b mut Hole<'a, T, K>, index: usize) -> Self {
         debug_assert!(index != hole.pos());
         debug_assert!(index < hole.data.len());
         HoleSwap { hole, index }
@@ -216,24 +260,28 @@ impl<'a, 'b, T> HoleSwap<'a, 'b, T> {
     /// and move the hole to where `other_element()` was.
     /// This invalidates the `HoleSwap`.
     pub fn move_to(self) {
+        stats::moved(self.hole.pos, self.index);
         unsafe {
             // SAFETY: `index` is a valid index in `data` and not a hole
             let elt = ptr::read(self.other_element());
             // SAFETY: `pos` is a valid index in `data` and a hole
             ptr::write(self.hole.data.get_unchecked_mut(self.hole.pos()), elt);
+            self.hole.track.moved(self.hole.data.get_unchecked(self.hole.pos()), self.hole.pos());
         }
         self.hole.pos = self.index;
     }
 
     /// Swaps `hole_element()` with `other_element()`, without moving the hole
     pub fn swap_with(&mut self) {
+        stats::swap();
         // SAFETY: `index` is a valid index in `data` and not a hole
         let other_element = unsafe { self.hole.data.get_unchecked_mut(self.index) };
         mem::swap(other_element, &mut self.hole.elt);
+        self.hole.track.moved(other_element, self.index);
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "alloc"))]
 mod test {
     use super::*;
 
@@ -15,6 +15,9 @@ pub trait HeapIndex {
 
     #[allow(clippy::wrong_self_convention)]
     fn is_min_level(self) -> bool;
+
+    #[cfg(feature = "alloc")]
+    fn subtree_len(self, len: usize) -> usize;
 }
 
 impl HeapIndex for usize {
@@ -40,6 +43,55 @@ impl HeapIndex for usize {
     fn is_min_level(self) -> bool {
         (self + 1).leading_zeros() & 1 == 1
     }
+
+    #[cfg(feature = "alloc")]
+    fn subtree_len(self, len: usize) -> usize {
+        let mut result = 0;
+        let mut first = self;
+        let mut last = self;
+
+        while first < len {
+            result += last.min(len - 1) - first + 1;
+            first = first.child1();
+            last = last.child2();
+        }
+
+        result
+    }
+}
+
+/// Calls `f(pos, rank)` for each position below `len`, in order, where
+/// `rank` is the index among elements sorted in ascending order of the
+/// one that belongs at `pos` in a min-max-heap.
+///
+/// A node on a min level takes the smallest element of its subtree and
+/// a node on a max level the largest, with the rest split between its
+/// children’s subtrees in order. So `start`, the smallest rank in the
+/// current node’s subtree, moves past that subtree to the next node on
+/// the same level, and past the ancestors that separate the two. Those
+/// are one for each level up to their nearest common ancestor, from
+/// the left node’s side on max levels and the right node’s on min
+/// levels.
+#[cfg(feature = "alloc")]
+pub fn sorted_ranks<F: FnMut(usize, usize)>(len: usize, mut f: F) {
+    let height = (usize::BITS - len.leading_zeros()) as usize;
+
+    for depth in 0 .. height {
+        let min_level = depth % 2 == 0;
+        // Each subtree here has `below` full levels, counting its root,
+        // above the bottom one, which may be partial.
+        let below = height - 1 - depth;
+        let full = (1 << below) - 1;
+        // Only the min ancestors come before the leftmost subtree.
+        let mut start = depth - depth / 2;
+
+        for pos in (1 << depth) - 1 .. ((1 << (depth + 1)) - 1).min(len) {
+            let first_leaf = ((pos + 1) << below) - 1;
+            let size = full + (1 << below).min(len - first_leaf.min(len));
+            f(pos, if min_level { start } else { start + size - 1 });
+            start += size + (pos + 1).trailing_ones() as usize;
+        }
+    }
 }
 
 //                       0
@@ -47,9 +99,11 @@ impl HeapIndex for usize {
 //      3         4             5           6
 //    7   8     9   10       11   12     13   14
 
-#[cfg(test)]
+#[cfg(all(test, feature = "alloc"))]
 mod test {
     use super::*;
+    use alloc::vec;
+    use alloc::vec::Vec;
 
     #[test]
     fn t_parent() {
@@ -144,4 +198,34 @@ mod test {
         assert!(!14.is_min_level());
         assert!(15.is_min_level());
     }
+
+    #[test]
+    fn t_subtree_len() {
+        assert_eq!(0, 0.subtree_len(0));
+        assert_eq!(1, 0.subtree_len(1));
+        assert_eq!(15, 0.subtree_len(15));
+        assert_eq!(7, 1.subtree_len(15));
+        assert_eq!(6, 1.subtree_len(10));
+        assert_eq!(3, 2.subtree_len(10));
+        assert_eq!(4, 2.subtree_len(12));
+        assert_eq!(1, 5.subtree_len(10));
+        assert_eq!(0, 10.subtree_len(10));
+    }
+
+    #[test]
+    fn t_sorted_ranks() {
+        let ranks = |len| {
+            let mut result = vec![];
+            sorted_ranks(len, |pos, rank| {
+                assert_eq!(result.len(), pos);
+                result.push(rank);
+            });
+            result
+        };
+
+        assert_eq!(Vec::<usize>::new(), ranks(0));
+        assert_eq!(vec![0], ranks(1));
+        assert_eq!(vec![0, 1, 2], ranks(3));
+        assert_eq!(vec![0, 6, 9, 1, 4, 7, 8, 2, 3, 5], ranks(10));
+    }
 }
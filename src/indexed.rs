@@ -0,0 +1,531 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::slice;
+
+use super::{Compare, MinMaxHeap, Natural, find_max_slice};
+use super::hole::{Hole, Track};
+
+/// A min-max-heap whose elements can be found again after insertion.
+///
+/// Pushing an element returns a [`Handle`] that stays valid until the
+/// element leaves the heap, and through which the element can be read,
+/// replaced or removed in *O*(log *n*) time. This is what algorithms
+/// such as Dijkstra’s and A\* need in order to reprioritize queued
+/// entries.
+///
+/// Internally, every element sits next to the slot of its handle, and
+/// each slot records where its element currently is in the heap. The
+/// sift operations keep the two in step. A slot is reused after its
+/// element is removed, but the handle also records how many times the
+/// slot has been reused, so a stale handle never refers to a newer
+/// element.
+///
+/// # Examples
+///
+/// ```
+/// use min_max_heap::IndexedMinMaxHeap;
+///
+/// let mut heap = IndexedMinMaxHeap::new();
+/// let a = heap.push(5);
+/// let b = heap.push(8);
+/// let c = heap.push(3);
+///
+/// assert_eq!(heap.peek_min(), Some((c, &3)));
+///
+/// heap.update(b, 1);
+/// assert_eq!(heap.peek_min(), Some((b, &1)));
+///
+/// assert_eq!(heap.remove(c), Some(3));
+/// assert_eq!(heap.remove(c), None);
+/// assert_eq!(heap.pop_max(), Some((a, 5)));
+/// ```
+///
+/// [`Handle`]: struct.Handle.html
+#[derive(Clone, Debug)]
+pub struct IndexedMinMaxHeap<T> {
+    // In heap order, each element with the index of its slot.
+    entries: Vec<(T, usize)>,
+    slots:   Vec<Slot>,
+    free:    Vec<usize>,
+}
+
+#[derive(Clone, Debug)]
+struct Slot {
+    // The position of the slot’s element in `entries`, if it has one.
+    pos:        Option<usize>,
+    generation: usize,
+}
+
+// Orders the entries by their elements alone.
+struct ByElement;
+
+impl<T: Ord> Compare<(T, usize)> for ByElement {
+    fn compare(&self, a: &(T, usize), b: &(T, usize)) -> Ordering {
+        a.0.cmp(&b.0)
+    }
+}
+
+// Keeps each slot’s position in step as the sift code moves entries.
+struct Positions<'a>(&'a mut [Slot]);
+
+impl<T> Track<(T, usize)> for Positions<'_> {
+    #[inline]
+    fn moved(&mut self, entry: &(T, usize), pos: usize) {
+        self.0[entry.1].pos = Some(pos);
+    }
+}
+
+/// Refers to an element of an [`IndexedMinMaxHeap`].
+///
+/// Handles are returned by
+/// [`IndexedMinMaxHeap::push`](struct.IndexedMinMaxHeap.html#method.push),
+/// and are only meaningful for the heap that returned them.
+///
+/// [`IndexedMinMaxHeap`]: struct.IndexedMinMaxHeap.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Handle {
+    slot:       usize,
+    generation: usize,
+}
+
+impl<T> Default for IndexedMinMaxHeap<T> {
+    fn default() -> Self {
+        IndexedMinMaxHeap::new()
+    }
+}
+
+impl<T> IndexedMinMaxHeap<T> {
+    /// Creates a new, empty `IndexedMinMaxHeap`.
+    ///
+    /// *O*(1).
+    pub fn new() -> Self {
+        IndexedMinMaxHeap {
+            entries: Vec::new(),
+            slots:   Vec::new(),
+            free:    Vec::new(),
+        }
+    }
+
+    /// Creates a new, empty `IndexedMinMaxHeap` with space allocated to
+    /// hold `len` elements.
+    ///
+    /// *O*(*n*).
+    pub fn with_capacity(len: usize) -> Self {
+        IndexedMinMaxHeap {
+            entries: Vec::with_capacity(len),
+            slots:   Vec::with_capacity(len),
+            free:    Vec::new(),
+        }
+    }
+
+    /// The number of elements in the heap.
+    ///
+    /// *O*(1).
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Is the heap empty?
+    ///
+    /// *O*(1).
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Does `handle` refer to an element of the heap?
+    ///
+    /// *O*(1).
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.position(handle).is_some()
+    }
+
+    /// Gets a reference to the element that `handle` refers to, if it
+    /// is still in the heap.
+    ///
+    /// *O*(1).
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        self.position(handle).map(|pos| &self.entries[pos].0)
+    }
+
+    /// Returns an iterator over the elements and their handles in
+    /// arbitrary order.
+    ///
+    /// *O*(1) on creation, and *O*(1) for each `next()` operation.
+    pub fn iter(&self) -> IndexedIter<'_, T> {
+        IndexedIter {
+            entries: self.entries.iter(),
+            slots:   &self.slots,
+        }
+    }
+
+    /// Drops all elements from the heap, invalidating all handles.
+    ///
+    /// *O*(*n*).
+    pub fn clear(&mut self) {
+        for (_, slot) in self.entries.drain(..) {
+            self.slots[slot].pos = None;
+            self.slots[slot].generation += 1;
+            self.free.push(slot);
+        }
+    }
+
+    fn position(&self, handle: Handle) -> Option<usize> {
+        self.slots.get(handle.slot)
+            .filter(|slot| slot.generation == handle.generation)
+            .and_then(|slot| slot.pos)
+    }
+
+    fn handle(&self, pos: usize) -> Handle {
+        let slot = self.entries[pos].1;
+        Handle {
+            slot,
+            generation: self.slots[slot].generation,
+        }
+    }
+
+    fn expect_position(&self, handle: Handle) -> usize {
+        self.position(handle)
+            .expect("IndexedMinMaxHeap: handle does not refer to an element")
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.entries.swap(i, j);
+        self.slots[self.entries[i].1].pos = Some(i);
+        self.slots[self.entries[j].1].pos = Some(j);
+    }
+
+    /// Removes the element at `pos`, frees its slot, and returns it
+    /// with its handle, without restoring the heap property at `pos`.
+    fn take(&mut self, pos: usize) -> (Handle, T) {
+        let handle = self.handle(pos);
+        let last = self.entries.len() - 1;
+        self.swap(pos, last);
+
+        let (element, slot) = self.entries.pop().unwrap();
+        self.slots[slot].pos = None;
+        self.slots[slot].generation += 1;
+        self.free.push(slot);
+
+        (handle, element)
+    }
+}
+
+impl<T: Ord> IndexedMinMaxHeap<T> {
+    /// Adds an element to the heap, returning a handle to it.
+    ///
+    /// *O*(log *n*).
+    pub fn push(&mut self, element: T) -> Handle {
+        let pos = self.entries.len();
+
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.slots[slot].pos = Some(pos);
+                slot
+            }
+            None => {
+                self.slots.push(Slot { pos: Some(pos), generation: 0 });
+                self.slots.len() - 1
+            }
+        };
+
+        self.entries.push((element, slot));
+        let handle = self.handle(pos);
+        self.hole(pos).bubble_up(&ByElement);
+        handle
+    }
+
+    /// Gets the minimum element and its handle, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_min(&self) -> Option<(Handle, &T)> {
+        self.entries.first().map(|entry| (self.handle(0), &entry.0))
+    }
+
+    /// Gets the maximum element and its handle, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_max(&self) -> Option<(Handle, &T)> {
+        self.find_max().map(|pos| (self.handle(pos), &self.entries[pos].0))
+    }
+
+    /// Removes the minimum element, if any, returning it with its
+    /// now-invalid handle.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_min(&mut self) -> Option<(Handle, T)> {
+        if self.entries.is_empty() {
+            None
+        } else {
+            Some(self.remove_at(0))
+        }
+    }
+
+    /// Removes the maximum element, if any, returning it with its
+    /// now-invalid handle.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_max(&mut self) -> Option<(Handle, T)> {
+        self.find_max().map(|pos| self.remove_at(pos))
+    }
+
+    /// Removes the element that `handle` refers to, if it is still in
+    /// the heap.
+    ///
+    /// *O*(log *n*).
+    pub fn remove(&mut self, handle: Handle) -> Option<T> {
+        self.position(handle).map(|pos| self.remove_at(pos).1)
+    }
+
+    /// Replaces the element that `handle` refers to, returning the old
+    /// element. The handle remains valid.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `handle` does not refer to an element of the heap.
+    ///
+    /// *O*(log *n*).
+    pub fn update(&mut self, handle: Handle, element: T) -> T {
+        self.update_with(handle, |old| core::mem::replace(old, element))
+    }
+
+    /// Applies `f` to the element that `handle` refers to, then restores
+    /// the heap property.
+    ///
+    /// Panics if `handle` does not refer to an element of the heap.
+    pub(crate) fn update_with<R, F>(&mut self, handle: Handle, f: F) -> R
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        let pos = self.expect_position(handle);
+        let result = f(&mut self.entries[pos].0);
+        self.sift(pos);
+        result
+    }
+
+    /// Replaces the element that `handle` refers to with one that is no
+    /// smaller, returning the old element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `handle` does not refer to an element of the heap, or
+    /// if `element` is smaller than the element it replaces.
+    ///
+    /// *O*(log *n*).
+    pub fn increase_key(&mut self, handle: Handle, element: T) -> T {
+        let pos = self.expect_position(handle);
+        assert!(element >= self.entries[pos].0,
+                "IndexedMinMaxHeap::increase_key: new element is smaller");
+        self.update(handle, element)
+    }
+
+    /// Replaces the element that `handle` refers to with one that is no
+    /// larger, returning the old element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `handle` does not refer to an element of the heap, or
+    /// if `element` is larger than the element it replaces.
+    ///
+    /// *O*(log *n*).
+    pub fn decrease_key(&mut self, handle: Handle, element: T) -> T {
+        let pos = self.expect_position(handle);
+        assert!(element <= self.entries[pos].0,
+                "IndexedMinMaxHeap::decrease_key: new element is larger");
+        self.update(handle, element)
+    }
+
+    /// Converts into a plain `MinMaxHeap`, discarding the handles.
+    ///
+    /// *O*(*n*).
+    pub fn into_heap(self) -> MinMaxHeap<T> {
+        // The entries are already in heap order.
+        MinMaxHeap(self.entries.into_iter().map(|(element, _)| element).collect(), Natural)
+    }
+
+    fn find_max(&self) -> Option<usize> {
+        find_max_slice(&self.entries, &ByElement)
+    }
+
+    fn remove_at(&mut self, pos: usize) -> (Handle, T) {
+        let result = self.take(pos);
+        if pos < self.entries.len() {
+            self.sift(pos);
+        }
+        result
+    }
+
+    /// Restores the heap after the element at `pos` has been changed
+    /// arbitrarily.
+    fn sift(&mut self, pos: usize) {
+        self.hole(pos).bubble_up(&ByElement);
+        self.hole(pos).trickle_down(&ByElement);
+    }
+
+    fn hole(&mut self, pos: usize) -> Hole<'_, (T, usize), Positions<'_>> {
+        // SAFETY: the callers ensure `pos < self.entries.len()`
+        unchecked! {
+            Hole::with_track(&mut self.entries, pos, Positions(&mut self.slots))
+        }
+    }
+}
+
+impl<T: Ord> Extend<T> for IndexedMinMaxHeap<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for element in iter {
+            self.push(element);
+        }
+    }
+}
+
+/// A borrowed iterator over the elements of an [`IndexedMinMaxHeap`] and
+/// their handles, in arbitrary order.
+///
+/// This type is created with
+/// [`IndexedMinMaxHeap::iter`](struct.IndexedMinMaxHeap.html#method.iter).
+///
+/// [`IndexedMinMaxHeap`]: struct.IndexedMinMaxHeap.html
+#[derive(Clone, Debug)]
+pub struct IndexedIter<'a, T: 'a> {
+    entries: slice::Iter<'a, (T, usize)>,
+    slots:   &'a [Slot],
+}
+
+impl<'a, T> Iterator for IndexedIter<'a, T> {
+    type Item = (Handle, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.next().map(|&(ref element, slot)| {
+            let handle = Handle {
+                slot,
+                generation: self.slots[slot].generation,
+            };
+            (handle, element)
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IndexedIter<'a, T> { }
+
+#[cfg(test)]
+mod test {
+    extern crate rand;
+
+    use super::*;
+    use self::rand::Rng;
+
+    fn check<T: Ord + Clone + std::fmt::Debug>(heap: &IndexedMinMaxHeap<T>,
+                                               model: &[(Handle, T)]) {
+        heap.clone().into_heap().assert_valid();
+        assert_eq!(model.len(), heap.len());
+
+        for (handle, element) in model {
+            assert_eq!(Some(element), heap.get(*handle));
+        }
+
+        assert_eq!(model.iter().map(|p| &p.1).min(), heap.peek_min().map(|p| p.1));
+        assert_eq!(model.iter().map(|p| &p.1).max(), heap.peek_max().map(|p| p.1));
+
+        for (handle, element) in heap.iter() {
+            assert!(model.contains(&(handle, element.clone())));
+        }
+    }
+
+    #[test]
+    fn random() {
+        let mut rng = rand::thread_rng();
+        let mut heap = IndexedMinMaxHeap::new();
+        let mut model: Vec<(Handle, u32)> = Vec::new();
+        let mut dead = Vec::new();
+
+        for _ in 0 .. 2000 {
+            match rng.gen_range(0 .. 7) {
+                0 | 1 => {
+                    let x = rng.gen_range(0 .. 100);
+                    model.push((heap.push(x), x));
+                }
+                2 if !model.is_empty() => {
+                    let i = rng.gen_range(0 .. model.len());
+                    let (handle, x) = model.swap_remove(i);
+                    assert_eq!(Some(x), heap.remove(handle));
+                    dead.push(handle);
+                }
+                3 if !model.is_empty() => {
+                    let i = rng.gen_range(0 .. model.len());
+                    let x = rng.gen_range(0 .. 100);
+                    assert_eq!(model[i].1, heap.update(model[i].0, x));
+                    model[i].1 = x;
+                }
+                4 if !model.is_empty() => {
+                    let (handle, x) = heap.pop_min().unwrap();
+                    let i = model.iter().position(|p| p.0 == handle).unwrap();
+                    assert_eq!(model.swap_remove(i).1, x);
+                    dead.push(handle);
+                }
+                5 if !model.is_empty() => {
+                    let (handle, x) = heap.pop_max().unwrap();
+                    let i = model.iter().position(|p| p.0 == handle).unwrap();
+                    assert_eq!(model.swap_remove(i).1, x);
+                    dead.push(handle);
+                }
+                _ => {}
+            }
+
+            check(&heap, &model);
+        }
+
+        for handle in dead {
+            assert!(!heap.contains(handle));
+            assert_eq!(None, heap.get(handle));
+            assert_eq!(None, heap.remove(handle));
+        }
+    }
+
+    #[test]
+    fn keys() {
+        let mut heap = IndexedMinMaxHeap::new();
+        let a = heap.push(10);
+        let b = heap.push(20);
+        let c = heap.push(30);
+
+        assert_eq!(10, heap.increase_key(a, 40));
+        assert_eq!(Some((a, &40)), heap.peek_max());
+        assert_eq!(30, heap.decrease_key(c, 0));
+        assert_eq!(Some((c, &0)), heap.peek_min());
+        assert_eq!(Some(&20), heap.get(b));
+    }
+
+    #[test]
+    #[should_panic(expected = "new element is larger")]
+    fn decrease_key_larger() {
+        let mut heap = IndexedMinMaxHeap::new();
+        let a = heap.push(10);
+        heap.decrease_key(a, 11);
+    }
+
+    #[test]
+    #[should_panic(expected = "handle does not refer to an element")]
+    fn update_stale() {
+        let mut heap = IndexedMinMaxHeap::new();
+        let a = heap.push(10);
+        heap.pop_min();
+        heap.push(10);
+        heap.update(a, 5);
+    }
+
+    #[test]
+    fn clear() {
+        let mut heap = IndexedMinMaxHeap::new();
+        let a = heap.push(1);
+        heap.clear();
+        assert!(heap.is_empty());
+        assert!(!heap.contains(a));
+
+        let b = heap.push(2);
+        assert_ne!(a, b);
+        assert_eq!(Some((b, &2)), heap.peek_min());
+    }
+}
@@ -0,0 +1,334 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::iter::FromIterator;
+use std::mem;
+
+use super::indexed::{Handle, IndexedIter, IndexedMinMaxHeap};
+
+/// A double-ended priority map: each key is queued at most once, and
+/// its priority can be looked up or changed by key.
+///
+/// This pairs an [`IndexedMinMaxHeap`] of key–priority entries with a
+/// hash map from each key to the handle of its entry. Keys are stored in
+/// both, so they must be `Clone`.
+///
+/// # Examples
+///
+/// ```
+/// use min_max_heap::KeyedMinMaxHeap;
+///
+/// let mut queue = KeyedMinMaxHeap::new();
+/// queue.push("a", 5);
+/// queue.push("b", 2);
+/// queue.push("c", 9);
+///
+/// assert_eq!(queue.change_priority("c", 1), Some(9));
+/// assert_eq!(queue.peek_min(), Some((&"c", &1)));
+/// assert_eq!(queue.peek_max(), Some((&"a", &5)));
+///
+/// assert_eq!(queue.remove("a"), Some(("a", 5)));
+/// assert_eq!(queue.pop_max(), Some(("b", 2)));
+/// ```
+///
+/// [`IndexedMinMaxHeap`]: struct.IndexedMinMaxHeap.html
+#[derive(Clone, Debug)]
+pub struct KeyedMinMaxHeap<K, P> {
+    heap:    IndexedMinMaxHeap<Entry<K, P>>,
+    handles: HashMap<K, Handle>,
+}
+
+#[derive(Clone, Debug)]
+struct Entry<K, P> {
+    priority: P,
+    key:      K,
+}
+
+impl<K: Hash + Eq, P> Default for KeyedMinMaxHeap<K, P> {
+    fn default() -> Self {
+        KeyedMinMaxHeap::new()
+    }
+}
+
+impl<K: Hash + Eq, P> KeyedMinMaxHeap<K, P> {
+    /// Creates a new, empty `KeyedMinMaxHeap`.
+    ///
+    /// *O*(1).
+    pub fn new() -> Self {
+        KeyedMinMaxHeap {
+            heap:    IndexedMinMaxHeap::new(),
+            handles: HashMap::new(),
+        }
+    }
+
+    /// Creates a new, empty `KeyedMinMaxHeap` with space allocated to
+    /// hold `len` entries.
+    ///
+    /// *O*(*n*).
+    pub fn with_capacity(len: usize) -> Self {
+        KeyedMinMaxHeap {
+            heap:    IndexedMinMaxHeap::with_capacity(len),
+            handles: HashMap::with_capacity(len),
+        }
+    }
+
+    /// The number of keys in the queue.
+    ///
+    /// *O*(1).
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Is the queue empty?
+    ///
+    /// *O*(1).
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Drops all entries from the queue.
+    ///
+    /// *O*(*n*).
+    pub fn clear(&mut self) {
+        self.heap.clear();
+        self.handles.clear();
+    }
+
+    /// Is `key` in the queue?
+    ///
+    /// Expected *O*(1).
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.handles.contains_key(key)
+    }
+
+    /// Gets the priority of `key`, if it is in the queue.
+    ///
+    /// Expected *O*(1).
+    pub fn get_priority<Q>(&self, key: &Q) -> Option<&P>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let handle = *self.handles.get(key)?;
+        self.heap.get(handle).map(|entry| &entry.priority)
+    }
+
+    /// Returns an iterator over the keys and their priorities in
+    /// arbitrary order.
+    ///
+    /// *O*(1) on creation, and *O*(1) for each `next()` operation.
+    pub fn iter(&self) -> KeyedIter<'_, K, P> {
+        KeyedIter(self.heap.iter())
+    }
+}
+
+impl<K: Hash + Eq + Clone, P: Ord> KeyedMinMaxHeap<K, P> {
+    /// Queues `key` with the given priority.
+    ///
+    /// If `key` is already queued, its priority is changed instead and
+    /// the old priority is returned.
+    ///
+    /// *O*(log *n*), plus expected *O*(1) for hashing.
+    pub fn push(&mut self, key: K, priority: P) -> Option<P> {
+        if let Some(&handle) = self.handles.get(&key) {
+            return Some(self.replace(handle, priority));
+        }
+
+        let handle = self.heap.push(Entry { priority, key: key.clone() });
+        self.handles.insert(key, handle);
+        None
+    }
+
+    /// Changes the priority of `key`, returning the old priority, or
+    /// returns `None` and leaves the queue unchanged if `key` is not
+    /// queued.
+    ///
+    /// *O*(log *n*), plus expected *O*(1) for hashing.
+    pub fn change_priority<Q>(&mut self, key: &Q, priority: P) -> Option<P>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let handle = *self.handles.get(key)?;
+        Some(self.replace(handle, priority))
+    }
+
+    /// Removes `key` from the queue, returning it with its priority.
+    ///
+    /// *O*(log *n*), plus expected *O*(1) for hashing.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<(K, P)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let handle = self.handles.remove(key)?;
+        self.heap.remove(handle).map(|entry| (entry.key, entry.priority))
+    }
+
+    /// Gets the key with the lowest priority, and its priority, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_min(&self) -> Option<(&K, &P)> {
+        self.heap.peek_min().map(|(_, entry)| (&entry.key, &entry.priority))
+    }
+
+    /// Gets the key with the highest priority, and its priority, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_max(&self) -> Option<(&K, &P)> {
+        self.heap.peek_max().map(|(_, entry)| (&entry.key, &entry.priority))
+    }
+
+    /// Removes the key with the lowest priority, if any.
+    ///
+    /// *O*(log *n*), plus expected *O*(1) for hashing.
+    pub fn pop_min(&mut self) -> Option<(K, P)> {
+        let (_, entry) = self.heap.pop_min()?;
+        self.handles.remove(&entry.key);
+        Some((entry.key, entry.priority))
+    }
+
+    /// Removes the key with the highest priority, if any.
+    ///
+    /// *O*(log *n*), plus expected *O*(1) for hashing.
+    pub fn pop_max(&mut self) -> Option<(K, P)> {
+        let (_, entry) = self.heap.pop_max()?;
+        self.handles.remove(&entry.key);
+        Some((entry.key, entry.priority))
+    }
+
+    fn replace(&mut self, handle: Handle, priority: P) -> P {
+        self.heap.update_with(handle, |entry| mem::replace(&mut entry.priority, priority))
+    }
+}
+
+impl<K: Hash + Eq + Clone, P: Ord> Extend<(K, P)> for KeyedMinMaxHeap<K, P> {
+    fn extend<I: IntoIterator<Item = (K, P)>>(&mut self, iter: I) {
+        for (key, priority) in iter {
+            self.push(key, priority);
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, P: Ord> FromIterator<(K, P)> for KeyedMinMaxHeap<K, P> {
+    fn from_iter<I>(iter: I) -> Self
+            where I: IntoIterator<Item = (K, P)> {
+        let mut result = KeyedMinMaxHeap::new();
+        result.extend(iter);
+        result
+    }
+}
+
+impl<K, P: Ord> PartialEq for Entry<K, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<K, P: Ord> Eq for Entry<K, P> { }
+
+impl<K, P: Ord> PartialOrd for Entry<K, P> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K, P: Ord> Ord for Entry<K, P> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority.cmp(&other.priority)
+    }
+}
+
+/// A borrowed iterator over the keys of a [`KeyedMinMaxHeap`] and their
+/// priorities, in arbitrary order.
+///
+/// This type is created with
+/// [`KeyedMinMaxHeap::iter`](struct.KeyedMinMaxHeap.html#method.iter).
+///
+/// [`KeyedMinMaxHeap`]: struct.KeyedMinMaxHeap.html
+#[derive(Clone, Debug)]
+pub struct KeyedIter<'a, K: 'a, P: 'a>(IndexedIter<'a, Entry<K, P>>);
+
+impl<'a, K, P> Iterator for KeyedIter<'a, K, P> {
+    type Item = (&'a K, &'a P);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(_, entry)| (&entry.key, &entry.priority))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, K, P> ExactSizeIterator for KeyedIter<'a, K, P> { }
+
+#[cfg(test)]
+mod test {
+    extern crate rand;
+
+    use super::*;
+    use std::borrow::ToOwned;
+    use std::string::String;
+    use self::rand::Rng;
+
+    #[test]
+    fn random() {
+        let mut rng = rand::thread_rng();
+        let mut queue = KeyedMinMaxHeap::new();
+        let mut model: HashMap<u32, u32> = HashMap::new();
+
+        for _ in 0 .. 2000 {
+            let key = rng.gen_range(0 .. 30);
+            let priority = rng.gen_range(0 .. 100);
+
+            match rng.gen_range(0 .. 5) {
+                0 | 1 => {
+                    assert_eq!(model.insert(key, priority), queue.push(key, priority));
+                }
+                2 => {
+                    let expected = model.get_mut(&key)
+                        .map(|old| mem::replace(old, priority));
+                    assert_eq!(expected, queue.change_priority(&key, priority));
+                }
+                3 => {
+                    assert_eq!(model.remove(&key).map(|p| (key, p)), queue.remove(&key));
+                }
+                _ => {
+                    if let Some((key, priority)) = queue.pop_min() {
+                        assert_eq!(Some(priority), model.remove(&key));
+                        assert!(model.values().all(|&p| p >= priority));
+                    } else {
+                        assert!(model.is_empty());
+                    }
+                }
+            }
+
+            assert_eq!(model.len(), queue.len());
+            assert_eq!(model.values().min(), queue.peek_min().map(|p| p.1));
+            assert_eq!(model.values().max(), queue.peek_max().map(|p| p.1));
+            for (k, p) in queue.iter() {
+                assert_eq!(Some(p), model.get(k));
+                assert_eq!(Some(p), queue.get_priority(k));
+            }
+        }
+    }
+
+    #[test]
+    fn borrowed_keys() {
+        let mut queue: KeyedMinMaxHeap<String, i32> =
+            vec![("x".to_owned(), 3), ("y".to_owned(), 7)].into_iter().collect();
+
+        assert!(queue.contains_key("x"));
+        assert_eq!(Some(&7), queue.get_priority("y"));
+        assert_eq!(Some(3), queue.change_priority("x", 10));
+        assert_eq!(Some(("x".to_owned(), 10)), queue.pop_max());
+        assert!(!queue.contains_key("x"));
+        assert_eq!(None, queue.change_priority("x", 0));
+    }
+}
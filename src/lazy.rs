@@ -0,0 +1,313 @@
+use alloc::vec::Vec;
+use core::mem;
+
+use super::MinMaxHeap;
+
+/// A min-max-heap that supports deleting elements by value, lazily.
+///
+/// Removing an element only records a *tombstone* for it in a second
+/// min-max-heap. Whenever an extreme of the heap matches the same extreme
+/// of the tombstones, both are discarded, so the minimum and maximum seen
+/// from outside are always live. Once the tombstones outnumber the live
+/// elements, the heap is compacted in one pass. This is the usual way to
+/// keep a search frontier free of stale entries.
+///
+/// Since tombstones are matched by equality, an element passed to
+/// [`remove`] must actually be in the heap; removing an absent element is
+/// a logic error that may cause a later, equal element to disappear, and
+/// makes [`len`] undercount until the next compaction, which drops any
+/// tombstones that match nothing. It does not panic.
+///
+/// # Examples
+///
+/// ```
+/// use min_max_heap::LazyMinMaxHeap;
+///
+/// let mut heap = LazyMinMaxHeap::new();
+/// heap.extend(vec![3, 1, 4, 1, 5]);
+///
+/// heap.remove(1);
+/// heap.remove(5);
+///
+/// assert_eq!(heap.len(), 3);
+/// assert_eq!(heap.peek_min(), Some(&1));
+/// assert_eq!(heap.peek_max(), Some(&4));
+/// ```
+///
+/// [`remove`]: struct.LazyMinMaxHeap.html#method.remove
+/// [`len`]: struct.LazyMinMaxHeap.html#method.len
+#[derive(Clone, Debug)]
+pub struct LazyMinMaxHeap<T> {
+    // Invariant: `dead` is a sub-multiset of `live` (unless an absent
+    // element was removed), and neither extreme of `live` is equal to the
+    // same extreme of `dead`.
+    live: MinMaxHeap<T>,
+    dead: MinMaxHeap<T>,
+}
+
+impl<T> Default for LazyMinMaxHeap<T> {
+    fn default() -> Self {
+        LazyMinMaxHeap::new()
+    }
+}
+
+impl<T> LazyMinMaxHeap<T> {
+    /// Creates a new, empty `LazyMinMaxHeap`.
+    ///
+    /// *O*(1).
+    pub fn new() -> Self {
+        LazyMinMaxHeap {
+            live: MinMaxHeap::new(),
+            dead: MinMaxHeap::new(),
+        }
+    }
+
+    /// The number of elements in the heap, not counting removed ones.
+    ///
+    /// *O*(1).
+    pub fn len(&self) -> usize {
+        self.live.len().saturating_sub(self.dead.len())
+    }
+
+    /// Is the heap empty?
+    ///
+    /// *O*(1).
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of removed elements still awaiting compaction.
+    ///
+    /// *O*(1).
+    pub fn tombstones(&self) -> usize {
+        self.dead.len()
+    }
+
+    /// Drops all elements and tombstones.
+    ///
+    /// *O*(*n*).
+    pub fn clear(&mut self) {
+        self.live.clear();
+        self.dead.clear();
+    }
+}
+
+impl<T: Ord> LazyMinMaxHeap<T> {
+    /// Adds an element to the heap.
+    ///
+    /// *O*(log *n*).
+    pub fn push(&mut self, element: T) {
+        self.live.push(element);
+        self.discard_dead();
+    }
+
+    /// Removes an element equal to `element` from the heap.
+    ///
+    /// The heap must contain such an element; see the [type-level
+    /// documentation](struct.LazyMinMaxHeap.html) for what happens if not.
+    ///
+    /// Amortized *O*(log *n*).
+    pub fn remove(&mut self, element: T) {
+        self.dead.push(element);
+        self.discard_dead();
+    }
+
+    /// Gets a reference to the minimum live element, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_min(&self) -> Option<&T> {
+        self.live.peek_min()
+    }
+
+    /// Gets a reference to the maximum live element, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_max(&self) -> Option<&T> {
+        self.live.peek_max()
+    }
+
+    /// Removes the minimum live element, if any.
+    ///
+    /// Amortized *O*(log *n*).
+    pub fn pop_min(&mut self) -> Option<T> {
+        let result = self.live.pop_min();
+        self.discard_dead();
+        result
+    }
+
+    /// Removes the maximum live element, if any.
+    ///
+    /// Amortized *O*(log *n*).
+    pub fn pop_max(&mut self) -> Option<T> {
+        let result = self.live.pop_max();
+        self.discard_dead();
+        result
+    }
+
+    /// Discards all tombstones along with the elements they mark, and any
+    /// tombstones that mark no element.
+    ///
+    /// *O*(*n* log *n*).
+    pub fn compact(&mut self) {
+        if self.dead.is_empty() {
+            return;
+        }
+
+        let mut dead = mem::take(&mut self.dead).into_vec_asc().into_iter().peekable();
+        let live = mem::take(&mut self.live).into_vec_asc();
+
+        let live: Vec<T> = live.into_iter()
+            .filter(|element| {
+                while dead.next_if(|dead| dead < element).is_some() {}
+
+                if dead.peek() == Some(element) {
+                    dead.next();
+                    false
+                } else {
+                    true
+                }
+            })
+            .collect();
+
+        self.live = MinMaxHeap::from(live);
+    }
+
+    /// Converts into a plain `MinMaxHeap` of the live elements.
+    ///
+    /// *O*(*n* log *n*) if there are tombstones, and *O*(1) otherwise.
+    pub fn into_heap(mut self) -> MinMaxHeap<T> {
+        self.compact();
+        self.live
+    }
+
+    /// Restores the invariant after an extreme may have become dead,
+    /// compacting if the tombstones now outnumber the live elements.
+    fn discard_dead(&mut self) {
+        while !self.dead.is_empty() && self.live.peek_min() == self.dead.peek_min() {
+            self.live.pop_min();
+            self.dead.pop_min();
+        }
+
+        while !self.dead.is_empty() && self.live.peek_max() == self.dead.peek_max() {
+            self.live.pop_max();
+            self.dead.pop_max();
+        }
+
+        if self.dead.len() > self.len() {
+            self.compact();
+        }
+    }
+}
+
+impl<T: Ord> From<MinMaxHeap<T>> for LazyMinMaxHeap<T> {
+    fn from(heap: MinMaxHeap<T>) -> Self {
+        LazyMinMaxHeap {
+            live: heap,
+            dead: MinMaxHeap::new(),
+        }
+    }
+}
+
+impl<T: Ord> Extend<T> for LazyMinMaxHeap<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for element in iter {
+            self.push(element);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate rand;
+
+    use super::*;
+    use self::rand::Rng;
+
+    #[test]
+    fn random() {
+        let mut rng = rand::thread_rng();
+        let mut heap = LazyMinMaxHeap::new();
+        let mut model = Vec::new();
+
+        for _ in 0 .. 3000 {
+            match rng.gen_range(0 .. 5) {
+                0 | 1 => {
+                    let x = rng.gen_range(0 .. 50);
+                    heap.push(x);
+                    model.push(x);
+                }
+                2 if !model.is_empty() => {
+                    let x = model.swap_remove(rng.gen_range(0 .. model.len()));
+                    heap.remove(x);
+                }
+                3 => {
+                    model.sort();
+                    let expected = if model.is_empty() { None } else { Some(model.remove(0)) };
+                    assert_eq!(expected, heap.pop_min());
+                }
+                _ => {
+                    model.sort();
+                    assert_eq!(model.pop(), heap.pop_max());
+                }
+            }
+
+            assert_eq!(model.len(), heap.len());
+            assert_eq!(model.iter().min(), heap.peek_min());
+            assert_eq!(model.iter().max(), heap.peek_max());
+            assert!(heap.tombstones() <= heap.len());
+        }
+
+        model.sort();
+        assert_eq!(model, heap.into_heap().into_vec_asc());
+    }
+
+    #[test]
+    fn compact() {
+        let mut heap: LazyMinMaxHeap<_> = MinMaxHeap::from(vec![1, 2, 3, 4, 5, 6, 7, 8]).into();
+        heap.remove(4);
+        heap.remove(5);
+        assert_eq!(2, heap.tombstones());
+        assert_eq!(6, heap.len());
+
+        heap.compact();
+        assert_eq!(0, heap.tombstones());
+        assert_eq!(vec![1, 2, 3, 6, 7, 8], heap.into_heap().into_vec_asc());
+    }
+
+    #[test]
+    fn extremes() {
+        let mut heap = LazyMinMaxHeap::new();
+        heap.extend(vec![2, 2, 5]);
+        heap.remove(2);
+        assert_eq!(0, heap.tombstones());
+        assert_eq!(Some(&2), heap.peek_min());
+        heap.remove(2);
+        heap.remove(5);
+        assert!(heap.is_empty());
+        assert_eq!(None, heap.peek_max());
+    }
+
+    #[test]
+    fn remove_absent() {
+        let mut heap = LazyMinMaxHeap::new();
+        heap.remove(1);
+        assert!(heap.is_empty());
+        assert_eq!(0, heap.tombstones());
+
+        heap.extend(vec![1, 3, 5, 7, 9]);
+        heap.remove(4);
+        heap.remove(7);
+        assert_eq!(2, heap.tombstones());
+        assert_eq!(3, heap.len());
+
+        heap.compact();
+        assert_eq!(0, heap.tombstones());
+        assert_eq!(4, heap.len());
+
+        for &element in &[0, 2, 4, 6, 8, 10] {
+            heap.remove(element);
+        }
+        assert_eq!(0, heap.tombstones());
+        assert_eq!(vec![1, 3, 5, 9], heap.into_heap().into_vec_asc());
+    }
+}
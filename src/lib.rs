@@ -17,7 +17,32 @@
 //! min-max-heap = "1.3.0"
 //! ```
 //!
-//! This crate supports Rust version 1.46 and later.
+//! This crate supports Rust version 1.57 and later.
+//!
+//! Without its default `std` feature, the crate needs only `core` and,
+//! with its `alloc` feature, `alloc`, so it works in `no_std`
+//! environments. The [`sync`] module, [`ExpiryHeap`], and
+//! [`KeyedMinMaxHeap`] need `std`:
+//!
+//! ```toml
+//! [dependencies]
+//! min-max-heap = { version = "1.3.0", default-features = false, features = ["alloc"] }
+//! ```
+//!
+//! Without `alloc`, only the heaps that never allocate are available:
+//! [`ArrayMinMaxHeap`], [`SliceMinMaxHeap`], and [`UninitMinMaxHeap`]
+//! (the first and last of which also need `safe` to be off).
+//! The implementations for optional dependencies, such as `serde`, need
+//! `alloc` as well.
+//!
+//! The heap’s sifting code moves elements with unchecked indexing and
+//! raw pointer reads and writes. The `safe` feature swaps it for an
+//! implementation that uses only safe swaps, which is somewhat slower.
+//! With `safe`, the crate forbids `unsafe_code`, so it leaves out what
+//! cannot be written without it: [`ArrayMinMaxHeap`] and
+//! [`UninitMinMaxHeap`], which manage uninitialized memory, the `rkyv`
+//! support, and the `unsafe` constructors such as
+//! `MinMaxHeap::from_vec_unchecked`.
 //!
 //! ## References
 //!
@@ -38,39 +63,305 @@
 //! [binary_heap.rs]:
 //!   <https://doc.rust-lang.org/1.56.1/src/alloc/collections/binary_heap.rs.html>
 
+#![no_std]
 #![warn(missing_docs)]
+#![cfg_attr(feature = "allocator_api", feature(allocator_api))]
+#![cfg_attr(feature = "unstable", feature(min_specialization, trusted_len))]
+#![cfg_attr(feature = "safe", forbid(unsafe_code))]
+
+#[cfg(feature = "alloc")]
+#[macro_use]
+extern crate alloc;
+#[cfg(any(feature = "std", test))]
+extern crate std;
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use alloc::collections::{BTreeSet, BinaryHeap, TryReserveError, VecDeque};
+#[cfg(feature = "alloc")]
+use alloc::vec::{self, Vec};
+#[cfg(feature = "alloc")]
+use core::cmp::Ordering;
+#[cfg(feature = "alloc")]
+use core::iter::FromIterator;
+use core::iter::FusedIterator;
+use core::{fmt, slice};
+#[cfg(feature = "alloc")]
+use core::mem;
+#[cfg(feature = "alloc")]
+use core::ops::{Bound, Deref, DerefMut, RangeBounds};
+#[cfg(all(feature = "alloc", not(feature = "safe")))]
+use core::ptr;
+
+// Calls the unchecked helpers, such as `Hole::new`, which are `unsafe`
+// except when the `safe` feature swaps in checked versions of them.
+#[cfg(not(feature = "safe"))]
+macro_rules! unchecked {
+    ($($body:tt)*) => { unsafe { $($body)* } };
+}
 
-#[cfg(feature = "serde")]
-use serde::{Serialize, Deserialize};
+#[cfg(feature = "safe")]
+macro_rules! unchecked {
+    ($($body:tt)*) => { { $($body)* } };
+}
+
+// Enters a `tracing` span, named for the operation and recording the
+// heap’s length, until the end of the enclosing block. Without the
+// `tracing` feature, this expands to nothing.
+#[cfg(all(feature = "alloc", feature = "tracing"))]
+macro_rules! trace_span {
+    ($level:ident, $op:literal, $len:expr) => {
+        let _span = tracing::span!(tracing::Level::$level, $op, len = $len).entered();
+    };
+}
+
+#[cfg(all(feature = "alloc", not(feature = "tracing")))]
+macro_rules! trace_span {
+    ($($args:tt)*) => { };
+}
+
+// Checks the whole heap after a mutating operation, panicking at the
+// first out-of-order element. Without the `paranoid` feature, this
+// expands to nothing.
+#[cfg(all(feature = "alloc", feature = "paranoid"))]
+macro_rules! paranoid_check {
+    ($heap:expr) => { $heap.assert_valid(); };
+}
 
-use std::iter::FromIterator;
-use std::{fmt, mem, slice, vec};
-use std::ops::{Deref, DerefMut};
+#[cfg(all(feature = "alloc", not(feature = "paranoid")))]
+macro_rules! paranoid_check {
+    ($heap:expr) => { };
+}
 
+#[cfg(feature = "allocator_api")]
+mod alloc_in;
+#[cfg(all(feature = "alloc", feature = "arbitrary"))]
+mod arbitrary_impls;
+// `ArrayMinMaxHeap` and `UninitMinMaxHeap` manage uninitialized memory,
+// and checking `rkyv` archives needs an `unsafe impl`, so the `safe`
+// feature leaves them out.
+#[cfg(not(feature = "safe"))]
+mod array;
+#[cfg(all(feature = "alloc", feature = "bincode"))]
+mod bincode_impls;
+#[cfg(feature = "alloc")]
+mod blocked;
+#[cfg(feature = "alloc")]
+mod bounded;
+#[cfg(all(feature = "alloc", feature = "borsh"))]
+mod borsh_impls;
+#[cfg(all(feature = "alloc", feature = "bumpalo"))]
+mod bump;
+mod compare;
+#[cfg(feature = "alloc")]
+mod cursor;
+#[cfg(feature = "alloc")]
+mod dary;
+#[cfg(all(feature = "alloc", feature = "defmt"))]
+mod defmt_impls;
+#[cfg(feature = "alloc")]
+mod display_tree;
+mod double_ended;
+#[cfg(feature = "dot")]
+mod dot;
+#[cfg(feature = "std")]
+mod expiry;
+#[cfg(feature = "alloc")]
+mod frontier;
+#[cfg(feature = "alloc")]
+mod growth;
+#[cfg_attr(feature = "safe", path = "safe_hole.rs")]
 mod hole;
 mod index;
-
+#[cfg(feature = "alloc")]
+mod indexed;
+#[cfg(feature = "alloc")]
+mod interval;
+#[cfg(feature = "std")]
+mod keyed;
+#[cfg(feature = "alloc")]
+mod lazy;
+#[cfg(feature = "alloc")]
+mod median_heap;
+#[cfg(feature = "alloc")]
+mod persistent;
+#[cfg(all(feature = "alloc", feature = "quickcheck"))]
+mod quickcheck_impls;
+#[cfg(all(feature = "alloc", feature = "rayon"))]
+mod rayon_impls;
+#[cfg(all(feature = "alloc", feature = "rkyv", not(feature = "safe")))]
+mod rkyv_impls;
+#[cfg(all(feature = "alloc", feature = "schemars"))]
+mod schemars_impls;
+#[cfg(all(feature = "alloc", feature = "serde"))]
+mod serde_impls;
+#[cfg(all(feature = "alloc", feature = "serde"))]
+pub mod serde_sorted;
+mod slice_heap;
+#[cfg(all(feature = "alloc", feature = "smallvec"))]
+mod small;
+#[cfg(feature = "alloc")]
+mod sliding_window;
+#[cfg(feature = "unstable")]
+mod spec_extend;
+#[cfg(feature = "alloc")]
+mod stable;
+mod stats;
+#[cfg(all(feature = "alloc", feature = "proptest"))]
+pub mod strategies;
+#[cfg(feature = "stream")]
+mod stream;
+#[cfg(feature = "std")]
+pub mod sync;
+#[cfg(all(test, feature = "alloc"))]
+mod test_model;
+#[cfg(feature = "alloc")]
+mod top_k;
+#[cfg(not(feature = "safe"))]
+mod uninit;
+#[cfg(feature = "alloc")]
+mod weighted;
+#[cfg(all(feature = "alloc", feature = "zeroize"))]
+mod zeroize_impls;
+
+#[cfg(feature = "alloc")]
+use self::frontier::Frontier;
 use self::hole::*;
+#[cfg(feature = "alloc")]
+use self::index::*;
+
+#[cfg(feature = "allocator_api")]
+pub use self::alloc_in::{AllocDrainAsc, AllocDrainDesc, AllocMinMaxHeap, AllocPeekMaxMut,
+                        AllocPeekMinMut};
+#[cfg(not(feature = "safe"))]
+pub use self::array::ArrayMinMaxHeap;
+#[cfg(feature = "alloc")]
+pub use self::blocked::BlockedMinMaxHeap;
+#[cfg(feature = "alloc")]
+pub use self::bounded::{BoundedMinMaxHeap, Eviction};
+#[cfg(all(feature = "alloc", feature = "bumpalo"))]
+pub use self::bump::BumpMinMaxHeap;
+pub use self::compare::{Compare, FnComparator, KeyComparator, NanPolicy, Natural,
+                        PartialComparator};
+#[cfg(feature = "alloc")]
+pub use self::cursor::{Cursor, Cursors};
+#[cfg(feature = "alloc")]
+pub use self::dary::DaryMinMaxHeap;
+#[cfg(feature = "alloc")]
+pub use self::display_tree::DisplayTree;
+pub use self::double_ended::DoubleEndedHeap;
+#[cfg(feature = "std")]
+pub use self::expiry::{Expired, ExpiryHeap};
+#[cfg(feature = "alloc")]
+pub use self::growth::{Growth, GrowthMinMaxHeap};
+#[cfg(feature = "alloc")]
+pub use self::indexed::{Handle, IndexedIter, IndexedMinMaxHeap};
+#[cfg(feature = "alloc")]
+pub use self::interval::IntervalHeap;
+#[cfg(feature = "std")]
+pub use self::keyed::{KeyedIter, KeyedMinMaxHeap};
+#[cfg(feature = "alloc")]
+pub use self::lazy::LazyMinMaxHeap;
+#[cfg(feature = "alloc")]
+pub use self::median_heap::MedianHeap;
+#[cfg(all(feature = "alloc", feature = "rkyv", not(feature = "safe")))]
+pub use self::rkyv_impls::ArchivedMinMaxHeap;
+#[cfg(feature = "alloc")]
+pub use self::persistent::{PersistentIterAsc, PersistentMinMaxHeap};
+pub use self::slice_heap::SliceMinMaxHeap;
+#[cfg(all(feature = "alloc", feature = "smallvec"))]
+pub use self::small::SmallMinMaxHeap;
+#[cfg(feature = "alloc")]
+pub use self::sliding_window::SlidingWindowMinMax;
+#[cfg(feature = "alloc")]
+pub use self::stable::{StableIter, StableMinMaxHeap};
+#[cfg(feature = "stats")]
+pub use self::stats::HeapStats;
+#[cfg(feature = "stream")]
+pub use self::stream::{IntoStreamAsc, IntoStreamDesc};
+#[cfg(feature = "alloc")]
+pub use self::top_k::TopK;
+#[cfg(not(feature = "safe"))]
+pub use self::uninit::UninitMinMaxHeap;
+#[cfg(feature = "alloc")]
+pub use self::weighted::WeightedMinMaxHeap;
 
 /// A double-ended priority queue.
 ///
 /// Most operations are *O*(log *n*).
-#[derive(Clone, Debug)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct MinMaxHeap<T>(Vec<T>);
+///
+/// Elements are ordered by a comparator of type `C`, which by default is
+/// [`Natural`], the ordering given by `T: Ord`. Any other [`Compare`]
+/// implementation can be supplied with [`with_comparator`].
+///
+/// [`Natural`]: struct.Natural.html
+/// [`Compare`]: trait.Compare.html
+/// [`with_comparator`]: struct.MinMaxHeap.html#method.with_comparator
+#[cfg(feature = "alloc")]
+#[derive(Clone)]
+pub struct MinMaxHeap<T, C = Natural>(Vec<T>, C);
+
+#[cfg(feature = "alloc")]
+impl<T: fmt::Debug, C> fmt::Debug for MinMaxHeap<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("MinMaxHeap")
+         .field(&self.0)
+         .finish()
+    }
+}
+
+/// Heaps are equal when they hold the same elements, each the same
+/// number of times, however the elements are laid out.
+///
+/// # Examples
+///
+/// ```
+/// use min_max_heap::MinMaxHeap;
+///
+/// let a = MinMaxHeap::from(vec![1, 2, 2, 3]);
+/// let b: MinMaxHeap<_> = vec![3, 2, 1, 2].into_iter().collect();
+/// let c = MinMaxHeap::from(vec![1, 2, 3, 3]);
+///
+/// assert_eq!(a, b);
+/// assert_ne!(a, c);
+/// ```
+///
+/// *O*(*n* log *n*).
+#[cfg(feature = "alloc")]
+impl<T: Ord, C> PartialEq for MinMaxHeap<T, C> {
+    fn eq(&self, other: &Self) -> bool {
+        if self.len() != other.len() {
+            return false;
+        }
+
+        fn sorted<T: Ord>(elements: &[T]) -> Vec<&T> {
+            let mut refs: Vec<&T> = elements.iter().collect();
+            refs.sort_unstable();
+            refs
+        }
 
+        sorted(&self.0) == sorted(&other.0)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Ord, C> Eq for MinMaxHeap<T, C> { }
+
+#[cfg(feature = "alloc")]
 impl<T> Default for MinMaxHeap<T> {
     fn default() -> Self {
         MinMaxHeap::new()
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<T> MinMaxHeap<T> {
     /// Creates a new, empty `MinMaxHeap`.
     ///
     /// *O*(1).
     pub fn new() -> Self {
-        MinMaxHeap(Vec::new())
+        MinMaxHeap::with_comparator(Natural)
     }
 
     /// Creates a new, empty `MinMaxHeap` with space allocated to hold
@@ -78,7 +369,139 @@ impl<T> MinMaxHeap<T> {
     ///
     /// *O*(n).
     pub fn with_capacity(len: usize) -> Self {
-        MinMaxHeap(Vec::with_capacity(len))
+        MinMaxHeap::with_capacity_and_comparator(len, Natural)
+    }
+
+    /// Creates a `MinMaxHeap` from a vector that is already in heap
+    /// order, without rebuilding it.
+    ///
+    /// This is for vectors that came from [`into_vec`] or that are
+    /// otherwise known to be valid, such as trusted serialized data.
+    /// Debug builds check the vector with [`is_valid`].
+    ///
+    /// # Safety
+    ///
+    /// `vec` must satisfy the min-max-heap property. This crate’s own
+    /// code stays memory-safe if it does not, but the heap’s methods
+    /// will return elements in an unspecified order, which unsafe code
+    /// relying on that order could turn into undefined behavior.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let heap: MinMaxHeap<_> = (0 .. 10).map(|i| i * 7 % 10).collect();
+    /// let vec = heap.into_vec();
+    ///
+    /// // SAFETY: `vec` came from a heap with the same ordering.
+    /// let heap = unsafe { MinMaxHeap::from_vec_unchecked(vec) };
+    /// assert_eq!(heap.peek_max(), Some(&9));
+    /// ```
+    ///
+    /// *O*(1).
+    ///
+    /// [`into_vec`]: #method.into_vec
+    /// [`is_valid`]: #method.is_valid
+    #[cfg(not(feature = "safe"))]
+    pub unsafe fn from_vec_unchecked(vec: Vec<T>) -> Self
+        where T: Ord
+    {
+        MinMaxHeap::from_vec_with_comparator_unchecked(vec, Natural)
+    }
+
+    /// Creates a `MinMaxHeap` from elements in ascending order.
+    ///
+    /// The ordering lets this move each element straight to where it
+    /// belongs in the heap, without comparing any. That is only faster
+    /// while the elements fit in cache, so past 256 KiB of them this
+    /// heapifies them as `From<Vec<T>>` does instead. If the elements
+    /// are not in fact sorted, the heap will behave in an unspecified
+    /// (but memory-safe) way.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let heap = MinMaxHeap::from_sorted_iter_asc(vec![1, 3, 3, 7]);
+    ///
+    /// assert_eq!(heap.peek_min(), Some(&1));
+    /// assert_eq!(heap.peek_max(), Some(&7));
+    /// ```
+    ///
+    /// *O*(*n*), with no comparisons up to 256 KiB of elements.
+    pub fn from_sorted_iter_asc<I: IntoIterator<Item = T>>(iter: I) -> Self
+        where T: Ord
+    {
+        let mut heap = MinMaxHeap(iter.into_iter().collect(), Natural);
+        heap.heapify_sorted();
+        heap
+    }
+
+    /// Creates a `MinMaxHeap` from elements in descending order.
+    ///
+    /// This is like [`from_sorted_iter_asc`], and likewise compares no
+    /// elements up to 256 KiB of them.
+    ///
+    /// [`from_sorted_iter_asc`]: #method.from_sorted_iter_asc
+    ///
+    /// *O*(*n*), with no comparisons up to 256 KiB of elements.
+    pub fn from_sorted_iter_desc<I: IntoIterator<Item = T>>(iter: I) -> Self
+        where T: Ord
+    {
+        let mut heap = MinMaxHeap(iter.into_iter().collect::<Vec<_>>(), Natural);
+        heap.0.reverse();
+        heap.heapify_sorted();
+        heap
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, C> MinMaxHeap<T, C> {
+    /// Creates a new, empty `MinMaxHeap` ordered by `cmp`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use min_max_heap::{Compare, MinMaxHeap};
+    ///
+    /// struct ByLastDigit;
+    ///
+    /// impl Compare<u32> for ByLastDigit {
+    ///     fn compare(&self, a: &u32, b: &u32) -> Ordering {
+    ///         (a % 10).cmp(&(b % 10))
+    ///     }
+    /// }
+    ///
+    /// let mut heap = MinMaxHeap::with_comparator(ByLastDigit);
+    /// heap.push(19);
+    /// heap.push(25);
+    /// heap.push(31);
+    ///
+    /// assert_eq!(heap.peek_min(), Some(&31));
+    /// assert_eq!(heap.peek_max(), Some(&19));
+    /// ```
+    ///
+    /// *O*(1).
+    pub fn with_comparator(cmp: C) -> Self {
+        MinMaxHeap(Vec::new(), cmp)
+    }
+
+    /// Creates a new, empty `MinMaxHeap` ordered by `cmp`, with space
+    /// allocated to hold `len` elements.
+    ///
+    /// *O*(n).
+    pub fn with_capacity_and_comparator(len: usize, cmp: C) -> Self {
+        MinMaxHeap(Vec::with_capacity(len), cmp)
+    }
+
+    /// Returns a reference to the heap’s comparator.
+    ///
+    /// *O*(1).
+    pub fn comparator(&self) -> &C {
+        &self.1
     }
 
     /// The number of elements in the heap.
@@ -96,987 +519,4635 @@ impl<T> MinMaxHeap<T> {
     }
 }
 
-impl<T: Ord> MinMaxHeap<T> {
-    /// Adds an element to the heap.
+#[cfg(feature = "alloc")]
+impl<T, F> MinMaxHeap<T, FnComparator<F>>
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    /// Creates a new, empty `MinMaxHeap` ordered by the comparison
+    /// function `compare`.
     ///
-    /// Amortized *O*(log *n*); worst-case *O*(*n*) when the backing vector needs to
-    /// grow.
-    pub fn push(&mut self, element: T) {
-        let pos = self.len();
-        self.0.push(element);
-        // SAFETY: `pos` is the index of the new element
-        unsafe {
-            self.bubble_up(pos);
-        }
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let mut heap = MinMaxHeap::new_by(|a: &(&str, u32), b: &(&str, u32)| a.1.cmp(&b.1));
+    /// heap.push(("b", 2));
+    /// heap.push(("c", 3));
+    /// heap.push(("a", 1));
+    ///
+    /// assert_eq!(heap.peek_min(), Some(&("a", 1)));
+    /// assert_eq!(heap.peek_max(), Some(&("c", 3)));
+    /// ```
+    ///
+    /// *O*(1).
+    pub fn new_by(compare: F) -> Self {
+        MinMaxHeap::with_comparator(FnComparator(compare))
     }
 
-    /// Gets a reference to the minimum element, if any.
+    /// Creates a `MinMaxHeap` ordered by the comparison function
+    /// `compare` from the elements of `vec`, in a single pass.
     ///
-    /// *O*(1).
-    pub fn peek_min(&self) -> Option<&T> {
-        self.0.first()
+    /// *O*(*n*).
+    pub fn from_vec_by(vec: Vec<T>, compare: F) -> Self {
+        MinMaxHeap::from_vec_with_comparator(vec, FnComparator(compare))
     }
+}
 
-    /// Returns a mutable reference to the minimum element, if any. Once this reference is dropped,
-    /// the heap is adjusted if necessary.
+#[cfg(feature = "alloc")]
+impl<T, K, F> MinMaxHeap<T, KeyComparator<F>>
+where
+    K: Ord,
+    F: Fn(&T) -> K,
+{
+    /// Creates a new, empty `MinMaxHeap` that orders elements by the key
+    /// that `key` extracts from them, like `slice::sort_by_key`.
     ///
-    /// Note: If the `PeekMinMut` value is leaked, the heap may be in an
-    /// inconsistent state.
+    /// # Examples
     ///
-    /// *O*(1) for the peek; *O*(log *n*) when the reference is dropped.
-    pub fn peek_min_mut(&mut self) -> Option<PeekMinMut<T>> {
-        if self.is_empty() {
-            None
-        } else {
-            Some(PeekMinMut {
-                heap: self,
-                sift: false,
-            })
-        }
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// struct Job { name: &'static str, cost: u32 }
+    ///
+    /// let mut heap = MinMaxHeap::new_by_key(|job: &Job| job.cost);
+    /// heap.push(Job { name: "index", cost: 40 });
+    /// heap.push(Job { name: "fetch", cost: 5 });
+    /// heap.push(Job { name: "render", cost: 12 });
+    ///
+    /// assert_eq!(heap.pop_min().map(|job| job.name), Some("fetch"));
+    /// assert_eq!(heap.pop_max().map(|job| job.name), Some("index"));
+    /// ```
+    ///
+    /// *O*(1).
+    pub fn new_by_key(key: F) -> Self {
+        MinMaxHeap::with_comparator(KeyComparator(key))
     }
 
-    /// Gets a reference to the maximum element, if any.
+    /// Creates a `MinMaxHeap` that orders elements by the key that `key`
+    /// extracts from them, from the elements of `vec`, in a single pass.
+    ///
+    /// *O*(*n*).
+    pub fn from_vec_by_key(vec: Vec<T>, key: F) -> Self {
+        MinMaxHeap::from_vec_with_comparator(vec, KeyComparator(key))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: PartialOrd> MinMaxHeap<T, PartialComparator> {
+    /// Creates a new, empty `MinMaxHeap` for a type that is only
+    /// `PartialOrd`, such as `f64`, handling NaNs as `nan` says.
+    ///
+    /// With `NanPolicy::Reject`, add elements with
+    /// [`push_partial`](#method.push_partial), since `push` panics when
+    /// it compares a NaN.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::{MinMaxHeap, NanPolicy};
+    ///
+    /// let mut heap = MinMaxHeap::new_partial(NanPolicy::AsMax);
+    /// heap.push(2.5);
+    /// heap.push(f64::NAN);
+    /// heap.push(-1.0);
+    ///
+    /// assert_eq!(heap.pop_min(), Some(-1.0));
+    /// assert!(heap.pop_max().unwrap().is_nan());
+    ///
+    /// let mut heap = MinMaxHeap::new_partial(NanPolicy::Reject);
+    /// assert_eq!(heap.push_partial(1.0), Ok(()));
+    /// assert!(heap.push_partial(f64::NAN).is_err());
+    /// ```
     ///
     /// *O*(1).
-    pub fn peek_max(&self) -> Option<&T> {
-        // SAFETY: `i` is a valid index in `self.0`
-        self.find_max().map(|i| unsafe { self.0.get_unchecked(i) })
+    pub fn new_partial(nan: NanPolicy) -> Self {
+        MinMaxHeap::with_comparator(PartialComparator(nan))
     }
 
-    /// Returns a mutable reference to the maximum element, if any. Once this reference is dropped,
-    /// the heap is adjusted if necessary.
+    /// Creates a `MinMaxHeap` for a type that is only `PartialOrd` from
+    /// the elements of `vec`, in a single pass.
     ///
-    /// Note: If the `PeekMaxMut` value is leaked, the heap may be in an
-    /// inconsistent state.
+    /// # Panics
     ///
-    /// *O*(1) for the peek; *O*(log *n*) when the reference is dropped.
-    pub fn peek_max_mut(&mut self) -> Option<PeekMaxMut<T>> {
-        self.find_max().map(move |i| PeekMaxMut {
-            heap: self,
-            max_index: i,
-            sift: false,
-        })
+    /// Panics if `nan` is `NanPolicy::Reject` and `vec` contains a NaN.
+    ///
+    /// *O*(*n*).
+    pub fn from_vec_partial(vec: Vec<T>, nan: NanPolicy) -> Self {
+        let cmp = PartialComparator(nan);
+        assert!(vec.iter().all(|element| cmp.accepts(element)),
+                "MinMaxHeap::from_vec_partial: NaN rejected");
+        MinMaxHeap::from_vec_with_comparator(vec, cmp)
     }
 
-    fn find_max_slice(slice: &[T]) -> Option<usize> {
-        match slice.len() {
-            0 => None,
-            1 => Some(0),
-            2 => Some(1),
-            _ => if slice[1] > slice[2] { Some(1) } else { Some(2) },
+    /// Adds an element to the heap, unless it is a NaN and the heap’s
+    /// policy is `NanPolicy::Reject`, in which case it is returned.
+    ///
+    /// Amortized *O*(log *n*).
+    pub fn push_partial(&mut self, element: T) -> Result<(), T> {
+        if self.1.accepts(&element) {
+            self.push(element);
+            Ok(())
+        } else {
+            Err(element)
         }
     }
+}
 
-    fn find_max(&self) -> Option<usize> {
-        Self::find_max_slice(&self.0)
+#[cfg(feature = "alloc")]
+impl<T, C: Compare<T>> MinMaxHeap<T, C> {
+    /// Creates a `MinMaxHeap` ordered by `cmp` from the elements of
+    /// `vec`, in a single pass.
+    ///
+    /// If `vec` is already sorted, in either direction, the elements
+    /// are arranged into a heap in place, without comparing them
+    /// further.
+    ///
+    /// *O*(*n*).
+    pub fn from_vec_with_comparator(vec: Vec<T>, cmp: C) -> Self {
+        let mut heap = MinMaxHeap(vec, cmp);
+
+        match Self::sorted_order(&heap.0, &heap.1) {
+            Some(descending) => {
+                if descending {
+                    heap.0.reverse();
+                }
+                Self::arrange_sorted_in_place(&mut heap.0);
+                paranoid_check!(heap);
+            }
+            None => heap.rebuild(),
+        }
+
+        heap
     }
 
-    /// Removes the minimum element, if any.
+    /// Creates a `MinMaxHeap` ordered by `cmp` from a vector that is
+    /// already in heap order under `cmp`, without rebuilding it.
     ///
-    /// *O*(log *n*).
-    pub fn pop_min(&mut self) -> Option<T> {
-        self.0.pop().map(|mut item| {
-            if let Some(min) = self.0.first_mut() {
-                mem::swap(&mut item, min);
-                // SAFETY: `self.0` is not empty
-                unsafe {
-                    self.trickle_down_min(0);
-                }
+    /// Debug builds check the vector with [`is_valid`].
+    ///
+    /// # Safety
+    ///
+    /// As for [`from_vec_unchecked`], `vec` must satisfy the
+    /// min-max-heap property under `cmp`.
+    ///
+    /// *O*(1).
+    ///
+    /// [`from_vec_unchecked`]: #method.from_vec_unchecked
+    /// [`is_valid`]: #method.is_valid
+    #[cfg(not(feature = "safe"))]
+    pub unsafe fn from_vec_with_comparator_unchecked(vec: Vec<T>, cmp: C) -> Self {
+        let heap = MinMaxHeap(vec, cmp);
+        debug_assert!(heap.is_valid(), "from_vec_unchecked: vector is not in heap order");
+        paranoid_check!(heap);
+        heap
+    }
+
+    /// Whether `slice` is sorted: `Some(false)` if ascending,
+    /// `Some(true)` if descending and not ascending, and `None` if
+    /// neither. Unsorted input usually gives up after a few
+    /// comparisons.
+    fn sorted_order(slice: &[T], cmp: &C) -> Option<bool> {
+        let mut pairs = slice.windows(2);
+        let descending = loop {
+            match pairs.next() {
+                None => return Some(false),
+                Some(pair) => match cmp.compare(&pair[0], &pair[1]) {
+                    Ordering::Equal   => continue,
+                    Ordering::Less    => break false,
+                    Ordering::Greater => break true,
+                },
             }
+        };
 
-            item
-        })
+        let sorted = pairs.all(|pair| if descending {
+            cmp.compares_ge(&pair[0], &pair[1])
+        } else {
+            cmp.compares_le(&pair[0], &pair[1])
+        });
+
+        if sorted { Some(descending) } else { None }
     }
 
-    /// Removes the maximum element, if any.
+    /// Rearranges ascending `elements` into a min-max-heap in place,
+    /// without comparing them, in *O*(*n*) time.
     ///
-    /// *O*(log *n*).
-    pub fn pop_max(&mut self) -> Option<T> {
-        self.find_max().map(|max| {
-            let mut item = self.0.pop().unwrap();
+    /// The smallest elements fill the min levels in ascending order,
+    /// and the rest fill the max levels in descending order. Then each
+    /// element on a min level is at most its descendants, which are
+    /// either later on the min levels or on the max levels, and each
+    /// element on a max level is at least its descendants, likewise.
+    fn arrange_sorted_in_place(elements: &mut [T]) {
+        if mem::size_of::<T>() == 0 {
+            return;
+        }
 
-            if let Some(max_element) = self.0.get_mut(max) {
-                mem::swap(&mut item, max_element);
-                // SAFETY: `max` is a valid index in `self.0`
-                unsafe {
-                    self.trickle_down_max(max);
-                }
+        let len = elements.len();
+
+        // The sizes of the levels, which are clipped to `len`, and how
+        // many elements go on the min and max levels.
+        let mut levels = 0;
+        let (mut mins, mut maxes) = (0, 0);
+        while (1 << levels) - 1 < len {
+            let size = ((1 << (levels + 1)) - 1).min(len) - ((1 << levels) - 1);
+            if levels % 2 == 0 { mins += size } else { maxes += size }
+            levels += 1;
+        }
+
+        // Now the min levels’ elements come first, level by level, and
+        // then the max levels’, level by level.
+        elements[mins ..].reverse();
+
+        // From the bottom level up, move each min level’s elements past
+        // the max levels’ that belong above them. Levels grow
+        // geometrically, so this moves each element a constant number of
+        // times on average.
+        for level in (0 .. levels).rev() {
+            let size = ((1 << (level + 1)) - 1).min(len) - ((1 << level) - 1);
+            if level % 2 == 0 {
+                elements[mins - size .. mins + maxes].rotate_left(size);
+                mins -= size;
+            } else {
+                maxes -= size;
             }
+        }
+    }
 
-            item
-        })
+    /// Adds an element to the heap.
+    ///
+    /// Amortized *O*(log *n*); worst-case *O*(*n*) when the backing vector needs to
+    /// grow.
+    pub fn push(&mut self, element: T) {
+        trace_span!(TRACE, "push", self.len());
+        self.0.push(element);
+        push_slice(&mut self.0, &self.1);
+        paranoid_check!(self);
     }
 
-    /// Pushes an element, then pops the minimum element.
+    /// Adds an element to the heap, or returns an error, rather than
+    /// aborting, if the backing vector needs to grow and cannot.
     ///
-    /// Calling `push_pop_min` is equivalent to calling [`push`]
-    /// followed by [`pop_min`], except that it avoids allocation.
+    /// When this returns an error, `element` has been dropped and the
+    /// heap is unchanged.
     ///
-    /// This means that if the element you give it is smaller than
-    /// anything already in the heap then `push_pop_min` gives you back
-    /// that same element. If the heap is empty then `push_pop_min`
-    /// returns its argument and leaves the heap empty.  In order to
-    /// always insert the element, even if it would be the new minimum,
-    /// see [`replace_min`], which equivalent to [`pop_min`] followed by
-    /// [`push`].
+    /// # Examples
     ///
-    /// [`push`]:
-    ///     <struct.MinMaxHeap.html#method.push>
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
     ///
-    /// [`pop_min`]:
-    ///     <struct.MinMaxHeap.html#method.pop_min>
+    /// let mut heap = MinMaxHeap::new();
+    /// heap.try_push(3).expect("out of memory");
+    /// heap.try_push(1).expect("out of memory");
     ///
-    /// [`replace_min`]:
-    ///     <struct.MinMaxHeap.html#method.replace_min>
+    /// assert_eq!(heap.peek_min(), Some(&1));
+    /// ```
     ///
-    /// *O*(log *n*).
-    pub fn push_pop_min(&mut self, mut element: T) -> T {
-        if let Some(mut min) = self.peek_min_mut() {
-            if element > *min {
-                mem::swap(&mut element, &mut min);
-            }
-        }
-        element
+    /// Amortized *O*(log *n*); worst-case *O*(*n*) when the backing vector needs to
+    /// grow.
+    pub fn try_push(&mut self, element: T) -> Result<(), TryReserveError> {
+        self.0.try_reserve(1)?;
+        self.push(element);
+        Ok(())
     }
 
-    /// Pushes an element, then pops the maximum element.
+    /// Adds an element to the heap if it has spare capacity, and
+    /// otherwise gives the element back, so that it never allocates.
     ///
-    /// Calling `push_pop_max` is equivalent to calling [`push`]
-    /// followed by [`pop_max`], except that it avoids allocation.
+    /// # Examples
     ///
-    /// This means that if the element you give it is greater than
-    /// anything already in the heap then `push_pop_max` gives you back
-    /// that same element. If the heap is empty then `push_pop_max`
-    /// returns its argument and leaves the heap empty.  In order to
-    /// always insert the element, even if it would be the new maximum,
-    /// see [`replace_max`], which equivalent to [`pop_max`] followed by
-    /// [`push`].
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
     ///
-    /// [`push`]:
-    ///     <struct.MinMaxHeap.html#method.push>
+    /// let mut heap = MinMaxHeap::with_capacity(2);
+    /// assert_eq!(heap.push_within_capacity(5), Ok(()));
+    /// assert_eq!(heap.push_within_capacity(8), Ok(()));
     ///
-    /// [`pop_max`]:
-    ///     <struct.MinMaxHeap.html#method.pop_max>
-    ///
-    /// [`replace_max`]:
-    ///     <struct.MinMaxHeap.html#method.replace_max>
+    /// let capacity = heap.capacity();
+    /// while heap.len() < capacity {
+    ///     heap.push_within_capacity(0).unwrap();
+    /// }
+    /// assert_eq!(heap.push_within_capacity(3), Err(3));
+    /// ```
     ///
     /// *O*(log *n*).
-    pub fn push_pop_max(&mut self, mut element: T) -> T {
-        if let Some(mut max) = self.peek_max_mut() {
-            if element < *max {
-                mem::swap(&mut element, &mut max);
-            }
+    pub fn push_within_capacity(&mut self, element: T) -> Result<(), T> {
+        if self.len() == self.capacity() {
+            Err(element)
+        } else {
+            self.push(element);
+            Ok(())
         }
-        element
     }
 
-    /// Pops the minimum element and pushes a new element, in an
-    /// optimized fashion.
+    /// Moves all the elements of `vec` into the heap.
     ///
-    /// Except for avoiding allocation, calling `replace_min` is
-    /// equivalent to calling [`pop_min`] followed by [`push`].
+    /// The elements are moved into the heap’s storage wholesale, rather
+    /// than pushed one at a time, and the heap is then restored by
+    /// bubbling up each of them or, when they are many relative to the
+    /// heap, by rebuilding it. If the heap is empty and `vec` has at
+    /// least as much capacity, the heap takes over `vec`’s buffer.
     ///
-    /// This means that `replace_min` will always leaves the element you
-    /// give it in the heap and gives you back the *old* minimum
-    /// element; it never returns the element you just gave it. If the
-    /// heap is empty there is no old minimum, so `replace_min` pushes
-    /// the element and returns `None`. If you want to get back the
-    /// smallest element *including* the one you are about to push, see
-    /// [`push_pop_min`].
+    /// # Examples
     ///
-    /// [`push`]:
-    ///     <struct.MinMaxHeap.html#method.push>
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
     ///
-    /// [`pop_min`]:
-    ///     <struct.MinMaxHeap.html#method.pop_min>
+    /// let mut heap = MinMaxHeap::new();
+    /// heap.extend_from_vec(vec![5, 1, 8]);
+    /// heap.extend_from_vec(vec![3, 9]);
     ///
-    /// [`push_pop_min`]:
-    ///     <struct.MinMaxHeap.html#method.push_pop_min>
+    /// assert_eq!(heap.into_vec_asc(), [1, 3, 5, 8, 9]);
+    /// ```
     ///
-    /// *O*(log *n*).
-    pub fn replace_min(&mut self, mut element: T) -> Option<T> {
-        if let Some(mut min) = self.peek_min_mut() {
-            mem::swap(&mut element, &mut min);
-            return Some(element);
+    /// *O*(*n* + *k*) or *O*(*k* log *n*), whichever is less, where *k*
+    /// is the length of `vec`.
+    pub fn extend_from_vec(&mut self, mut vec: Vec<T>) {
+        if self.is_empty() && vec.capacity() >= self.capacity() {
+            mem::swap(&mut self.0, &mut vec);
+            self.rebuild();
+        } else {
+            let start = self.len();
+            self.0.append(&mut vec);
+            self.rebuild_tail(start);
         }
-
-        // Heap was empty, so no reordering is necessary
-        self.0.push(element);
-        None
     }
 
-    /// Pops the maximum element and pushes a new element, in an
-    /// optimized fashion.
-    ///
-    /// Except for avoiding allocation, calling `replace_max` is
-    /// equivalent to calling [`pop_max`] followed by [`push`].
+    /// Clones all the elements of `src` into the heap.
     ///
-    /// This means that `replace_max` will always leaves the element you
-    /// give it in the heap and gives you back the *old* maximum
-    /// element; it never returns the element you just gave it. If the
-    /// heap is empty there is no old maximum, so `replace_max` pushes
-    /// the element and returns `None`. If you want to get back the
-    /// largest element *including* the one you are about to push, see
-    /// [`push_pop_max`].
+    /// The elements are appended to the heap’s storage in one go, which
+    /// for `Copy` types is a plain memory copy, and the heap is then
+    /// restored as in [`extend_from_vec`].
     ///
-    /// [`push`]:
-    ///     <struct.MinMaxHeap.html#method.push>
+    /// [`extend_from_vec`]: #method.extend_from_vec
     ///
-    /// [`pop_max`]:
-    ///     <struct.MinMaxHeap.html#method.pop_max>
+    /// # Examples
     ///
-    /// [`push_pop_max`]:
-    ///     <struct.MinMaxHeap.html#method.push_pop_max>
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
     ///
-    /// *O*(log *n*).
-    pub fn replace_max(&mut self, mut element: T) -> Option<T> {
-        if let Some(mut max) = self.peek_max_mut() {
-            // If `element` is the new min, swap it with the current min
-            // (unless the min is the same as the max)
-            if max.heap.len() > 1 {
-                let min = &mut max.heap.0[0];
-                if element < *min {
-                    mem::swap(&mut element, min);
-                }
-            }
-            mem::swap(&mut element, &mut max);
-            return Some(element);
-        }
-
-        // Heap was empty, so no reordering is necessary
-        self.0.push(element);
-        None
-    }
-
-    /// Returns an ascending (sorted) vector, reusing the heap’s
-    /// storage.
+    /// let mut heap = MinMaxHeap::from(vec![4, 6]);
+    /// heap.extend_from_slice(&[5, 1, 8]);
     ///
-    /// *O*(*n* log *n*).
-    pub fn into_vec_asc(mut self) -> Vec<T> {
-        let mut elements = &mut *self.0;
-        while elements.len() > 1 {
-            let max = Self::find_max_slice(elements).unwrap();
-            let (last, elements_rest) = elements.split_last_mut().unwrap();
-            elements = elements_rest;
-            if let Some(max_element) = elements.get_mut(max) {
-                mem::swap(max_element, last);
-                // SAFETY: `max < elements.len()`
-                unsafe {
-                    Self::trickle_down_slice(elements, max);
-                }
-            }
-        }
-        self.into_vec()
-    }
-
-    /// Returns an descending (sorted) vector, reusing the heap’s
-    /// storage.
+    /// assert_eq!(heap.into_vec_asc(), [1, 4, 5, 6, 8]);
+    /// ```
     ///
-    /// *O*(*n* log *n*).
-    pub fn into_vec_desc(mut self) -> Vec<T> {
-        let mut elements = &mut *self.0;
-        while elements.len() > 1 {
-            let (last, elements_rest) = elements.split_last_mut().unwrap();
-            elements = elements_rest;
-            mem::swap(&mut elements[0], last);
-            // SAFETY: `elements` is not empty
-            unsafe {
-                Self::trickle_down_min_slice(elements, 0);
-            }
-        }
-        self.into_vec()
+    /// *O*(*n* + *k*) or *O*(*k* log *n*), whichever is less, where *k*
+    /// is the length of `src`.
+    pub fn extend_from_slice(&mut self, src: &[T])
+        where T: Clone
+    {
+        let guard = RebuildOnDrop { start: self.len(), heap: self };
+        guard.heap.0.extend_from_slice(src);
     }
 
-    /// Retains only the elements specified by the predicate.
+    /// Pushes elements that are already in ascending order.
     ///
-    /// In other words, remove all elements `e` such that `f(&e)` returns
-    /// `false`. The elements are visited in unsorted (and unspecified) order.
+    /// If the heap is empty, the ordering lets this arrange the elements
+    /// into a heap without comparing them at all. Otherwise, the ones
+    /// that are at least the heap’s maximum are found by binary search
+    /// and each moved straight up to the max levels without comparisons,
+    /// while the ones before them are bubbled up as by [`push`]. If the
+    /// elements are not in fact sorted, the heap will behave in an
+    /// unspecified (but memory-safe) way afterward.
     ///
     /// # Examples
     ///
-    /// Basic usage:
-    ///
     /// ```
     /// use min_max_heap::MinMaxHeap;
     ///
-    /// let mut heap = MinMaxHeap::from(vec![-10, -5, 1, 2, 4, 13]);
+    /// let mut heap = MinMaxHeap::new();
+    /// heap.push_sorted_asc(1 ..= 10);
     ///
-    /// heap.retain(|x| x % 2 == 0); // only keep even numbers
+    /// assert_eq!(heap.peek_min(), Some(&1));
+    /// assert_eq!(heap.peek_max(), Some(&10));
     ///
-    /// let mut vec = heap.into_vec();
-    /// vec.sort();
-    /// assert_eq!(vec, [-10, 2, 4])
+    /// heap.push_sorted_asc(11 ..= 20);
+    /// assert_eq!(heap.peek_max(), Some(&20));
     /// ```
-    pub fn retain<F>(&mut self, f: F)
-    where
-        F: FnMut(&T) -> bool,
-    {
-        self.0.retain(f);
-        self.rebuild();
+    ///
+    /// *O*(*k*) when the heap is empty, where *k* is the number of
+    /// elements pushed, with no comparisons up to 256 KiB of them, as in
+    /// [`from_sorted_iter_asc`]. Otherwise *O*(*k* log *n*), with
+    /// *O*(log *k*) comparisons for the elements at least the heap’s
+    /// maximum and *O*(log *n*) for each of the rest.
+    ///
+    /// [`from_sorted_iter_asc`]: #method.from_sorted_iter_asc
+    /// [`push`]: #method.push
+    pub fn push_sorted_asc<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.push_sorted(iter, false)
     }
 
-    /// Caller must ensure that `pos` is a valid index in `self.0`.
-    #[inline]
-    unsafe fn trickle_down_min(&mut self, pos: usize) {
-        Self::trickle_down_min_slice(&mut self.0, pos);
+    /// Pushes elements that are already in descending order.
+    ///
+    /// This is like [`push_sorted_asc`], with the roles of the minimum
+    /// and maximum swapped: elements at most the heap’s minimum move
+    /// straight up to the min levels without comparisons.
+    ///
+    /// [`push_sorted_asc`]: #method.push_sorted_asc
+    ///
+    /// *O*(*k*) when the heap is empty, where *k* is the number of
+    /// elements pushed, with no comparisons up to 256 KiB of them, as in
+    /// [`from_sorted_iter_asc`]. Otherwise *O*(*k* log *n*), with
+    /// *O*(log *k*) comparisons for the elements at most the heap’s
+    /// minimum and *O*(log *n*) for each of the rest.
+    ///
+    /// [`from_sorted_iter_asc`]: #method.from_sorted_iter_asc
+    pub fn push_sorted_desc<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.push_sorted(iter, true)
     }
 
-    /// Caller must ensure that `pos` is a valid index in `self.0`.
-    #[inline]
-    unsafe fn trickle_down_max(&mut self, pos: usize) {
-        debug_assert!(pos < self.len());
-        Hole::new(&mut self.0, pos).trickle_down_max();
-    }
+    fn push_sorted<I: IntoIterator<Item = T>>(&mut self, iter: I, descending: bool) {
+        let start = self.len();
+        // The extreme that the new elements run toward, if there is one.
+        let extreme = if descending {
+            if self.is_empty() { None } else { Some(0) }
+        } else {
+            self.find_max()
+        };
+
+        // If `iter` panics, the guard restores the heap; otherwise, the
+        // elements are put in place below instead.
+        let guard = RebuildOnDrop { start, heap: self };
+        guard.heap.0.extend(iter);
+        mem::forget(guard);
+
+        let extreme = match extreme {
+            Some(extreme) => extreme,
+            None => {
+                if descending {
+                    self.0.reverse();
+                }
+                self.heapify_sorted();
+                return;
+            }
+        };
+
+        // The new elements from `split` on are beyond everything already
+        // in the heap, and so is each of them beyond those before it.
+        let split = {
+            let (old, new) = self.0.split_at(start);
+            let (extreme, cmp) = (&old[extreme], &self.1);
+            start + new.partition_point(|element| stats::compared(if descending {
+                cmp.compares_gt(element, extreme)
+            } else {
+                cmp.compares_lt(element, extreme)
+            }))
+        };
+
+        for pos in start .. self.len() {
+            // SAFETY: `pos < len`, and from `split` on each element is
+            // at least (or at most) every element before it
+            unchecked! {
+                if pos < split {
+                    self.bubble_up(pos);
+                } else {
+                    self.bubble_up_extreme(pos, !descending);
+                }
+            }
+        }
 
-    /// Caller must ensure that `pos` is a valid index in `self.0`.
-    #[inline]
-    unsafe fn trickle_down(&mut self, pos: usize) {
-        Self::trickle_down_slice(&mut self.0, pos);
+        paranoid_check!(self);
     }
 
-    /// Caller must ensure that `pos` is a valid index in `slice`.
-    #[inline]
-    unsafe fn trickle_down_min_slice(slice: &mut [T], pos: usize) {
-        debug_assert!(pos < slice.len());
-        Hole::new(slice, pos).trickle_down_min();
+    /// Turns the heap’s elements, which are in ascending order, into a
+    /// valid heap.
+    ///
+    /// Arranging them moves each one once into a fresh buffer, but
+    /// reads the old one a level at a time, so it only beats rebuilding
+    /// in place while both buffers stay in cache.
+    fn heapify_sorted(&mut self) {
+        if self.len().saturating_mul(mem::size_of::<T>()) <= ARRANGE_SORTED_MAX_BYTES {
+            Self::arrange_sorted(&mut self.0);
+            paranoid_check!(self);
+        } else {
+            self.rebuild();
+        }
     }
 
-    /// Caller must ensure that `pos` is a valid index in `slice`.
-    #[inline]
-    unsafe fn trickle_down_slice(slice: &mut [T], pos: usize) {
-        debug_assert!(pos < slice.len());
-        Hole::new(slice, pos).trickle_down();
-    }
+    /// Rearranges ascending `elements` into a min-max-heap without
+    /// comparing them, in *O*(*n*) time.
+    #[cfg(not(feature = "safe"))]
+    fn arrange_sorted(elements: &mut Vec<T>) {
+        if mem::size_of::<T>() == 0 {
+            return;
+        }
 
-    /// Caller must ensure that `pos` is a valid index in `self.0`.
-    #[inline]
-    unsafe fn bubble_up(&mut self, pos: usize) {
-        debug_assert!(pos < self.len());
-        Hole::new(&mut self.0, pos).bubble_up();
+        let len = elements.len();
+        let mut arranged = Vec::<T>::with_capacity(elements.capacity());
+        // SAFETY: `sorted_ranks` gives each position below `len` once,
+        // in order, and each rank below `len` once, so this moves each
+        // element once into the next slot of `arranged`. Nothing in
+        // between can panic, and `elements` forgets the moved elements
+        // before `arranged` owns them.
+        unsafe {
+            elements.set_len(0);
+            let (src, dst) = (elements.as_ptr(), arranged.as_mut_ptr());
+            sorted_ranks(len, |pos, rank| {
+                ptr::copy_nonoverlapping(src.add(rank), dst.add(pos), 1);
+            });
+            arranged.set_len(len);
+        }
+        *elements = arranged;
     }
 
-    fn rebuild(&mut self) {
-        for n in (0..(self.len() / 2)).rev() {
-            // SAFETY: `n < self.len()`
-            unsafe {
-                self.trickle_down(n);
+    /// Rearranges ascending `elements` into a min-max-heap without
+    /// comparing them, in *O*(*n*) time.
+    ///
+    /// This puts each element in place by swapping, which is slower than
+    /// moving each one once into a fresh buffer.
+    #[cfg(feature = "safe")]
+    fn arrange_sorted(elements: &mut [T]) {
+        if mem::size_of::<T>() == 0 {
+            return;
+        }
+
+        // `dest[i]` is the position that the `i`th smallest element
+        // belongs in. Each swap puts one element where it belongs.
+        let mut dest = alloc::vec![0; elements.len()];
+        sorted_ranks(elements.len(), |pos, rank| dest[rank] = pos);
+
+        for i in 0 .. dest.len() {
+            while dest[i] != i {
+                let j = dest[i];
+                elements.swap(i, j);
+                dest.swap(i, j);
             }
         }
     }
-}
 
-impl<T> MinMaxHeap<T> {
-    /// Drops all items from the heap.
+    /// Gets a reference to the minimum element, if any.
     ///
-    /// *O*(*n*)
-    pub fn clear(&mut self) {
-        self.0.clear();
+    /// *O*(1).
+    pub fn peek_min(&self) -> Option<&T> {
+        self.0.first()
     }
 
-    /// The number of elements the heap can hold without reallocating.
+    /// Returns a mutable reference to the minimum element, if any. Once this reference is dropped,
+    /// the heap is adjusted if necessary.
     ///
-    /// *O*(1)
-    pub fn capacity(&self) -> usize {
-        self.0.capacity()
+    /// Note: If the `PeekMinMut` value is leaked, the heap may be in an
+    /// inconsistent state.
+    ///
+    /// *O*(1) for the peek; *O*(log *n*) when the reference is dropped.
+    pub fn peek_min_mut(&mut self) -> Option<PeekMinMut<'_, T, C>> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(PeekMinMut {
+                heap: self,
+                sift: false,
+            })
+        }
     }
 
-    /// Reserves the minimum capacity for exactly `additional` more
-    /// elements to be inserted in the given `MinMaxHeap`.
+    /// Gets a reference to the maximum element, if any.
     ///
-    /// *O*(*n*)
+    /// *O*(1).
+    pub fn peek_max(&self) -> Option<&T> {
+        // SAFETY: `i` is a valid index in `self.0`
+        self.find_max().map(|i| unchecked! { self.get_at(i) })
+    }
+
+    /// Returns a mutable reference to the maximum element, if any. Once this reference is dropped,
+    /// the heap is adjusted if necessary.
     ///
-    /// # Panics
+    /// Note: If the `PeekMaxMut` value is leaked, the heap may be in an
+    /// inconsistent state.
     ///
-    /// Panics if the new capacity overflows `usize`.
-    pub fn reserve_exact(&mut self, additional: usize) {
-        self.0.reserve_exact(additional)
+    /// *O*(1) for the peek; *O*(log *n*) when the reference is dropped.
+    pub fn peek_max_mut(&mut self) -> Option<PeekMaxMut<'_, T, C>> {
+        self.find_max().map(move |i| PeekMaxMut {
+            heap: self,
+            max_index: i,
+            sift: false,
+        })
     }
 
-    /// Reserves the minimum capacity for at least `additional` more
-    /// elements to be inserted in the given `MinMaxHeap`.
+    fn find_max(&self) -> Option<usize> {
+        find_max_slice(&self.0, &self.1)
+    }
+
+    /// Returns a read-only cursor at the minimum element, which is the
+    /// root of the implicit tree, if any.
     ///
-    /// *O*(*n*)
+    /// See [`Cursor`] for an example.
     ///
-    /// # Panics
+    /// *O*(1).
     ///
-    /// Panics if the new capacity overflows `usize`.
-    pub fn reserve(&mut self, additional: usize) {
-        self.0.reserve(additional)
+    /// [`Cursor`]: struct.Cursor.html
+    pub fn cursor_min(&self) -> Option<Cursor<'_, T>> {
+        if self.0.is_empty() {
+            None
+        } else {
+            Some(Cursor::new(&self.0, 0))
+        }
     }
 
-    /// Discards extra capacity.
+    /// Returns a read-only cursor at the maximum element, if any.
     ///
-    /// *O*(*n*)
-    pub fn shrink_to_fit(&mut self) {
-        self.0.shrink_to_fit()
+    /// *O*(1).
+    pub fn cursor_max(&self) -> Option<Cursor<'_, T>> {
+        self.find_max().map(|i| Cursor::new(&self.0, i))
     }
 
-    /// Consumes the `MinMaxHeap` and returns its elements in a vector
-    /// in arbitrary order.
+    /// Returns references to the `k` smallest elements in ascending
+    /// order, leaving the heap untouched.
     ///
-    /// *O*(*n*)
-    pub fn into_vec(self) -> Vec<T> {
-        self.0
+    /// If the heap holds fewer than `k` elements then all of them are
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let heap = MinMaxHeap::from(vec![5, 1, 8, 3, 9, 2]);
+    ///
+    /// assert_eq!(heap.peek_k_min(3), [&1, &2, &3]);
+    /// assert_eq!(heap.peek_k_max(2), [&9, &8]);
+    /// assert_eq!(heap.len(), 6);
+    /// ```
+    ///
+    /// *O*(*k* log *k*).
+    pub fn peek_k_min(&self, k: usize) -> Vec<&T> {
+        self.iter_asc().take(k).collect()
     }
 
-    /// Returns a borrowing iterator over the min-max-heap’s elements in
-    /// arbitrary order.
+    /// Returns references to the `k` largest elements in descending
+    /// order, leaving the heap untouched.
     ///
-    /// *O*(1) on creation, and *O*(1) for each `next()` operation.
-    pub fn iter(&self) -> Iter<T> {
-        Iter(self.0.iter())
+    /// If the heap holds fewer than `k` elements then all of them are
+    /// returned.
+    ///
+    /// *O*(*k* log *k*).
+    pub fn peek_k_max(&self, k: usize) -> Vec<&T> {
+        self.iter_desc().take(k).collect()
     }
 
-    /// Returns a draining iterator over the min-max-heap’s elements in
-    /// arbitrary order.
+    /// Gets a reference to the `k`th smallest element, counting from
+    /// zero, if any.
     ///
-    /// *O*(1) on creation, and *O*(1) for each `next()` operation.
-    pub fn drain(&mut self) -> Drain<T> {
-        Drain(self.0.drain(..))
+    /// `peek_kth_min(0)` is the same as [`peek_min`].
+    ///
+    /// [`peek_min`]:
+    ///     <struct.MinMaxHeap.html#method.peek_min>
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let heap = MinMaxHeap::from(vec![5, 1, 8, 3, 9, 2]);
+    ///
+    /// assert_eq!(heap.peek_kth_min(2), Some(&3));
+    /// assert_eq!(heap.peek_kth_max(1), Some(&8));
+    /// assert_eq!(heap.peek_kth_min(6), None);
+    /// ```
+    ///
+    /// *O*(*k* log *k*).
+    pub fn peek_kth_min(&self, k: usize) -> Option<&T> {
+        if k >= self.len() {
+            None
+        } else {
+            self.iter_asc().nth(k)
+        }
     }
 
-    /// Returns a draining iterator over the min-max-heap’s elements in
-    /// ascending (min-first) order.
+    /// Gets a reference to the `k`th largest element, counting from
+    /// zero, if any.
     ///
-    /// *O*(1) on creation, and *O*(log *n*) for each `next()` operation.
-    pub fn drain_asc(&mut self) -> DrainAsc<T> {
-        DrainAsc(self)
+    /// `peek_kth_max(0)` is the same as [`peek_max`].
+    ///
+    /// [`peek_max`]:
+    ///     <struct.MinMaxHeap.html#method.peek_max>
+    ///
+    /// *O*(*k* log *k*).
+    pub fn peek_kth_max(&self, k: usize) -> Option<&T> {
+        if k >= self.len() {
+            None
+        } else {
+            self.iter_desc().nth(k)
+        }
+    }
+
+    /// Finds the `k`th smallest element, counting from zero, by
+    /// selecting over the heap’s storage and then rebuilding it.
+    ///
+    /// Unlike [`peek_kth_min`], the cost does not grow with `k`, which
+    /// makes this the better choice for order statistics deep inside a
+    /// large heap, such as the median. Returns `None` if `k` is out of
+    /// bounds.
+    ///
+    /// [`peek_kth_min`]:
+    ///     <struct.MinMaxHeap.html#method.peek_kth_min>
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let mut heap: MinMaxHeap<_> = (0 .. 101).rev().collect();
+    ///
+    /// assert_eq!(heap.select_nth_min(50), Some(&50));
+    /// assert_eq!(heap.select_nth_max(10), Some(&90));
+    /// assert_eq!(heap.peek_min(), Some(&0));
+    /// ```
+    ///
+    /// Expected *O*(*n*).
+    pub fn select_nth_min(&mut self, k: usize) -> Option<&T> {
+        let len = self.len();
+        if k >= len {
+            return None;
+        }
+
+        // Park the selected element at the end, heapify the rest, and
+        // then insert it, keeping track of where it lands.
+        let cmp = &self.1;
+        self.0.select_nth_unstable_by(k, |a, b| cmp.compare(a, b));
+        self.0.swap(k, len - 1);
+        rebuild_slice(&mut self.0[.. len - 1], &self.1);
+        // SAFETY: `len - 1` is the index of the last element
+        let pos = unchecked! { self.bubble_up(len - 1) };
+        paranoid_check!(self);
+        self.0.get(pos)
+    }
+
+    /// Finds the `k`th largest element, counting from zero, by
+    /// selecting over the heap’s storage and then rebuilding it.
+    ///
+    /// Returns `None` if `k` is out of bounds.
+    ///
+    /// Expected *O*(*n*).
+    pub fn select_nth_max(&mut self, k: usize) -> Option<&T> {
+        let len = self.len();
+        if k >= len {
+            None
+        } else {
+            self.select_nth_min(len - 1 - k)
+        }
+    }
+
+    /// Returns a borrowing iterator over the min-max-heap’s elements in
+    /// ascending (min-first) order.
+    ///
+    /// The heap is not modified; instead the iterator keeps an
+    /// auxiliary queue of the subtrees it has yet to visit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let heap = MinMaxHeap::from(vec![5, 1, 8, 3]);
+    ///
+    /// assert_eq!(heap.iter_asc().collect::<Vec<_>>(), [&1, &3, &5, &8]);
+    /// assert_eq!(heap.iter_desc().collect::<Vec<_>>(), [&8, &5, &3, &1]);
+    /// ```
+    ///
+    /// *O*(1) on creation, and *O*(log *n*) for each `next()` operation.
+    pub fn iter_asc(&self) -> IterAsc<'_, T, C> {
+        IterAsc(Frontier::new(&self.0, &self.1, true))
+    }
+
+    /// Returns a borrowing iterator over the min-max-heap’s elements in
+    /// descending (max-first) order.
+    ///
+    /// The heap is not modified; instead the iterator keeps an
+    /// auxiliary queue of the subtrees it has yet to visit.
+    ///
+    /// *O*(1) on creation, and *O*(log *n*) for each `next()` operation.
+    pub fn iter_desc(&self) -> IterDesc<'_, T, C> {
+        IterDesc(Frontier::new(&self.0, &self.1, false))
+    }
+
+    /// Returns a mutable reference to the first element, in arbitrary
+    /// order, that satisfies `pred`. Once this reference is dropped, the
+    /// heap is adjusted if necessary.
+    ///
+    /// Note: If the `ElementMut` value is leaked, the heap may be in an
+    /// inconsistent state.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let mut heap = MinMaxHeap::from(vec![1, 5, 3, 8]);
+    ///
+    /// *heap.get_mut_by(|&x| x == 3).unwrap() = 10;
+    /// assert_eq!(heap.peek_max(), Some(&10));
+    /// assert!(heap.get_mut_by(|&x| x == 3).is_none());
+    /// ```
+    ///
+    /// *O*(*n*) for the search; *O*(log *n*) when the reference is
+    /// dropped.
+    pub fn get_mut_by<F>(&mut self, pred: F) -> Option<ElementMut<'_, T, C>>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.0.iter().position(pred).map(move |pos| ElementMut {
+            heap: self,
+            pos,
+            sift: false,
+        })
+    }
+
+    /// Removes and returns the element at position `index`, where
+    /// positions are in the arbitrary order of [`iter`].
+    ///
+    /// The last element is moved into the vacated position, which is then
+    /// sifted up or down as needed.
+    ///
+    /// [`iter`]:
+    ///     <struct.MinMaxHeap.html#method.iter>
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let mut heap = MinMaxHeap::from(vec![1, 5, 3, 8]);
+    ///
+    /// let index = heap.iter().position(|&x| x == 5).unwrap();
+    /// assert_eq!(heap.swap_remove_index(index), 5);
+    /// assert_eq!(heap.into_vec_asc(), [1, 3, 8]);
+    /// ```
+    ///
+    /// *O*(log *n*).
+    pub fn swap_remove_index(&mut self, index: usize) -> T {
+        let result = self.0.swap_remove(index);
+        if index < self.len() {
+            // SAFETY: `index` is a valid index in `self.0`
+            unchecked! {
+                self.sift(index);
+            }
+        }
+        paranoid_check!(self);
+        result
+    }
+
+    /// Removes the minimum element, if any.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_min(&mut self) -> Option<T> {
+        trace_span!(TRACE, "pop_min", self.len());
+        if self.0.is_empty() {
+            return None;
+        }
+
+        let item = self.0.swap_remove(0);
+        trickle_down_extreme_slice(&mut self.0, 0, &self.1);
+        paranoid_check!(self);
+        Some(item)
+    }
+
+    /// Removes the maximum element, if any.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_max(&mut self) -> Option<T> {
+        trace_span!(TRACE, "pop_max", self.len());
+        let max = self.find_max()?;
+        let item = self.0.swap_remove(max);
+        trickle_down_extreme_slice(&mut self.0, max, &self.1);
+        paranoid_check!(self);
+        Some(item)
+    }
+
+    /// Pushes an element, then pops the minimum element.
+    ///
+    /// Calling `push_pop_min` is equivalent to calling [`push`]
+    /// followed by [`pop_min`], except that it avoids allocation.
+    ///
+    /// This means that if the element you give it is smaller than
+    /// anything already in the heap then `push_pop_min` gives you back
+    /// that same element. If the heap is empty then `push_pop_min`
+    /// returns its argument and leaves the heap empty.  In order to
+    /// always insert the element, even if it would be the new minimum,
+    /// see [`replace_min`], which equivalent to [`pop_min`] followed by
+    /// [`push`].
+    ///
+    /// [`push`]:
+    ///     <struct.MinMaxHeap.html#method.push>
+    ///
+    /// [`pop_min`]:
+    ///     <struct.MinMaxHeap.html#method.pop_min>
+    ///
+    /// [`replace_min`]:
+    ///     <struct.MinMaxHeap.html#method.replace_min>
+    ///
+    /// *O*(log *n*).
+    pub fn push_pop_min(&mut self, element: T) -> T {
+        let element = push_pop_min_slice(&mut self.0, element, &self.1);
+        paranoid_check!(self);
+        element
+    }
+
+    /// Pushes an element, then pops the maximum element.
+    ///
+    /// Calling `push_pop_max` is equivalent to calling [`push`]
+    /// followed by [`pop_max`], except that it avoids allocation.
+    ///
+    /// This means that if the element you give it is greater than
+    /// anything already in the heap then `push_pop_max` gives you back
+    /// that same element. If the heap is empty then `push_pop_max`
+    /// returns its argument and leaves the heap empty.  In order to
+    /// always insert the element, even if it would be the new maximum,
+    /// see [`replace_max`], which equivalent to [`pop_max`] followed by
+    /// [`push`].
+    ///
+    /// [`push`]:
+    ///     <struct.MinMaxHeap.html#method.push>
+    ///
+    /// [`pop_max`]:
+    ///     <struct.MinMaxHeap.html#method.pop_max>
+    ///
+    /// [`replace_max`]:
+    ///     <struct.MinMaxHeap.html#method.replace_max>
+    ///
+    /// *O*(log *n*).
+    pub fn push_pop_max(&mut self, element: T) -> T {
+        let element = push_pop_max_slice(&mut self.0, element, &self.1);
+        paranoid_check!(self);
+        element
+    }
+
+    /// Pushes an element, evicting the maximum element if the heap
+    /// would otherwise hold more than `cap` elements.
+    ///
+    /// This keeps the heap holding the `cap` smallest elements it has
+    /// seen, and is equivalent to [`push`] when the heap has room. When
+    /// it is full, it behaves like [`push_pop_max`] and returns the
+    /// evicted element, which may be the one just given.
+    ///
+    /// The heap must not already hold more than `cap` elements, since
+    /// only one is evicted per push; use [`truncate_to_smallest`] first to
+    /// shrink it. Debug builds check this.
+    ///
+    /// [`push`]:
+    ///     <struct.MinMaxHeap.html#method.push>
+    ///
+    /// [`push_pop_max`]:
+    ///     <struct.MinMaxHeap.html#method.push_pop_max>
+    ///
+    /// [`truncate_to_smallest`]:
+    ///     <struct.MinMaxHeap.html#method.truncate_to_smallest>
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let mut heap = MinMaxHeap::new();
+    ///
+    /// assert_eq!(heap.push_capped_max(2, 5), None);
+    /// assert_eq!(heap.push_capped_max(2, 3), None);
+    /// assert_eq!(heap.push_capped_max(2, 4), Some(5));
+    /// assert_eq!(heap.push_capped_max(2, 9), Some(9));
+    /// assert_eq!(heap.into_vec_asc(), [3, 4]);
+    /// ```
+    ///
+    /// *O*(log *n*).
+    pub fn push_capped_max(&mut self, cap: usize, element: T) -> Option<T> {
+        debug_assert!(self.len() <= cap, "push_capped_max: heap is already over its cap");
+
+        if self.len() < cap {
+            self.push(element);
+            None
+        } else {
+            Some(self.push_pop_max(element))
+        }
+    }
+
+    /// Pushes an element, evicting the minimum element if the heap
+    /// would otherwise hold more than `cap` elements.
+    ///
+    /// This keeps the heap holding the `cap` largest elements it has
+    /// seen, and is equivalent to [`push`] when the heap has room. When
+    /// it is full, it behaves like [`push_pop_min`] and returns the
+    /// evicted element, which may be the one just given.
+    ///
+    /// The heap must not already hold more than `cap` elements, since
+    /// only one is evicted per push; use [`truncate_to_largest`] first to
+    /// shrink it. Debug builds check this.
+    ///
+    /// [`push`]:
+    ///     <struct.MinMaxHeap.html#method.push>
+    ///
+    /// [`push_pop_min`]:
+    ///     <struct.MinMaxHeap.html#method.push_pop_min>
+    ///
+    /// [`truncate_to_largest`]:
+    ///     <struct.MinMaxHeap.html#method.truncate_to_largest>
+    ///
+    /// *O*(log *n*).
+    pub fn push_capped_min(&mut self, cap: usize, element: T) -> Option<T> {
+        debug_assert!(self.len() <= cap, "push_capped_min: heap is already over its cap");
+
+        if self.len() < cap {
+            self.push(element);
+            None
+        } else {
+            Some(self.push_pop_min(element))
+        }
+    }
+
+    /// Replaces the minimum element with the result of applying `f` to
+    /// it, then restores the heap. Returns `false`, without calling `f`,
+    /// if the heap is empty.
+    ///
+    /// This moves the minimum out and back in place, sifting only once.
+    /// If `f` panics, the old minimum is dropped and the heap remains
+    /// valid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let mut heap = MinMaxHeap::from(vec![1, 5, 3]);
+    ///
+    /// assert!(heap.replace_min_with(|min| min + 10));
+    /// assert_eq!(heap.into_vec_asc(), [3, 5, 11]);
+    /// ```
+    ///
+    /// *O*(log *n*).
+    pub fn replace_min_with<F>(&mut self, f: F) -> bool
+    where
+        F: FnOnce(T) -> T,
+    {
+        if self.is_empty() {
+            return false;
+        }
+
+        // SAFETY: `self.0` is not empty
+        unchecked! {
+            self.replace_with_at(0, f);
+            self.trickle_down_min(0);
+        }
+
+        paranoid_check!(self);
+        true
+    }
+
+    /// Replaces the maximum element with the result of applying `f` to
+    /// it, then restores the heap. Returns `false`, without calling `f`,
+    /// if the heap is empty.
+    ///
+    /// This moves the maximum out and back in place, sifting only once.
+    /// If `f` panics, the old maximum is dropped and the heap remains
+    /// valid.
+    ///
+    /// *O*(log *n*).
+    pub fn replace_max_with<F>(&mut self, f: F) -> bool
+    where
+        F: FnOnce(T) -> T,
+    {
+        match self.find_max() {
+            None => false,
+            Some(max) => {
+                // SAFETY: `max` is the index of the maximum
+                unchecked! {
+                    self.replace_with_at(max, f);
+                    self.sift_max(max);
+                }
+                paranoid_check!(self);
+                true
+            }
+        }
+    }
+
+    /// Pops the minimum element and pushes a new element, in an
+    /// optimized fashion.
+    ///
+    /// Except for avoiding allocation, calling `replace_min` is
+    /// equivalent to calling [`pop_min`] followed by [`push`].
+    ///
+    /// This means that `replace_min` will always leaves the element you
+    /// give it in the heap and gives you back the *old* minimum
+    /// element; it never returns the element you just gave it. If the
+    /// heap is empty there is no old minimum, so `replace_min` pushes
+    /// the element and returns `None`. If you want to get back the
+    /// smallest element *including* the one you are about to push, see
+    /// [`push_pop_min`].
+    ///
+    /// [`push`]:
+    ///     <struct.MinMaxHeap.html#method.push>
+    ///
+    /// [`pop_min`]:
+    ///     <struct.MinMaxHeap.html#method.pop_min>
+    ///
+    /// [`push_pop_min`]:
+    ///     <struct.MinMaxHeap.html#method.push_pop_min>
+    ///
+    /// *O*(log *n*).
+    pub fn replace_min(&mut self, element: T) -> Option<T> {
+        let result = match replace_min_slice(&mut self.0, element, &self.1) {
+            Ok(min)      => Some(min),
+            // Heap was empty, so no reordering is necessary
+            Err(element) => { self.0.push(element); None }
+        };
+        paranoid_check!(self);
+        result
+    }
+
+    /// Pops the maximum element and pushes a new element, in an
+    /// optimized fashion.
+    ///
+    /// Except for avoiding allocation, calling `replace_max` is
+    /// equivalent to calling [`pop_max`] followed by [`push`].
+    ///
+    /// This means that `replace_max` will always leaves the element you
+    /// give it in the heap and gives you back the *old* maximum
+    /// element; it never returns the element you just gave it. If the
+    /// heap is empty there is no old maximum, so `replace_max` pushes
+    /// the element and returns `None`. If you want to get back the
+    /// largest element *including* the one you are about to push, see
+    /// [`push_pop_max`].
+    ///
+    /// [`push`]:
+    ///     <struct.MinMaxHeap.html#method.push>
+    ///
+    /// [`pop_max`]:
+    ///     <struct.MinMaxHeap.html#method.pop_max>
+    ///
+    /// [`push_pop_max`]:
+    ///     <struct.MinMaxHeap.html#method.push_pop_max>
+    ///
+    /// *O*(log *n*).
+    pub fn replace_max(&mut self, element: T) -> Option<T> {
+        let result = match replace_max_slice(&mut self.0, element, &self.1) {
+            Ok(max)      => Some(max),
+            // Heap was empty, so no reordering is necessary
+            Err(element) => { self.0.push(element); None }
+        };
+        paranoid_check!(self);
+        result
+    }
+
+    /// Returns an ascending (sorted) vector, reusing the heap’s
+    /// storage.
+    ///
+    /// *O*(*n* log *n*).
+    pub fn into_vec_asc(mut self) -> Vec<T> {
+        let n = self.len().saturating_sub(1);
+        Self::move_max_to_tail(&mut self.0, n, &self.1);
+        self.into_vec()
+    }
+
+    /// Returns an descending (sorted) vector, reusing the heap’s
+    /// storage.
+    ///
+    /// *O*(*n* log *n*).
+    pub fn into_vec_desc(mut self) -> Vec<T> {
+        let n = self.len().saturating_sub(1);
+        Self::move_min_to_tail(&mut self.0, n, &self.1);
+        self.into_vec()
+    }
+
+    /// Returns an ascending (sorted) boxed slice, reusing the heap’s
+    /// storage and shrinking it to fit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let mut heap = MinMaxHeap::with_capacity(100);
+    /// heap.extend(vec![5, 1, 8]);
+    ///
+    /// let sorted: Box<[_]> = heap.into_boxed_slice_asc();
+    /// assert_eq!(&*sorted, &[1, 5, 8]);
+    /// ```
+    ///
+    /// *O*(*n* log *n*).
+    pub fn into_boxed_slice_asc(self) -> Box<[T]> {
+        self.into_vec_asc().into_boxed_slice()
+    }
+
+    /// Returns a descending (sorted) boxed slice, reusing the heap’s
+    /// storage and shrinking it to fit.
+    ///
+    /// *O*(*n* log *n*).
+    pub fn into_boxed_slice_desc(self) -> Box<[T]> {
+        self.into_vec_desc().into_boxed_slice()
+    }
+
+    /// Returns the `k` smallest elements in ascending order, discarding
+    /// the rest and reusing the heap’s storage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let heap = MinMaxHeap::from(vec![5, 1, 8, 3, 9, 2]);
+    ///
+    /// assert_eq!(heap.clone().into_k_smallest(2), [1, 2]);
+    /// assert_eq!(heap.into_k_largest(2), [9, 8]);
+    /// ```
+    ///
+    /// *O*(*n* + *k* log *n*).
+    pub fn into_k_smallest(mut self, k: usize) -> Vec<T> {
+        let len = self.len();
+        if k >= len {
+            return self.into_vec_asc();
+        }
+
+        Self::move_min_to_tail(&mut self.0, k, &self.1);
+        let mut result = self.into_vec();
+        result.drain(.. len - k);
+        result.reverse();
+        result
+    }
+
+    /// Returns the `k` largest elements in descending order, discarding
+    /// the rest and reusing the heap’s storage.
+    ///
+    /// *O*(*n* + *k* log *n*).
+    pub fn into_k_largest(mut self, k: usize) -> Vec<T> {
+        let len = self.len();
+        if k >= len {
+            return self.into_vec_desc();
+        }
+
+        Self::move_max_to_tail(&mut self.0, k, &self.1);
+        let mut result = self.into_vec();
+        result.drain(.. len - k);
+        result.reverse();
+        result
+    }
+
+    /// Pops up to `n` minimum elements, appending them to `out` in
+    /// ascending order. Returns the number of elements popped.
+    ///
+    /// This is equivalent to calling [`pop_min`] up to `n` times, but
+    /// the elements are sifted out in place and moved to `out` in one
+    /// go, and draining the whole heap sorts it directly.
+    ///
+    /// [`pop_min`]:
+    ///     <struct.MinMaxHeap.html#method.pop_min>
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let mut heap = MinMaxHeap::from(vec![5, 1, 8, 3, 9, 2]);
+    /// let mut batch = Vec::new();
+    ///
+    /// assert_eq!(heap.pop_min_batch(&mut batch, 4), 4);
+    /// assert_eq!(batch, [1, 2, 3, 5]);
+    /// assert_eq!(heap.pop_min_batch(&mut batch, 4), 2);
+    /// assert_eq!(batch, [1, 2, 3, 5, 8, 9]);
+    /// ```
+    ///
+    /// *O*(*k* log *n*), where *k* is the number of elements popped.
+    pub fn pop_min_batch(&mut self, out: &mut Vec<T>, n: usize) -> usize {
+        let len = self.len();
+        let n = n.min(len);
+
+        if n == len {
+            let start = out.len();
+            let cmp = &self.1;
+            out.append(&mut self.0);
+            out[start ..].sort_unstable_by(|a, b| cmp.compare(a, b));
+        } else {
+            Self::move_min_to_tail(&mut self.0, n, &self.1);
+            out.extend(self.0.drain(len - n ..).rev());
+        }
+
+        paranoid_check!(self);
+        n
+    }
+
+    /// Pops up to `n` maximum elements, appending them to `out` in
+    /// descending order. Returns the number of elements popped.
+    ///
+    /// This is equivalent to calling [`pop_max`] up to `n` times, but
+    /// the elements are sifted out in place and moved to `out` in one
+    /// go, and draining the whole heap sorts it directly.
+    ///
+    /// [`pop_max`]:
+    ///     <struct.MinMaxHeap.html#method.pop_max>
+    ///
+    /// *O*(*k* log *n*), where *k* is the number of elements popped.
+    pub fn pop_max_batch(&mut self, out: &mut Vec<T>, n: usize) -> usize {
+        let len = self.len();
+        let n = n.min(len);
+
+        if n == len {
+            let start = out.len();
+            let cmp = &self.1;
+            out.append(&mut self.0);
+            out[start ..].sort_unstable_by(|a, b| cmp.compare(b, a));
+        } else {
+            Self::move_max_to_tail(&mut self.0, n, &self.1);
+            out.extend(self.0.drain(len - n ..).rev());
+        }
+
+        paranoid_check!(self);
+        n
+    }
+
+    /// Moves the `n` largest elements of the heap in `elements` to its
+    /// tail in ascending order, leaving the rest a valid heap.
+    ///
+    /// Panics if `n > elements.len()`.
+    fn move_max_to_tail(mut elements: &mut [T], n: usize, cmp: &C) {
+        for _ in 0 .. n {
+            let max = find_max_slice(elements, cmp).unwrap();
+            let (last, elements_rest) = elements.split_last_mut().unwrap();
+            elements = elements_rest;
+            if let Some(max_element) = elements.get_mut(max) {
+                mem::swap(max_element, last);
+                // SAFETY: `max < elements.len()`
+                unchecked! {
+                    Self::trickle_down_slice(elements, max, cmp);
+                }
+            }
+        }
+    }
+
+    /// Moves the `n` smallest elements of the heap in `elements` to its
+    /// tail in descending order, leaving the rest a valid heap.
+    ///
+    /// Panics if `n > elements.len()`.
+    fn move_min_to_tail(mut elements: &mut [T], n: usize, cmp: &C) {
+        for _ in 0 .. n {
+            let (last, elements_rest) = elements.split_last_mut().unwrap();
+            elements = elements_rest;
+            if let Some(min_element) = elements.first_mut() {
+                mem::swap(min_element, last);
+                // SAFETY: `elements` is not empty
+                unchecked! {
+                    Self::trickle_down_min_slice(elements, 0, cmp);
+                }
+            }
+        }
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all elements `e` such that `f(&e)` returns
+    /// `false`. The elements are visited in unsorted (and unspecified) order.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let mut heap = MinMaxHeap::from(vec![-10, -5, 1, 2, 4, 13]);
+    ///
+    /// heap.retain(|x| x % 2 == 0); // only keep even numbers
+    ///
+    /// let mut vec = heap.into_vec();
+    /// vec.sort();
+    /// assert_eq!(vec, [-10, 2, 4])
+    /// ```
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.0.retain(f);
+        self.rebuild();
+    }
+
+    /// Discards all elements less than `bound`, returning how many were
+    /// removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let mut heap = MinMaxHeap::from(vec![5, 1, 8, 3, 9, 2]);
+    ///
+    /// assert_eq!(heap.drop_below(&3), 2);
+    /// assert_eq!(heap.drop_above(&8), 1);
+    /// assert_eq!(heap.into_vec_asc(), [3, 5, 8]);
+    /// ```
+    ///
+    /// *O*(1) if no element is below `bound`; otherwise *O*(*n*).
+    pub fn drop_below(&mut self, bound: &T) -> usize {
+        match self.peek_min() {
+            Some(min) if self.1.compares_lt(min, bound) => {
+                let len = self.len();
+                let cmp = &self.1;
+                self.0.retain(|e| cmp.compares_ge(e, bound));
+                self.rebuild();
+                len - self.len()
+            }
+            _ => 0,
+        }
+    }
+
+    /// Discards all elements greater than `bound`, returning how many
+    /// were removed.
+    ///
+    /// *O*(1) if no element is above `bound`; otherwise *O*(*n*).
+    pub fn drop_above(&mut self, bound: &T) -> usize {
+        match self.peek_max() {
+            Some(max) if self.1.compares_gt(max, bound) => {
+                let len = self.len();
+                let cmp = &self.1;
+                self.0.retain(|e| cmp.compares_le(e, bound));
+                self.rebuild();
+                len - self.len()
+            }
+            _ => 0,
+        }
+    }
+
+    /// Shrinks the heap to its `n` smallest elements, discarding the
+    /// rest.
+    ///
+    /// Unless only a few elements go, this selects the `n` smallest in
+    /// place, cuts off the rest, and rebuilds, rather than popping.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let mut heap: MinMaxHeap<_> = (0 .. 10).collect();
+    /// heap.truncate_to_smallest(3);
+    ///
+    /// assert_eq!(heap.into_vec_asc(), [0, 1, 2]);
+    /// ```
+    ///
+    /// *O*(min(*k* log *n*, *n*)), where *k* is the number of elements
+    /// discarded.
+    pub fn truncate_to_smallest(&mut self, n: usize) {
+        self.discard_extreme(self.len().saturating_sub(n), true);
+    }
+
+    /// Shrinks the heap to its `n` largest elements, discarding the
+    /// rest.
+    ///
+    /// Unless only a few elements go, this selects the `n` largest in
+    /// place, cuts off the rest, and rebuilds, rather than popping.
+    ///
+    /// *O*(min(*k* log *n*, *n*)), where *k* is the number of elements
+    /// discarded.
+    pub fn truncate_to_largest(&mut self, n: usize) {
+        self.discard_extreme(self.len().saturating_sub(n), false);
+    }
+
+    /// Removes the `n` smallest elements and returns them as a new
+    /// heap.
+    ///
+    /// If the heap holds `n` or fewer elements then all of them are
+    /// moved to the result, leaving `self` empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let mut heap: MinMaxHeap<_> = (0 .. 10).collect();
+    /// let low = heap.split_off_min(3);
+    ///
+    /// assert_eq!(low.into_vec_asc(), [0, 1, 2]);
+    /// assert_eq!(heap.len(), 7);
+    /// assert_eq!(heap.peek_min(), Some(&3));
+    /// ```
+    ///
+    /// *O*(min(*k*, *n* − *k*) log *n* + *k*), where *k* is the
+    /// number of elements returned.
+    pub fn split_off_min(&mut self, n: usize) -> Self
+    where
+        C: Clone,
+    {
+        self.split_off_extreme(n, Self::pop_min, Self::pop_max)
+    }
+
+    /// Removes the `n` largest elements and returns them as a new
+    /// heap.
+    ///
+    /// If the heap holds `n` or fewer elements then all of them are
+    /// moved to the result, leaving `self` empty.
+    ///
+    /// *O*(min(*k*, *n* − *k*) log *n* + *k*), where *k* is the
+    /// number of elements returned.
+    pub fn split_off_max(&mut self, n: usize) -> Self
+    where
+        C: Clone,
+    {
+        self.split_off_extreme(n, Self::pop_max, Self::pop_min)
+    }
+
+    /// Divides the heap into two heaps, holding its smaller and larger
+    /// halves.
+    ///
+    /// If the heap has an odd number of elements, the larger half gets
+    /// the extra one, so the median is the minimum of the second heap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let heap = MinMaxHeap::from(vec![5, 1, 8, 3, 9]);
+    /// let (lower, upper) = heap.split_at_median();
+    ///
+    /// assert_eq!(lower.into_vec_asc(), [1, 3]);
+    /// assert_eq!(upper.into_vec_asc(), [5, 8, 9]);
+    /// ```
+    ///
+    /// Expected *O*(*n*).
+    pub fn split_at_median(mut self) -> (Self, Self)
+    where
+        C: Clone,
+    {
+        let mid = self.len() / 2;
+        if mid > 0 {
+            let cmp = &self.1;
+            self.0.select_nth_unstable_by(mid, |a, b| cmp.compare(a, b));
+        }
+
+        let upper = self.0.split_off(mid);
+        self.rebuild();
+        let upper = MinMaxHeap::from_vec_with_comparator(upper, self.1.clone());
+        (self, upper)
+    }
+
+    /// Removes all elements greater than or equal to `bound` and
+    /// returns them as a new heap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let mut heap = MinMaxHeap::from(vec![5, 1, 8, 3, 9, 2]);
+    /// let high = heap.split_off_ge(&5);
+    ///
+    /// assert_eq!(high.into_vec_asc(), [5, 8, 9]);
+    /// assert_eq!(heap.into_vec_asc(), [1, 2, 3]);
+    /// ```
+    ///
+    /// *O*(*n*).
+    pub fn split_off_ge(&mut self, bound: &T) -> Self
+    where
+        C: Clone,
+    {
+        self.split_off_where(|cmp, e| cmp.compares_ge(e, bound))
+    }
+
+    /// Removes all elements less than `bound` and returns them as a new
+    /// heap.
+    ///
+    /// *O*(*n*).
+    pub fn split_off_lt(&mut self, bound: &T) -> Self
+    where
+        C: Clone,
+    {
+        self.split_off_where(|cmp, e| cmp.compares_lt(e, bound))
+    }
+
+    /// Moves the elements satisfying `f` into a new heap, then restores
+    /// the heap property on both.
+    fn split_off_where<F>(&mut self, mut f: F) -> Self
+    where
+        C: Clone,
+        F: FnMut(&C, &T) -> bool,
+    {
+        let mut keep = 0;
+        for i in 0 .. self.len() {
+            if !f(&self.1, &self.0[i]) {
+                self.0.swap(keep, i);
+                keep += 1;
+            }
+        }
+
+        let split = self.0.split_off(keep);
+        self.rebuild();
+        MinMaxHeap::from_vec_with_comparator(split, self.1.clone())
+    }
+
+    /// Splits off the `n` elements that `pop_near` would remove first,
+    /// popping from whichever end moves fewer elements.
+    fn split_off_extreme(&mut self,
+                         n: usize,
+                         pop_near: fn(&mut Self) -> Option<T>,
+                         pop_far: fn(&mut Self) -> Option<T>) -> Self
+    where
+        C: Clone,
+    {
+        let len = self.len();
+
+        if n >= len {
+            let empty = MinMaxHeap::with_comparator(self.1.clone());
+            mem::replace(self, empty)
+        } else if n <= len / 2 {
+            let split: Vec<T> = (0 .. n).map(|_| pop_near(self).unwrap()).collect();
+            MinMaxHeap::from_vec_with_comparator(split, self.1.clone())
+        } else {
+            let keep: Vec<T> = (n .. len).map(|_| pop_far(self).unwrap()).collect();
+            let keep = MinMaxHeap::from_vec_with_comparator(keep, self.1.clone());
+            mem::replace(self, keep)
+        }
+    }
+
+    /// Drops the `k` smallest elements, or the `k` largest if `largest`.
+    ///
+    /// Popping them one at a time costs about *k* log *n*; when that is
+    /// more than *n*, this instead selects over the storage, cuts off
+    /// the discarded elements, and rebuilds what is left.
+    fn discard_extreme(&mut self, k: usize, largest: bool) {
+        let len = self.len();
+        let depth = (usize::BITS - len.leading_zeros()) as usize;
+
+        if k >= len {
+            self.0.clear();
+        } else if k.saturating_mul(depth) <= len {
+            for _ in 0 .. k {
+                if largest { self.pop_max(); } else { self.pop_min(); }
+            }
+        } else {
+            let cmp = &self.1;
+            if largest {
+                self.0.select_nth_unstable_by(len - k, |a, b| cmp.compare(a, b));
+                self.0.truncate(len - k);
+            } else {
+                self.0.select_nth_unstable_by(k, |a, b| cmp.compare(a, b));
+                self.0.drain(.. k);
+            }
+            self.rebuild();
+        }
+    }
+
+    /// Applies `f` to every element, in arbitrary order, and then
+    /// restores the heap with a single rebuild.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let mut heap = MinMaxHeap::from(vec![-3, 1, 2]);
+    /// heap.modify_in_place(|x| *x = -*x);
+    ///
+    /// assert_eq!(heap.into_vec_asc(), [-2, -1, 3]);
+    /// ```
+    ///
+    /// *O*(*n*).
+    pub fn modify_in_place<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut T),
+    {
+        self.0.iter_mut().for_each(f);
+        self.rebuild();
+    }
+
+    /// Removes all but one of each group of equal elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let mut heap = MinMaxHeap::from(vec![3, 1, 3, 2, 1, 3]);
+    /// heap.dedup();
+    ///
+    /// assert_eq!(heap.into_vec_asc(), [1, 2, 3]);
+    /// ```
+    ///
+    /// *O*(*n* log *n*).
+    pub fn dedup(&mut self) {
+        let cmp = &self.1;
+        self.0.sort_unstable_by(|a, b| cmp.compare(a, b));
+        self.0.dedup_by(|a, b| cmp.compares_eq(a, b));
+        self.rebuild();
+    }
+
+    /// Returns the multiset difference of `self` and `other`: each
+    /// element of `self` appears as many times as it occurs in `self`
+    /// beyond its occurrences in `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let a = MinMaxHeap::from(vec![1, 2, 2, 2, 3]);
+    /// let b = MinMaxHeap::from(vec![2, 3, 3, 4]);
+    ///
+    /// assert_eq!(a.difference(&b).into_vec_asc(), [1, 2, 2]);
+    /// assert_eq!(a.intersection(&b).into_vec_asc(), [2, 3]);
+    /// ```
+    ///
+    /// *O*(*n* log *n* + *m* log *m*).
+    pub fn difference(&self, other: &Self) -> Self
+    where
+        T: Clone,
+        C: Clone,
+    {
+        let vec = self.matched(other)
+            .filter(|&(_, matched)| !matched)
+            .map(|(element, _)| element.clone())
+            .collect();
+        MinMaxHeap::from_vec_with_comparator(vec, self.1.clone())
+    }
+
+    /// Returns the multiset intersection of `self` and `other`: each
+    /// element appears as many times as it occurs in whichever of the
+    /// two heaps has fewer of it.
+    ///
+    /// *O*(*n* log *n* + *m* log *m*).
+    pub fn intersection(&self, other: &Self) -> Self
+    where
+        T: Clone,
+        C: Clone,
+    {
+        let vec = self.matched(other)
+            .filter(|&(_, matched)| matched)
+            .map(|(element, _)| element.clone())
+            .collect();
+        MinMaxHeap::from_vec_with_comparator(vec, self.1.clone())
+    }
+
+    /// Returns `true` if every element of `self` occurs in `other` at
+    /// least as many times as in `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let a = MinMaxHeap::from(vec![1, 2, 2]);
+    /// let b = MinMaxHeap::from(vec![2, 1, 3, 2]);
+    /// let c = MinMaxHeap::from(vec![4, 5]);
+    ///
+    /// assert!(a.is_subset(&b));
+    /// assert!(b.is_superset(&a));
+    /// assert!(!b.is_subset(&a));
+    /// assert!(a.is_disjoint(&c));
+    /// ```
+    ///
+    /// *O*(*n* log *n* + *m* log *m*).
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.len() <= other.len() && self.matched(other).all(|(_, matched)| matched)
+    }
+
+    /// Returns `true` if every element of `other` occurs in `self` at
+    /// least as many times as in `other`.
+    ///
+    /// *O*(*n* log *n* + *m* log *m*).
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+
+    /// Returns `true` if `self` and `other` have no elements in common.
+    ///
+    /// *O*(1) if the ranges of the two heaps do not overlap; otherwise
+    /// *O*(*n* log *n* + *m* log *m*).
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        match (self.peek_min(), self.peek_max(), other.peek_min(), other.peek_max()) {
+            (Some(min1), Some(max1), Some(min2), Some(max2)) =>
+                self.1.compares_lt(max1, min2) || self.1.compares_lt(max2, min1) ||
+                    !self.matched(other).any(|(_, matched)| matched),
+            _ => true,
+        }
+    }
+
+    /// Visits the elements of `self` in ascending order, pairing each
+    /// with whether it can be matched to a distinct equal element of
+    /// `other`. Elements are compared with `self`’s comparator.
+    fn matched<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = (&'a T, bool)> + 'a {
+        let cmp = &self.1;
+        let mut theirs = other.iter_asc().peekable();
+
+        self.iter_asc().map(move |mine| {
+            while let Some(&their) = theirs.peek() {
+                if cmp.compares_lt(their, mine) {
+                    theirs.next();
+                } else {
+                    break;
+                }
+            }
+
+            let matched = match theirs.peek() {
+                Some(their) => cmp.compares_eq(their, mine),
+                None        => false,
+            };
+            if matched {
+                theirs.next();
+            }
+
+            (mine, matched)
+        })
+    }
+
+    /// Counts the elements equal to `element`.
+    ///
+    /// Subtrees that cannot contain `element` are skipped: below a min
+    /// level every element is at least as large as the node, and below
+    /// a max level every element is at most as large.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let heap = MinMaxHeap::from(vec![3, 1, 4, 1, 5, 9, 2, 6]);
+    ///
+    /// assert_eq!(heap.count(&1), 2);
+    /// assert_eq!(heap.count(&9), 1);
+    /// assert_eq!(heap.count(&7), 0);
+    /// ```
+    ///
+    /// *O*(*n*) worst case.
+    pub fn count(&self, element: &T) -> usize {
+        let cmp = &self.1;
+        self.count_between(|e| cmp.compares_lt(e, element), |e| cmp.compares_gt(e, element))
+    }
+
+    /// Counts the elements less than `bound`.
+    ///
+    /// Subtrees that lie entirely on one side of `bound` are counted or
+    /// skipped without being visited.
+    ///
+    /// *O*(*n*) worst case.
+    pub fn count_lt(&self, bound: &T) -> usize {
+        let cmp = &self.1;
+        self.count_between(|_| false, |e| cmp.compares_ge(e, bound))
+    }
+
+    /// Counts the elements greater than `bound`.
+    ///
+    /// Subtrees that lie entirely on one side of `bound` are counted or
+    /// skipped without being visited.
+    ///
+    /// *O*(*n*) worst case.
+    pub fn count_gt(&self, bound: &T) -> usize {
+        let cmp = &self.1;
+        self.count_between(|e| cmp.compares_le(e, bound), |_| false)
+    }
+
+    /// Counts the elements within `range`.
+    ///
+    /// Subtrees that lie entirely outside `range` are skipped, and
+    /// subtrees that lie entirely inside it are counted without being
+    /// visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let heap: MinMaxHeap<_> = (0 .. 100).collect();
+    ///
+    /// assert_eq!(heap.range_count(10 .. 20), 10);
+    /// assert_eq!(heap.range_count(10 ..= 20), 11);
+    /// assert_eq!(heap.range_count(90 ..), 10);
+    /// assert_eq!(heap.range_count(..), 100);
+    /// ```
+    ///
+    /// *O*(*n*) worst case.
+    pub fn range_count<R: RangeBounds<T>>(&self, range: R) -> usize {
+        let cmp = &self.1;
+        let below = |e: &T| match range.start_bound() {
+            Bound::Included(start) => cmp.compares_lt(e, start),
+            Bound::Excluded(start) => cmp.compares_le(e, start),
+            Bound::Unbounded       => false,
+        };
+        let above = |e: &T| match range.end_bound() {
+            Bound::Included(end) => cmp.compares_gt(e, end),
+            Bound::Excluded(end) => cmp.compares_ge(e, end),
+            Bound::Unbounded     => false,
+        };
+
+        self.count_between(below, above)
+    }
+
+    /// Counts the elements `e` for which neither `below(e)` nor
+    /// `above(e)` holds, pruning subtrees that lie entirely below or
+    /// above that range and counting subtrees that lie entirely inside
+    /// it without visiting them.
+    ///
+    /// `below` must be downward closed and `above` upward closed.
+    fn count_between<B, A>(&self, below: B, above: A) -> usize
+    where
+        B: Fn(&T) -> bool,
+        A: Fn(&T) -> bool,
+    {
+        // `floor` means no element in the subtree is below the range,
+        // and `ceiling` means none is above it.
+        fn go<T, B, A>(slice: &[T], pos: usize, floor: bool, ceiling: bool,
+                       below: &B, above: &A) -> usize
+        where
+            B: Fn(&T) -> bool,
+            A: Fn(&T) -> bool,
+        {
+            if floor && ceiling {
+                return pos.subtree_len(slice.len());
+            }
+
+            let element = match slice.get(pos) {
+                Some(element) => element,
+                None => return 0,
+            };
+
+            let is_below = !floor && below(element);
+            let is_above = !ceiling && above(element);
+
+            // Descendants of a min-level node are no smaller than it, and
+            // descendants of a max-level node are no larger.
+            let (floor, ceiling) = if pos.is_min_level() {
+                if is_above { return 0; }
+                (floor || !is_below, ceiling)
+            } else {
+                if is_below { return 0; }
+                (floor, ceiling || !is_above)
+            };
+
+            let here = if is_below || is_above { 0 } else { 1 };
+
+            here + go(slice, pos.child1(), floor, ceiling, below, above)
+                 + go(slice, pos.child2(), floor, ceiling, below, above)
+        }
+
+        go(&self.0, 0, false, false, &below, &above)
+    }
+
+    /// Checks that the min-max-heap property holds throughout the heap.
+    ///
+    /// This can only fail if an element’s ordering changed while it was
+    /// in the heap, as through interior mutability, or if the heap was
+    /// corrupted by unsafe code.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let heap: MinMaxHeap<_> = vec![4, 1, 5, 9, 2, 6].into_iter().collect();
+    /// assert!(heap.is_valid());
+    /// ```
+    ///
+    /// *O*(*n*).
+    pub fn is_valid(&self) -> bool {
+        self.find_violation().is_none()
+    }
+
+    /// Checks that the min-max-heap property holds throughout the heap.
+    ///
+    /// # Panics
+    ///
+    /// Panics, naming the positions of the out-of-order elements in
+    /// level order, if [`is_valid`] would return `false`.
+    ///
+    /// With the `paranoid` feature, every mutating operation on the heap
+    /// ends by calling this.
+    ///
+    /// *O*(*n*).
+    ///
+    /// [`is_valid`]:
+    ///     <struct.MinMaxHeap.html#method.is_valid>
+    pub fn assert_valid(&self) {
+        if let Some((pos, ancestor)) = self.find_violation() {
+            let relation = if pos.is_min_level() == (pos.parent() == ancestor) {
+                "greater"
+            } else {
+                "less"
+            };
+
+            panic!("MinMaxHeap::assert_valid: element at position {} is \
+                    {} than its ancestor at position {}",
+                   pos, relation, ancestor);
+        }
+    }
+
+    /// Finds an element that is out of order with respect to its parent
+    /// or grandparent, returning both positions.
+    ///
+    /// Checking only these two ancestors suffices: an element on a min
+    /// level is no greater than its max-level parent and no less than its
+    /// min-level grandparent, and the reverse on a max level, so the
+    /// relations to all further ancestors follow by transitivity.
+    fn find_violation(&self) -> Option<(usize, usize)> {
+        Self::find_violation_slice(&self.0, &self.1)
+    }
+
+    fn find_violation_slice(slice: &[T], cmp: &C) -> Option<(usize, usize)> {
+        (1 .. slice.len()).find_map(|pos| {
+            let parent = pos.parent();
+            let min_level = pos.is_min_level();
+
+            if min_level && cmp.compares_gt(&slice[pos], &slice[parent]) ||
+                !min_level && cmp.compares_lt(&slice[pos], &slice[parent]) {
+                return Some((pos, parent));
+            }
+
+            if pos.has_grandparent() {
+                let grandparent = pos.grandparent();
+                if min_level && cmp.compares_lt(&slice[pos], &slice[grandparent]) ||
+                    !min_level && cmp.compares_gt(&slice[pos], &slice[grandparent]) {
+                    return Some((pos, grandparent));
+                }
+            }
+
+            None
+        })
+    }
+
+    fn rebuild(&mut self) {
+        trace_span!(DEBUG, "rebuild", self.len());
+        rebuild_slice(&mut self.0, &self.1);
+        paranoid_check!(self);
+    }
+
+    /// Appends the elements of `iter`, letting `Vec` reserve room for
+    /// them from the size hint, and then restores the heap.
+    fn extend_desugared<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let guard = RebuildOnDrop { start: self.len(), heap: self };
+        guard.heap.0.extend(iter);
+    }
+
+    /// Restores the heap property after elements were appended at
+    /// `start ..` to what was a valid heap, either by bubbling up each of
+    /// them or by rebuilding the whole heap, whichever looks cheaper.
+    fn rebuild_tail(&mut self, start: usize) {
+        if Self::rebuilds_tail(start, self.len()) {
+            self.rebuild();
+        } else {
+            self.bubble_up_tail(start);
+        }
+    }
+
+    /// Does rebuilding look cheaper than bubbling up each element, after
+    /// elements were appended at `start .. len`?
+    fn rebuilds_tail(start: usize, len: usize) -> bool {
+        let tail_len = len - start;
+        // Rebuilding takes about 2 *n* comparisons, and bubbling up each
+        // new element up to log₂ `start`, though usually far fewer.
+        let depth = (usize::BITS - start.leading_zeros()) as usize;
+        start < tail_len || 2 * len < tail_len.saturating_mul(depth)
+    }
+
+    /// Bubbles up each element from `start` on.
+    fn bubble_up_tail(&mut self, start: usize) {
+        for pos in start .. self.len() {
+            // SAFETY: `pos < self.len()`
+            unchecked! {
+                self.bubble_up(pos);
+            }
+        }
+        paranoid_check!(self);
+    }
+}
+
+#[cfg(all(feature = "alloc", not(feature = "safe")))]
+impl<T, C: Compare<T>> MinMaxHeap<T, C> {
+    /// Caller must ensure that `pos` is a valid index in `self.0`.
+    #[inline]
+    unsafe fn trickle_down_min(&mut self, pos: usize) {
+        Self::trickle_down_min_slice(&mut self.0, pos, &self.1);
+    }
+
+    /// Caller must ensure that `pos` is a valid index in `self.0`.
+    #[inline]
+    unsafe fn trickle_down(&mut self, pos: usize) {
+        Self::trickle_down_slice(&mut self.0, pos, &self.1);
+    }
+
+    /// Caller must ensure that `pos` is a valid index in `slice`.
+    #[inline]
+    unsafe fn trickle_down_min_slice(slice: &mut [T], pos: usize, cmp: &C) {
+        debug_assert!(pos < slice.len());
+        Hole::new(slice, pos).trickle_down_min(cmp);
+    }
+
+    /// Caller must ensure that `pos` is a valid index in `slice`.
+    #[inline]
+    unsafe fn trickle_down_slice(slice: &mut [T], pos: usize, cmp: &C) {
+        debug_assert!(pos < slice.len());
+        Hole::new(slice, pos).trickle_down(cmp);
+    }
+
+    /// Caller must ensure that `pos` is a valid index in `self.0`.
+    ///
+    /// Returns the element’s final position.
+    #[inline]
+    unsafe fn bubble_up(&mut self, pos: usize) -> usize {
+        debug_assert!(pos < self.len());
+        let mut hole = Hole::new(&mut self.0, pos);
+        hole.bubble_up(&self.1);
+        hole.pos()
+    }
+
+    /// Caller must ensure that `pos` is a valid index in `self.0`, and
+    /// that its element is at least (if `max`) or at most every other.
+    #[inline]
+    unsafe fn bubble_up_extreme(&mut self, pos: usize, max: bool) {
+        debug_assert!(pos < self.len());
+        Hole::new(&mut self.0, pos).bubble_up_extreme(max);
+    }
+
+    /// Restores the heap after the element at `pos` has been changed
+    /// arbitrarily.
+    ///
+    /// If the element moves up, whatever replaces it at `pos` came from
+    /// above and may still need to move down.
+    ///
+    /// Caller must ensure that `pos` is a valid index in `self.0`.
+    unsafe fn sift(&mut self, pos: usize) {
+        self.bubble_up(pos);
+        self.trickle_down(pos);
+    }
+
+    /// Restores the heap after the maximum element at `pos` has been
+    /// changed.
+    ///
+    /// Caller must ensure that `pos` is the index of the maximum.
+    unsafe fn sift_max(&mut self, pos: usize) {
+        sift_max_slice(&mut self.0, pos, &self.1);
+    }
+
+    /// Replaces the element at `pos` with the result of applying `f` to
+    /// it, without restoring the heap property.
+    ///
+    /// If `f` panics, the element is removed and the heap is rebuilt.
+    ///
+    /// Caller must ensure that `pos` is a valid index in `self.0`.
+    unsafe fn replace_with_at<F>(&mut self, pos: usize, f: F)
+    where
+        F: FnOnce(T) -> T,
+    {
+        struct Vacated<'a, T, C: Compare<T>> {
+            heap: &'a mut MinMaxHeap<T, C>,
+            pos:  usize,
+        }
+
+        impl<'a, T, C: Compare<T>> Drop for Vacated<'a, T, C> {
+            fn drop(&mut self) {
+                let vec = &mut self.heap.0;
+                let last = vec.len() - 1;
+                // SAFETY: `pos` is vacant, so we fill it with the last
+                // element and forget the last slot
+                unsafe {
+                    if self.pos != last {
+                        let base = vec.as_mut_ptr();
+                        ptr::copy_nonoverlapping(base.add(last), base.add(self.pos), 1);
+                    }
+                    vec.set_len(last);
+                }
+                self.heap.rebuild();
+            }
+        }
+
+        debug_assert!(pos < self.len());
+        let vacated = Vacated { heap: self, pos };
+        let element = ptr::read(vacated.heap.0.get_unchecked(pos));
+        let element = f(element);
+        ptr::write(vacated.heap.0.get_unchecked_mut(pos), element);
+        mem::forget(vacated);
+    }
+
+    /// Caller must ensure that `pos` is a valid index in `self.0`.
+    #[inline]
+    unsafe fn get_at(&self, pos: usize) -> &T {
+        debug_assert!(pos < self.len());
+        self.0.get_unchecked(pos)
+    }
+
+    /// Caller must ensure that `pos` is a valid index in `self.0`.
+    #[inline]
+    unsafe fn get_at_mut(&mut self, pos: usize) -> &mut T {
+        debug_assert!(pos < self.len());
+        self.0.get_unchecked_mut(pos)
+    }
+}
+
+// With the `safe` feature, these replace the helpers above. They have
+// the same preconditions, but check them, panicking rather than causing
+// undefined behavior.
+#[cfg(all(feature = "alloc", feature = "safe"))]
+impl<T, C: Compare<T>> MinMaxHeap<T, C> {
+    #[inline]
+    fn trickle_down_min(&mut self, pos: usize) {
+        Self::trickle_down_min_slice(&mut self.0, pos, &self.1);
+    }
+
+    #[inline]
+    fn trickle_down(&mut self, pos: usize) {
+        Self::trickle_down_slice(&mut self.0, pos, &self.1);
+    }
+
+    #[inline]
+    fn trickle_down_min_slice(slice: &mut [T], pos: usize, cmp: &C) {
+        Hole::new(slice, pos).trickle_down_min(cmp);
+    }
+
+    #[inline]
+    fn trickle_down_slice(slice: &mut [T], pos: usize, cmp: &C) {
+        Hole::new(slice, pos).trickle_down(cmp);
+    }
+
+    /// Returns the element’s final position.
+    #[inline]
+    fn bubble_up(&mut self, pos: usize) -> usize {
+        let mut hole = Hole::new(&mut self.0, pos);
+        hole.bubble_up(&self.1);
+        hole.pos()
+    }
+
+    #[inline]
+    fn bubble_up_extreme(&mut self, pos: usize, max: bool) {
+        Hole::new(&mut self.0, pos).bubble_up_extreme(max);
+    }
+
+    fn sift(&mut self, pos: usize) {
+        self.bubble_up(pos);
+        self.trickle_down(pos);
+    }
+
+    fn sift_max(&mut self, pos: usize) {
+        sift_max_slice(&mut self.0, pos, &self.1);
+    }
+
+    /// Takes the element out with `swap_remove` while `f` runs, and
+    /// swaps it back into place afterward.
+    fn replace_with_at<F>(&mut self, pos: usize, f: F)
+    where
+        F: FnOnce(T) -> T,
+    {
+        struct Vacated<'a, T, C: Compare<T>> {
+            heap: &'a mut MinMaxHeap<T, C>,
+        }
+
+        impl<'a, T, C: Compare<T>> Drop for Vacated<'a, T, C> {
+            fn drop(&mut self) {
+                self.heap.rebuild();
+            }
+        }
+
+        let element = self.0.swap_remove(pos);
+        let vacated = Vacated { heap: self };
+        let element = f(element);
+        mem::forget(vacated);
+
+        self.0.push(element);
+        let last = self.len() - 1;
+        self.0.swap(pos, last);
+    }
+
+    #[inline]
+    fn get_at(&self, pos: usize) -> &T {
+        &self.0[pos]
+    }
+
+    #[inline]
+    fn get_at_mut(&mut self, pos: usize) -> &mut T {
+        &mut self.0[pos]
+    }
+}
+
+// These work on any slice laid out as a heap, so the heaps that never
+// allocate share them with `MinMaxHeap`.
+
+/// Returns the position of the maximum element of the heap in `slice`.
+fn find_max_slice<T, C: Compare<T>>(slice: &[T], cmp: &C) -> Option<usize> {
+    match slice.len() {
+        0 => None,
+        1 => Some(0),
+        2 => Some(1),
+        _ => if stats::compared(cmp.compares_gt(&slice[1], &slice[2])) { Some(1) } else { Some(2) },
+    }
+}
+
+/// Arranges the elements of `slice` into a heap.
+fn rebuild_slice<T, C: Compare<T>>(slice: &mut [T], cmp: &C) {
+    for n in (0..(slice.len() / 2)).rev() {
+        // SAFETY: `n < slice.len()`
+        unchecked! {
+            Hole::new(slice, n).trickle_down(cmp);
+        }
+    }
+}
+
+// The heap operations of the vector-backed heaps, on their storage as a
+// slice, so that `MinMaxHeap` and the heaps with other vectors share
+// them. Each checks the positions it is given.
+
+/// Restores the heap in `slice` after an element was appended to it.
+#[cfg(feature = "alloc")]
+fn push_slice<T, C: Compare<T>>(slice: &mut [T], cmp: &C) {
+    if let Some(pos) = slice.len().checked_sub(1) {
+        // SAFETY: `pos` is the index of the last element
+        unchecked! {
+            Hole::new(slice, pos).bubble_up(cmp);
+        }
+    }
+}
+
+/// Restores the heap in `slice` after the minimum, at `pos == 0`, or
+/// the maximum, at `pos`, was replaced by an element that is at least
+/// the minimum, such as the last element by `swap_remove`. Does nothing
+/// if `pos` is past the end, as after removing the last element.
+#[cfg(feature = "alloc")]
+fn trickle_down_extreme_slice<T, C: Compare<T>>(slice: &mut [T], pos: usize, cmp: &C) {
+    if pos < slice.len() {
+        // SAFETY: `pos < slice.len()`
+        unchecked! {
+            if pos == 0 {
+                Hole::new(slice, pos).trickle_down_min(cmp);
+            } else {
+                Hole::new(slice, pos).trickle_down_max(cmp);
+            }
+        }
+    }
+}
+
+/// Restores the heap in `slice` after the maximum, at `pos`, was
+/// changed arbitrarily.
+#[cfg(feature = "alloc")]
+fn sift_max_slice<T, C: Compare<T>>(slice: &mut [T], pos: usize, cmp: &C) {
+    assert!(pos < slice.len());
+    // SAFETY: `pos < slice.len()`
+    let mut hole = unchecked! { Hole::new(slice, pos) };
+
+    if let Some(mut parent) = hole.get_parent() {
+        if cmp.compares_lt(parent.hole_element(), parent.other_element()) {
+           parent.swap_with();
+        }
+    }
+
+    hole.trickle_down_max(cmp);
+}
+
+/// Swaps `element` for the minimum of the heap in `slice` if that is
+/// smaller, and returns whichever is left over.
+#[cfg(feature = "alloc")]
+fn push_pop_min_slice<T, C: Compare<T>>(slice: &mut [T], mut element: T, cmp: &C) -> T {
+    if let Some(min) = slice.first_mut() {
+        if cmp.compares_gt(&element, min) {
+            mem::swap(&mut element, min);
+            trickle_down_extreme_slice(slice, 0, cmp);
+        }
+    }
+    element
+}
+
+/// Swaps `element` for the maximum of the heap in `slice` if that is
+/// larger, and returns whichever is left over.
+#[cfg(feature = "alloc")]
+fn push_pop_max_slice<T, C: Compare<T>>(slice: &mut [T], mut element: T, cmp: &C) -> T {
+    if let Some(max) = find_max_slice(slice, cmp) {
+        if cmp.compares_lt(&element, &slice[max]) {
+            mem::swap(&mut element, &mut slice[max]);
+            sift_max_slice(slice, max, cmp);
+        }
+    }
+    element
+}
+
+/// Swaps `element` for the minimum of the heap in `slice`, returning
+/// the old minimum, or gives `element` back if the heap is empty.
+#[cfg(feature = "alloc")]
+fn replace_min_slice<T, C: Compare<T>>(slice: &mut [T], mut element: T, cmp: &C)
+                                       -> Result<T, T> {
+    match slice.first_mut() {
+        Some(min) => {
+            mem::swap(&mut element, min);
+            trickle_down_extreme_slice(slice, 0, cmp);
+            Ok(element)
+        }
+        None => Err(element),
+    }
+}
+
+/// Swaps `element` for the maximum of the heap in `slice`, returning
+/// the old maximum, or gives `element` back if the heap is empty.
+#[cfg(feature = "alloc")]
+fn replace_max_slice<T, C: Compare<T>>(slice: &mut [T], mut element: T, cmp: &C)
+                                       -> Result<T, T> {
+    let max = match find_max_slice(slice, cmp) {
+        Some(max) => max,
+        None      => return Err(element),
+    };
+
+    // If `element` is the new min, swap it with the current min
+    // (unless the min is the same as the max)
+    if max > 0 && cmp.compares_lt(&element, &slice[0]) {
+        mem::swap(&mut element, &mut slice[0]);
+    }
+    mem::swap(&mut element, &mut slice[max]);
+    sift_max_slice(slice, max, cmp);
+    Ok(element)
+}
+
+
+#[cfg(feature = "alloc")]
+impl<T, C> MinMaxHeap<T, C> {
+    /// Drops all items from the heap.
+    ///
+    /// *O*(*n*)
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// The number of elements the heap can hold without reallocating.
+    ///
+    /// *O*(1)
+    pub fn capacity(&self) -> usize {
+        self.0.capacity()
+    }
+
+    /// The number of bytes the heap occupies: the heap itself plus its
+    /// allocated buffer, including unused capacity. This does not count
+    /// memory that the elements own; for that, see
+    /// [`allocated_bytes_with`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    /// use std::mem::size_of;
+    ///
+    /// let heap: MinMaxHeap<u64> = MinMaxHeap::with_capacity(10);
+    /// assert!(heap.allocated_bytes() >= size_of::<MinMaxHeap<u64>>() + 80);
+    /// ```
+    ///
+    /// *O*(1)
+    ///
+    /// [`allocated_bytes_with`]: #method.allocated_bytes_with
+    pub fn allocated_bytes(&self) -> usize {
+        mem::size_of::<Self>() + self.0.capacity() * mem::size_of::<T>()
+    }
+
+    /// Like [`allocated_bytes`], but adds `deep_size(element)` for each
+    /// element, which should give the number of bytes that the element
+    /// owns outside of itself, such as a `String`’s buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let mut heap = MinMaxHeap::new();
+    /// heap.push(String::with_capacity(100));
+    /// heap.push(String::with_capacity(50));
+    ///
+    /// let shallow = heap.allocated_bytes();
+    /// assert_eq!(heap.allocated_bytes_with(String::capacity), shallow + 150);
+    /// ```
+    ///
+    /// *O*(*n*) calls to `deep_size`
+    ///
+    /// [`allocated_bytes`]: #method.allocated_bytes
+    pub fn allocated_bytes_with<F>(&self, mut deep_size: F) -> usize
+        where F: FnMut(&T) -> usize
+    {
+        self.0.iter().fold(self.allocated_bytes(), |sum, element| sum + deep_size(element))
+    }
+
+    /// Reserves the minimum capacity for exactly `additional` more
+    /// elements to be inserted in the given `MinMaxHeap`.
+    ///
+    /// *O*(*n*)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    pub fn reserve_exact(&mut self, additional: usize) {
+        self.0.reserve_exact(additional)
+    }
+
+    /// Reserves the minimum capacity for at least `additional` more
+    /// elements to be inserted in the given `MinMaxHeap`.
+    ///
+    /// *O*(*n*)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    pub fn reserve(&mut self, additional: usize) {
+        self.0.reserve(additional)
+    }
+
+    /// Tries to reserve the minimum capacity for exactly `additional`
+    /// more elements to be inserted in the given `MinMaxHeap`, returning
+    /// an error instead of panicking or aborting if it cannot.
+    ///
+    /// *O*(*n*)
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.0.try_reserve_exact(additional)
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements
+    /// to be inserted in the given `MinMaxHeap`, returning an error
+    /// instead of panicking or aborting if it cannot.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let mut heap = MinMaxHeap::new();
+    /// heap.try_reserve(10).expect("out of memory");
+    /// heap.extend(0 .. 10);
+    ///
+    /// assert!(heap.try_reserve(usize::MAX).is_err());
+    /// ```
+    ///
+    /// *O*(*n*)
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.0.try_reserve(additional)
+    }
+
+    /// Discards extra capacity.
+    ///
+    /// *O*(*n*)
+    pub fn shrink_to_fit(&mut self) {
+        self.0.shrink_to_fit()
+    }
+
+    /// Discards capacity beyond the larger of the heap’s length and
+    /// `min_capacity`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let mut heap: MinMaxHeap<u32> = MinMaxHeap::with_capacity(100);
+    /// heap.extend(0 .. 5);
+    ///
+    /// heap.shrink_to(10);
+    /// assert!(heap.capacity() >= 10);
+    /// heap.shrink_to(0);
+    /// assert!(heap.capacity() >= 5);
+    /// ```
+    ///
+    /// *O*(*n*)
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        self.0.shrink_to(min_capacity)
+    }
+
+    /// Consumes the `MinMaxHeap` and returns its elements in a vector
+    /// in arbitrary order.
+    ///
+    /// *O*(*n*)
+    pub fn into_vec(self) -> Vec<T> {
+        self.0
+    }
+
+    /// Consumes the `MinMaxHeap` and returns a new heap containing `f`
+    /// applied to each element.
+    ///
+    /// The mapped elements are collected and then heapified in a single
+    /// pass, rather than pushed one at a time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let heap = MinMaxHeap::from(vec![1, -5, 3]);
+    /// let squares = heap.map(|x| x * x);
+    ///
+    /// assert_eq!(squares.into_vec_asc(), [1, 9, 25]);
+    /// ```
+    ///
+    /// *O*(*n*)
+    pub fn map<U, F>(self, f: F) -> MinMaxHeap<U>
+    where
+        U: Ord,
+        F: FnMut(T) -> U,
+    {
+        MinMaxHeap::from(self.0.into_iter().map(f).collect::<Vec<_>>())
+    }
+
+    /// Returns a borrowing iterator over the min-max-heap’s elements in
+    /// arbitrary order.
+    ///
+    /// *O*(1) on creation, and *O*(1) for each `next()` operation.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter(self.0.iter())
+    }
+
+    /// Returns an iterator over the levels of the implicit tree, from
+    /// the root down, each tagged as a min level or a max level.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::{Level, MinMaxHeap};
+    ///
+    /// let heap: MinMaxHeap<_> = (0 .. 5).collect();
+    /// let levels: Vec<_> = heap.levels().collect();
+    ///
+    /// assert_eq!(levels.len(), 3);
+    /// assert_eq!(levels[0], Level::Min(&[0][..]));
+    /// assert!(levels[1].is_max());
+    /// assert_eq!(levels[2].elements().len(), 2);
+    /// ```
+    ///
+    /// *O*(1) on creation, and *O*(1) for each `next()` operation.
+    pub fn levels(&self) -> Levels<'_, T> {
+        Levels {
+            rest:  &self.0,
+            width: 1,
+            min:   true,
+        }
+    }
+
+    /// Returns an adapter that renders the implicit tree as indented
+    /// text, with each element annotated by the kind of its level.
+    ///
+    /// This is meant for debugging; see [`DisplayTree`] for the format.
+    ///
+    /// *O*(1) on creation, and *O*(*n*) to format.
+    ///
+    /// [`DisplayTree`]: struct.DisplayTree.html
+    pub fn display_tree(&self) -> DisplayTree<'_, T> {
+        DisplayTree(&self.0)
+    }
+
+    /// Returns a draining iterator over the min-max-heap’s elements in
+    /// arbitrary order.
+    ///
+    /// *O*(1) on creation, and *O*(1) for each `next()` operation.
+    pub fn drain(&mut self) -> Drain<'_, T> {
+        Drain(self.0.drain(..))
+    }
+
+    /// Returns a draining iterator over the min-max-heap’s elements in
+    /// ascending (min-first) order.
+    ///
+    /// *O*(1) on creation, and *O*(log *n*) for each `next()` operation.
+    pub fn drain_asc(&mut self) -> DrainAsc<'_, T, C> {
+        DrainAsc(self)
+    }
+
+    /// Returns a draining iterator over the min-max-heap’s elements in
+    /// descending (max-first) order.
+    ///
+    /// *O*(1) on creation, and *O*(log *n*) for each `next()` operation.
+    pub fn drain_desc(&mut self) -> DrainDesc<'_, T, C> {
+        DrainDesc(self)
+    }
+}
+
+//
+// Iterators
+//
+
+/// A borrowed iterator over the elements of the min-max-heap in
+/// arbitrary order.
+///
+/// This type is created with
+/// [`MinMaxHeap::iter`](struct.MinMaxHeap.html#method.iter).
+pub struct Iter<'a, T: 'a>(slice::Iter<'a, T>);
+
+impl<'a, T> Clone for Iter<'a, T> {
+    fn clone(&self) -> Self {
+        Iter(self.0.clone())
+    }
+}
+
+impl<'a, T: fmt::Debug> fmt::Debug for Iter<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Iter")
+         .field(&self.0.as_slice())
+         .finish()
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> { self.0.next() }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> { }
+impl<'a, T> FusedIterator for Iter<'a, T> { }
+
+#[cfg(feature = "alloc")]
+impl<'a, T, C> IntoIterator for &'a MinMaxHeap<T, C> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter { self.iter() }
+}
+
+/// A borrowed iterator over the elements of the min-max-heap in
+/// ascending (min-first) order.
+///
+/// This type is created with
+/// [`MinMaxHeap::iter_asc`](struct.MinMaxHeap.html#method.iter_asc).
+#[cfg(feature = "alloc")]
+pub struct IterAsc<'a, T: 'a, C: 'a = Natural>(Frontier<'a, T, C>);
+
+#[cfg(feature = "alloc")]
+impl<'a, T, C> Clone for IterAsc<'a, T, C> {
+    fn clone(&self) -> Self {
+        IterAsc(self.0.clone())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T: fmt::Debug, C: Compare<T>> fmt::Debug for IterAsc<'a, T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IterAsc")
+         .field(&self.clone().collect::<Vec<_>>())
+         .finish()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, C: Compare<T>> Iterator for IterAsc<'a, T, C> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> { self.0.next() }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, C: Compare<T>> ExactSizeIterator for IterAsc<'a, T, C> { }
+#[cfg(feature = "alloc")]
+impl<'a, T, C: Compare<T>> FusedIterator for IterAsc<'a, T, C> { }
+
+/// A borrowed iterator over the elements of the min-max-heap in
+/// descending (max-first) order.
+///
+/// This type is created with
+/// [`MinMaxHeap::iter_desc`](struct.MinMaxHeap.html#method.iter_desc).
+#[cfg(feature = "alloc")]
+pub struct IterDesc<'a, T: 'a, C: 'a = Natural>(Frontier<'a, T, C>);
+
+#[cfg(feature = "alloc")]
+impl<'a, T, C> Clone for IterDesc<'a, T, C> {
+    fn clone(&self) -> Self {
+        IterDesc(self.0.clone())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T: fmt::Debug, C: Compare<T>> fmt::Debug for IterDesc<'a, T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IterDesc")
+         .field(&self.clone().collect::<Vec<_>>())
+         .finish()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, C: Compare<T>> Iterator for IterDesc<'a, T, C> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> { self.0.next() }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, C: Compare<T>> ExactSizeIterator for IterDesc<'a, T, C> { }
+#[cfg(feature = "alloc")]
+impl<'a, T, C: Compare<T>> FusedIterator for IterDesc<'a, T, C> { }
+
+/// One level of the implicit tree of a min-max-heap.
+///
+/// Each element on a min level is no greater than its descendants, and
+/// each element on a max level is no less than its descendants.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Level<'a, T: 'a> {
+    /// A min level.
+    Min(&'a [T]),
+    /// A max level.
+    Max(&'a [T]),
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> Level<'a, T> {
+    /// The elements on this level, from left to right.
+    pub fn elements(&self) -> &'a [T] {
+        match *self {
+            Level::Min(elements) | Level::Max(elements) => elements,
+        }
+    }
+
+    /// Is this a min level?
+    pub fn is_min(&self) -> bool {
+        matches!(self, Level::Min(_))
+    }
+
+    /// Is this a max level?
+    pub fn is_max(&self) -> bool {
+        matches!(self, Level::Max(_))
+    }
+}
+
+/// An iterator over the levels of a min-max-heap’s implicit tree.
+///
+/// This type is created with
+/// [`MinMaxHeap::levels`](struct.MinMaxHeap.html#method.levels).
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub struct Levels<'a, T: 'a> {
+    rest:  &'a [T],
+    width: usize,
+    min:   bool,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> Iterator for Levels<'a, T> {
+    type Item = Level<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        let (elements, rest) = self.rest.split_at(self.width.min(self.rest.len()));
+        let result = if self.min { Level::Min(elements) } else { Level::Max(elements) };
+
+        self.rest  = rest;
+        self.width = self.width.saturating_mul(2);
+        self.min   = !self.min;
+
+        Some(result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let mut count = 0;
+        let mut remaining = self.rest.len();
+        let mut width = self.width;
+
+        while remaining > 0 {
+            remaining = remaining.saturating_sub(width);
+            width = width.saturating_mul(2);
+            count += 1;
+        }
+
+        (count, Some(count))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> ExactSizeIterator for Levels<'a, T> { }
+#[cfg(feature = "alloc")]
+impl<'a, T> FusedIterator for Levels<'a, T> { }
+
+/// An owning iterator over the elements of the min-max-heap in
+/// arbitrary order.
+#[cfg(feature = "alloc")]
+#[derive(Clone)]
+pub struct IntoIter<T>(vec::IntoIter<T>);
+
+#[cfg(feature = "alloc")]
+impl<T: fmt::Debug> fmt::Debug for IntoIter<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoIter")
+         .field(&self.0.as_slice())
+         .finish()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> { self.0.next() }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> ExactSizeIterator for IntoIter<T> { }
+#[cfg(feature = "alloc")]
+impl<T> FusedIterator for IntoIter<T> { }
+
+#[cfg(feature = "alloc")]
+impl<T, C> IntoIterator for MinMaxHeap<T, C> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self.0.into_iter())
+    }
+}
+
+/// A draining iterator over the elements of the min-max-heap in
+/// arbitrary order.
+///
+/// This type is created with
+/// [`MinMaxHeap::drain`](struct.MinMaxHeap.html#method.drain).
+#[cfg(feature = "alloc")]
+pub struct Drain<'a, T: 'a>(vec::Drain<'a, T>);
+
+#[cfg(feature = "alloc")]
+impl<'a, T: fmt::Debug> fmt::Debug for Drain<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Drain")
+         .field(&self.0.as_slice())
+         .finish()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> Iterator for Drain<'a, T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> { self.0.next() }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> ExactSizeIterator for Drain<'a, T> { }
+#[cfg(feature = "alloc")]
+impl<'a, T> FusedIterator for Drain<'a, T> { }
+
+#[cfg(feature = "alloc")]
+impl<T: Ord> FromIterator<T> for MinMaxHeap<T> {
+    /// Collects the elements into a vector and then heapifies them all
+    /// at once, in *O*(*n*) time, rather than pushing them one by one.
+    fn from_iter<I>(iter: I) -> Self
+            where I: IntoIterator<Item = T> {
+        MinMaxHeap::from(iter.into_iter().collect::<Vec<T>>())
+    }
+}
+
+/// A draining iterator over the elements of the min-max-heap in
+/// ascending (min-first) order.
+///
+/// Note that each `next()` and `next_back()` operation is
+/// *O*(log *n*) time, so this currently provides no performance
+/// advantage over `pop_min()` and `pop_max()`. Skipping ahead with `nth()`
+/// or `nth_back()`, though, takes at most expected *O*(*n*) time, since
+/// large skips select the skipped elements rather than popping them.
+///
+/// This type is created with
+/// [`MinMaxHeap::drain_asc`](struct.MinMaxHeap.html#method.drain_asc).
+#[cfg(feature = "alloc")]
+pub struct DrainAsc<'a, T: 'a, C: 'a = Natural>(&'a mut MinMaxHeap<T, C>);
+
+/// A draining iterator over the elements of the min-max-heap in
+/// descending (max-first) order.
+///
+/// Note that each `next()` and `next_back()` operation is
+/// *O*(log *n*) time, so this currently provides no performance
+/// advantage over `pop_max()` and `pop_min()`. Skipping ahead with `nth()`
+/// or `nth_back()`, though, takes at most expected *O*(*n*) time, since
+/// large skips select the skipped elements rather than popping them.
+///
+/// This type is created with
+/// [`MinMaxHeap::drain_desc`](struct.MinMaxHeap.html#method.drain_desc).
+#[cfg(feature = "alloc")]
+pub struct DrainDesc<'a, T: 'a, C: 'a = Natural>(&'a mut MinMaxHeap<T, C>);
+
+// The draining iterators show the elements that remain in the heap, in
+// heap order rather than the order they will be drained in.
+
+#[cfg(feature = "alloc")]
+impl<'a, T: fmt::Debug, C> fmt::Debug for DrainAsc<'a, T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("DrainAsc")
+         .field(&(self.0).0)
+         .finish()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T: fmt::Debug, C> fmt::Debug for DrainDesc<'a, T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("DrainDesc")
+         .field(&(self.0).0)
+         .finish()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, C> Drop for DrainAsc<'a, T, C> {
+    fn drop(&mut self) {
+        let _ = (self.0).0.drain(..);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, C> Drop for DrainDesc<'a, T, C> {
+    fn drop(&mut self) {
+        let _ = (self.0).0.drain(..);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, C: Compare<T>> Iterator for DrainAsc<'a, T, C> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.0.pop_min()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<T> {
+        self.0.discard_extreme(n, false);
+        self.0.pop_min()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len(), Some(self.len()))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, C: Compare<T>> Iterator for DrainDesc<'a, T, C> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.0.pop_max()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<T> {
+        self.0.discard_extreme(n, true);
+        self.0.pop_max()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len(), Some(self.len()))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, C: Compare<T>> DoubleEndedIterator for DrainAsc<'a, T, C> {
+    fn next_back(&mut self) -> Option<T> {
+        self.0.pop_max()
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<T> {
+        self.0.discard_extreme(n, true);
+        self.0.pop_max()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, C: Compare<T>> DoubleEndedIterator for DrainDesc<'a, T, C> {
+    fn next_back(&mut self) -> Option<T> {
+        self.0.pop_min()
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<T> {
+        self.0.discard_extreme(n, false);
+        self.0.pop_min()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, C: Compare<T>> ExactSizeIterator for DrainAsc<'a, T, C> {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, C: Compare<T>> FusedIterator for DrainAsc<'a, T, C> { }
+
+#[cfg(feature = "alloc")]
+impl<'a, T, C: Compare<T>> ExactSizeIterator for DrainDesc<'a, T, C> {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, C: Compare<T>> FusedIterator for DrainDesc<'a, T, C> { }
+
+//
+// From<Vec<_>>
+//
+
+#[cfg(feature = "alloc")]
+impl<T: Ord> From<Vec<T>> for MinMaxHeap<T> {
+    fn from(vec: Vec<T>) -> Self {
+        MinMaxHeap::from_vec_with_comparator(vec, Natural)
+    }
+}
+
+/// Builds a heap from the elements of an array.
+///
+/// # Examples
+///
+/// ```
+/// use min_max_heap::MinMaxHeap;
+///
+/// let heap = MinMaxHeap::from([3, 1, 2]);
+///
+/// assert_eq!(heap.peek_min(), Some(&1));
+/// assert_eq!(heap.peek_max(), Some(&3));
+/// ```
+///
+/// *O*(*n*).
+#[cfg(feature = "alloc")]
+impl<T: Ord, const N: usize> From<[T; N]> for MinMaxHeap<T> {
+    fn from(array: [T; N]) -> Self {
+        MinMaxHeap::from(Vec::from(array))
+    }
+}
+
+/// Builds a heap from the elements of a set, using their order to place
+/// them without comparing them, as [`from_sorted_iter_asc`] does.
+///
+/// The keys of a `BTreeMap` are sorted too, and can be turned into a heap
+/// the same way with [`from_sorted_iter_asc`]:
+///
+/// ```
+/// use min_max_heap::MinMaxHeap;
+/// use std::collections::BTreeMap;
+///
+/// let map: BTreeMap<_, _> = vec![(3, "c"), (1, "a"), (2, "b")].into_iter().collect();
+/// let heap = MinMaxHeap::from_sorted_iter_asc(map.into_keys());
+///
+/// assert_eq!(heap.peek_max(), Some(&3));
+/// ```
+///
+/// *O*(*n*), with no comparisons up to 256 KiB of elements.
+///
+/// [`from_sorted_iter_asc`]: struct.MinMaxHeap.html#method.from_sorted_iter_asc
+#[cfg(feature = "alloc")]
+impl<T: Ord> From<BTreeSet<T>> for MinMaxHeap<T> {
+    fn from(set: BTreeSet<T>) -> Self {
+        MinMaxHeap::from_sorted_iter_asc(set)
+    }
+}
+
+/// Builds a heap from the elements of a deque, in its storage.
+///
+/// *O*(*n*).
+#[cfg(feature = "alloc")]
+impl<T: Ord> From<VecDeque<T>> for MinMaxHeap<T> {
+    fn from(deque: VecDeque<T>) -> Self {
+        MinMaxHeap::from(Vec::from(deque))
+    }
+}
+
+//
+// Into Vec<_> and Box<[_]>
+//
+
+/// Returns the heap’s elements in arbitrary order, as
+/// [`MinMaxHeap::into_vec`](struct.MinMaxHeap.html#method.into_vec) does.
+///
+/// *O*(1).
+#[cfg(feature = "alloc")]
+impl<T, C> From<MinMaxHeap<T, C>> for Vec<T> {
+    fn from(heap: MinMaxHeap<T, C>) -> Self {
+        heap.into_vec()
+    }
+}
+
+/// Returns the heap’s elements in arbitrary order, discarding any extra
+/// capacity.
+///
+/// *O*(*n*) if there is extra capacity to discard, and *O*(1) otherwise.
+#[cfg(feature = "alloc")]
+impl<T, C> From<MinMaxHeap<T, C>> for Box<[T]> {
+    fn from(heap: MinMaxHeap<T, C>) -> Self {
+        heap.into_vec().into_boxed_slice()
+    }
+}
+
+//
+// BinaryHeap conversions
+//
+
+/// Rebuilds a `BinaryHeap`’s elements, in its storage, as a min-max-heap.
+///
+/// *O*(*n*).
+#[cfg(feature = "alloc")]
+impl<T: Ord> From<BinaryHeap<T>> for MinMaxHeap<T> {
+    fn from(heap: BinaryHeap<T>) -> Self {
+        MinMaxHeap::from(heap.into_vec())
+    }
+}
+
+/// Rebuilds a min-max-heap’s elements, in its storage, as a
+/// `BinaryHeap`.
+///
+/// *O*(*n*).
+#[cfg(feature = "alloc")]
+impl<T: Ord> From<MinMaxHeap<T>> for BinaryHeap<T> {
+    fn from(heap: MinMaxHeap<T>) -> Self {
+        BinaryHeap::from(heap.into_vec())
+    }
+}
+
+//
+// Extend
+//
+
+#[cfg(feature = "alloc")]
+impl<T, C: Compare<T>> DoubleEndedHeap for MinMaxHeap<T, C> {
+    type Item = T;
+
+    fn push(&mut self, element: T) { MinMaxHeap::push(self, element) }
+    fn peek_min(&self) -> Option<&T> { MinMaxHeap::peek_min(self) }
+    fn peek_max(&self) -> Option<&T> { MinMaxHeap::peek_max(self) }
+    fn pop_min(&mut self) -> Option<T> { MinMaxHeap::pop_min(self) }
+    fn pop_max(&mut self) -> Option<T> { MinMaxHeap::pop_max(self) }
+    fn len(&self) -> usize { MinMaxHeap::len(self) }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, C: Compare<T>> Extend<T> for MinMaxHeap<T, C> {
+    #[cfg(not(feature = "unstable"))]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.extend_desugared(iter);
+    }
+
+    #[cfg(feature = "unstable")]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        <Self as spec_extend::SpecExtend<I::IntoIter>>::spec_extend(self, iter.into_iter());
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T: Clone + 'a, C: Compare<T>> Extend<&'a T> for MinMaxHeap<T, C> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().cloned());
+    }
+}
+
+/// The most bytes of elements that `heapify_sorted` arranges without
+/// comparisons rather than rebuilding.
+#[cfg(feature = "alloc")]
+const ARRANGE_SORTED_MAX_BYTES: usize = 256 * 1024;
+
+/// Restores the heap when dropped, even if extending it panics.
+#[cfg(feature = "alloc")]
+struct RebuildOnDrop<'a, T: 'a, C: 'a + Compare<T>> {
+    heap:  &'a mut MinMaxHeap<T, C>,
+    start: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, C: Compare<T>> Drop for RebuildOnDrop<'a, T, C> {
+    fn drop(&mut self) {
+        self.heap.rebuild_tail(self.start);
+    }
+}
+
+/// Structure wrapping a mutable reference to the minimum item on a
+/// `MinMaxHeap`.
+///
+/// This `struct` is created by the [`peek_min_mut`] method on [`MinMaxHeap`]. See
+/// its documentation for more.
+///
+/// [`peek_min_mut`]: struct.MinMaxHeap.html#method.peek_min_mut
+/// [`MinMaxHeap`]: struct.MinMaxHeap.html
+#[cfg(feature = "alloc")]
+pub struct PeekMinMut<'a, T: 'a, C: 'a + Compare<T> = Natural> {
+    heap: &'a mut MinMaxHeap<T, C>,
+    sift: bool,
+}
+
+#[cfg(feature = "alloc")]
+impl<T: fmt::Debug, C: Compare<T>> fmt::Debug for PeekMinMut<'_, T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("PeekMinMut")
+         .field(&**self)
+         .finish()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, C: Compare<T>> Drop for PeekMinMut<'a, T, C> {
+    fn drop(&mut self) {
+        if self.sift {
+            trickle_down_extreme_slice(&mut self.heap.0, 0, &self.heap.1);
+            paranoid_check!(self.heap);
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, C: Compare<T>> Deref for PeekMinMut<'a, T, C> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        debug_assert!(!self.heap.is_empty());
+        // SAFE: PeekMinMut is only instantiated for non-empty heaps
+        unchecked! { self.heap.get_at(0) }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, C: Compare<T>> DerefMut for PeekMinMut<'a, T, C> {
+    fn deref_mut(&mut self) -> &mut T {
+        debug_assert!(!self.heap.is_empty());
+        self.sift = true;
+        // SAFE: PeekMinMut is only instantiated for non-empty heaps
+        unchecked! { self.heap.get_at_mut(0) }
     }
+}
 
-    /// Returns a draining iterator over the min-max-heap’s elements in
-    /// descending (max-first) order.
-    ///
-    /// *O*(1) on creation, and *O*(log *n*) for each `next()` operation.
-    pub fn drain_desc(&mut self) -> DrainDesc<T> {
-        DrainDesc(self)
+#[cfg(feature = "alloc")]
+impl<'a, T, C: Compare<T>> PeekMinMut<'a, T, C> {
+    /// Removes the peeked value from the heap and returns it.
+    pub fn pop(mut self) -> T {
+        // Sift is unnecessary since pop_min() already reorders heap
+        self.sift = false;
+        self.heap.pop_min().unwrap()
     }
 }
 
-//
-// Iterators
-//
+/// Structure wrapping a mutable reference to the maximum item on a
+/// `MinMaxHeap`.
+///
+/// This `struct` is created by the [`peek_max_mut`] method on [`MinMaxHeap`]. See
+/// its documentation for more.
+///
+/// [`peek_max_mut`]: struct.MinMaxHeap.html#method.peek_max_mut
+/// [`MinMaxHeap`]: struct.MinMaxHeap.html
+#[cfg(feature = "alloc")]
+pub struct PeekMaxMut<'a, T: 'a, C: 'a + Compare<T> = Natural> {
+    heap: &'a mut MinMaxHeap<T, C>,
+    max_index: usize,
+    sift: bool,
+}
 
-/// A borrowed iterator over the elements of the min-max-heap in
-/// arbitrary order.
+#[cfg(feature = "alloc")]
+impl<T: fmt::Debug, C: Compare<T>> fmt::Debug for PeekMaxMut<'_, T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("PeekMaxMut")
+         .field(&**self)
+         .finish()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, C: Compare<T>> Drop for PeekMaxMut<'a, T, C> {
+    fn drop(&mut self) {
+        if self.sift {
+            // SAFETY: `max_index` is a valid index in `heap`
+            unchecked! {
+                self.heap.sift_max(self.max_index);
+            }
+            paranoid_check!(self.heap);
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, C: Compare<T>> Deref for PeekMaxMut<'a, T, C> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        debug_assert!(self.max_index < self.heap.len());
+        // SAFE: PeekMaxMut is only instantiated for non-empty heaps
+        unchecked! { self.heap.get_at(self.max_index) }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, C: Compare<T>> DerefMut for PeekMaxMut<'a, T, C> {
+    fn deref_mut(&mut self) -> &mut T {
+        debug_assert!(self.max_index < self.heap.len());
+        self.sift = true;
+        // SAFE: PeekMaxMut is only instantiated for non-empty heaps
+        unchecked! { self.heap.get_at_mut(self.max_index) }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, C: Compare<T>> PeekMaxMut<'a, T, C> {
+    /// Removes the peeked value from the heap and returns it.
+    pub fn pop(mut self) -> T {
+        // Sift is unnecessary since pop_max() already reorders heap
+        self.sift = false;
+        self.heap.pop_max().unwrap()
+    }
+}
+
+/// Structure wrapping a mutable reference to an arbitrary item on a
+/// `MinMaxHeap`.
 ///
-/// This type is created with
-/// [`MinMaxHeap::iter`](struct.MinMaxHeap.html#method.iter).
-pub struct Iter<'a, T: 'a>(slice::Iter<'a, T>);
+/// This `struct` is created by the [`get_mut_by`] method on [`MinMaxHeap`]. See
+/// its documentation for more.
+///
+/// [`get_mut_by`]: struct.MinMaxHeap.html#method.get_mut_by
+/// [`MinMaxHeap`]: struct.MinMaxHeap.html
+#[cfg(feature = "alloc")]
+pub struct ElementMut<'a, T: 'a, C: 'a + Compare<T> = Natural> {
+    heap: &'a mut MinMaxHeap<T, C>,
+    pos: usize,
+    sift: bool,
+}
 
-impl<'a, T> Iterator for Iter<'a, T> {
-    type Item = &'a T;
-    fn next(&mut self) -> Option<Self::Item> { self.0.next() }
+#[cfg(feature = "alloc")]
+impl<T: fmt::Debug, C: Compare<T>> fmt::Debug for ElementMut<'_, T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("ElementMut")
+         .field(&**self)
+         .finish()
+    }
+}
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        self.0.size_hint()
+#[cfg(feature = "alloc")]
+impl<'a, T, C: Compare<T>> Drop for ElementMut<'a, T, C> {
+    fn drop(&mut self) {
+        if self.sift {
+            // SAFETY: `pos` is a valid index in `heap`
+            unchecked! {
+                self.heap.sift(self.pos);
+            }
+            paranoid_check!(self.heap);
+        }
     }
 }
 
-impl<'a, T> ExactSizeIterator for Iter<'a, T> { }
+#[cfg(feature = "alloc")]
+impl<'a, T, C: Compare<T>> Deref for ElementMut<'a, T, C> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        debug_assert!(self.pos < self.heap.len());
+        // SAFE: ElementMut is only instantiated with valid positions
+        unchecked! { self.heap.get_at(self.pos) }
+    }
+}
 
-impl<'a, T> IntoIterator for &'a MinMaxHeap<T> {
-    type Item = &'a T;
-    type IntoIter = Iter<'a, T>;
-    fn into_iter(self) -> Self::IntoIter { self.iter() }
+#[cfg(feature = "alloc")]
+impl<'a, T, C: Compare<T>> DerefMut for ElementMut<'a, T, C> {
+    fn deref_mut(&mut self) -> &mut T {
+        debug_assert!(self.pos < self.heap.len());
+        self.sift = true;
+        // SAFE: ElementMut is only instantiated with valid positions
+        unchecked! { self.heap.get_at_mut(self.pos) }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, C: Compare<T>> ElementMut<'a, T, C> {
+    /// Removes the referenced value from the heap and returns it.
+    pub fn pop(mut self) -> T {
+        // Sift is unnecessary since swap_remove_index() already reorders heap
+        self.sift = false;
+        self.heap.swap_remove_index(self.pos)
+    }
 }
 
-/// An owning iterator over the elements of the min-max-heap in
-/// arbitrary order.
-pub struct IntoIter<T>(vec::IntoIter<T>);
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    extern crate rand;
+
+    use super::*;
+    use self::rand::Rng;
+    use self::rand::seq::SliceRandom;
+
+    #[test]
+    fn example() {
+        let mut h = MinMaxHeap::new();
+        assert!(h.is_empty());
+
+        h.push(5);
+        assert!(!h.is_empty());
+        assert_eq!(Some(&5), h.peek_min());
+        assert_eq!(Some(&5), h.peek_max());
+
+        h.push(7);
+        assert_eq!(Some(&5), h.peek_min());
+        assert_eq!(Some(&7), h.peek_max());
+
+        h.push(6);
+        assert_eq!(Some(&5), h.peek_min());
+        assert_eq!(Some(&7), h.peek_max());
+
+        assert_eq!(Some(5), h.pop_min());
+        assert_eq!(Some(7), h.pop_max());
+        assert_eq!(Some(6), h.pop_max());
+        assert_eq!(None, h.pop_min());
+    }
+
+    #[test]
+    fn drain_asc() {
+        let mut h = MinMaxHeap::from(vec![3, 2, 4, 1]);
+        let mut i = h.drain_asc();
+        assert_eq!( i.next(), Some(1) );
+        assert_eq!( i.next(), Some(2) );
+        assert_eq!( i.next(), Some(3) );
+        assert_eq!( i.next(), Some(4) );
+        assert_eq!( i.next(), None );
+    }
+
+    // This test catches a lot:
+    #[test]
+    fn random_vectors() {
+        for i in 0 .. 300 {
+            check_heap(&random_heap(i));
+        }
+    }
+
+    #[test]
+    fn from_vector() {
+        for i in 0 .. 300 {
+            check_heap(&MinMaxHeap::from(random_vec(i)))
+        }
+    }
+
+    fn check_heap(heap: &MinMaxHeap<usize>) {
+        heap.assert_valid();
+
+        let asc  = iota_asc(heap.len());
+        let desc = iota_desc(heap.len());
+
+        assert_eq!(asc, into_vec_asc(heap.clone()));
+        assert_eq!(desc, into_vec_desc(heap.clone()));
+        assert_eq!(asc, heap.clone().into_vec_asc());
+        assert_eq!(desc, heap.clone().into_vec_desc());
+    }
+
+    fn random_vec(len: usize) -> Vec<usize> {
+        let mut result = (0 .. len).collect::<Vec<_>>();
+        result.shuffle(&mut rand::thread_rng());
+        result
+    }
+
+    fn random_heap(len: usize) -> MinMaxHeap<usize> {
+        MinMaxHeap::from_iter(random_vec(len))
+    }
+
+    fn into_vec_asc(mut heap: MinMaxHeap<usize>) -> Vec<usize> {
+        let mut result = Vec::with_capacity(heap.len());
+        while let Some(elem) = heap.pop_min() {
+            result.push(elem)
+        }
+        result
+    }
+
+    fn into_vec_desc(mut heap: MinMaxHeap<usize>) -> Vec<usize> {
+        let mut result = Vec::with_capacity(heap.len());
+        while let Some(elem) = heap.pop_max() {
+            result.push(elem)
+        }
+        result
+    }
+
+    fn iota_asc(len: usize) -> Vec<usize> {
+        (0 .. len).collect()
+    }
+
+    fn iota_desc(len: usize) -> Vec<usize> {
+        let mut result = (0 .. len).collect::<Vec<_>>();
+        result.reverse();
+        result
+    }
+
+    #[test]
+    fn replace_min() {
+        let mut h = MinMaxHeap::from(vec![1, 2]);
+        assert_eq!(Some(1), h.replace_min(0));
+        assert_eq!(Some(&0), h.peek_min());
+        assert_eq!(Some(&2), h.peek_max());
+
+        assert_eq!(Some(0), h.replace_min(3));
+        assert_eq!(Some(&2), h.peek_min());
+        assert_eq!(Some(&3), h.peek_max());
+    }
+
+    #[test]
+    fn replace_min_edge_cases() {
+        let mut empty_heap = MinMaxHeap::new();
+        assert_eq!(None, empty_heap.replace_min(1));
+        assert_eq!(Some(1), empty_heap.pop_min());
+        assert_eq!(None, empty_heap.pop_min());
+
+        let mut one_element_heap = MinMaxHeap::from(vec![2]);
+        assert_eq!(Some(2), one_element_heap.replace_min(1));
+        assert_eq!(Some(1), one_element_heap.pop_min());
+        assert_eq!(None, one_element_heap.pop_min());
+    }
+
+    #[test]
+    fn replace_max() {
+        let mut h = MinMaxHeap::from(vec![1, 2]);
+        assert_eq!(Some(2), h.replace_max(3));
+        assert_eq!(Some(&1), h.peek_min());
+        assert_eq!(Some(&3), h.peek_max());
+
+        assert_eq!(Some(3), h.replace_max(0));
+        assert_eq!(Some(&0), h.peek_min());
+        assert_eq!(Some(&1), h.peek_max());
+    }
+
+    #[test]
+    fn replace_max_edge_cases() {
+        let mut empty_heap = MinMaxHeap::new();
+        assert_eq!(None, empty_heap.replace_max(1));
+        assert_eq!(Some(1), empty_heap.pop_max());
+        assert_eq!(None, empty_heap.pop_max());
+
+        let mut one_element_heap = MinMaxHeap::from(vec![1]);
+        assert_eq!(Some(1), one_element_heap.replace_max(2));
+        assert_eq!(Some(2), one_element_heap.pop_max());
+        assert_eq!(None, one_element_heap.pop_max());
+    }
+
+    #[test]
+    fn peek_min_mut() {
+        let mut h = MinMaxHeap::from(vec![2, 3, 4]);
+        *h.peek_min_mut().unwrap() = 1;
+        assert_eq!(Some(&1), h.peek_min());
+        assert_eq!(Some(&4), h.peek_max());
 
-impl<T> Iterator for IntoIter<T> {
-    type Item = T;
-    fn next(&mut self) -> Option<Self::Item> { self.0.next() }
+        *h.peek_min_mut().unwrap() = 8;
+        assert_eq!(Some(&3), h.peek_min());
+        assert_eq!(Some(&8), h.peek_max());
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        self.0.size_hint()
+        assert_eq!(3, h.peek_min_mut().unwrap().pop());
+        assert_eq!(Some(&4), h.peek_min());
+        assert_eq!(Some(&8), h.peek_max());
     }
-}
 
-impl<T> ExactSizeIterator for IntoIter<T> { }
+    #[test]
+    fn peek_max_mut() {
+        let mut h = MinMaxHeap::from(vec![1, 2]);
+        *h.peek_max_mut().unwrap() = 3;
+        assert_eq!(Some(&1), h.peek_min());
+        assert_eq!(Some(&3), h.peek_max());
 
-impl<'a, T> IntoIterator for MinMaxHeap<T> {
-    type Item = T;
-    type IntoIter = IntoIter<T>;
-    fn into_iter(self) -> Self::IntoIter {
-        IntoIter(self.0.into_iter())
-    }
-}
+        *h.peek_max_mut().unwrap() = 0;
+        assert_eq!(Some(&0), h.peek_min());
+        assert_eq!(Some(&1), h.peek_max());
 
-/// A draining iterator over the elements of the min-max-heap in
-/// arbitrary order.
-///
-/// This type is created with
-/// [`MinMaxHeap::drain`](struct.MinMaxHeap.html#method.drain).
-pub struct Drain<'a, T: 'a>(vec::Drain<'a, T>);
+        assert_eq!(1, h.peek_max_mut().unwrap().pop());
+        assert_eq!(Some(&0), h.peek_min());
+        assert_eq!(Some(&0), h.peek_max());
 
-impl<'a, T> Iterator for Drain<'a, T> {
-    type Item = T;
-    fn next(&mut self) -> Option<Self::Item> { self.0.next() }
+        *h.peek_max_mut().unwrap() = 1;
+        assert_eq!(Some(&1), h.peek_min());
+        assert_eq!(Some(&1), h.peek_max());
+    }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        self.0.size_hint()
+    #[test]
+    fn peek_max_mut_one() {
+        let mut h = MinMaxHeap::from(vec![1]);
+        {
+            let mut max = h.peek_max_mut().unwrap();
+            assert_eq!(*max, 1);
+            *max = 2;
+        }
+        assert_eq!(h.peek_max(), Some(&2));
     }
-}
 
-impl<'a, T> ExactSizeIterator for Drain<'a, T> { }
+    #[test]
+    fn push_pop_max() {
+        let mut h = MinMaxHeap::from(vec![1, 2]);
+        assert_eq!(3, h.push_pop_max(3));
+        assert_eq!(2, h.push_pop_max(0));
+        assert_eq!(Some(&0), h.peek_min());
+        assert_eq!(Some(&1), h.peek_max());
+    }
 
-impl<T: Ord> FromIterator<T> for MinMaxHeap<T> {
-    fn from_iter<I>(iter: I) -> Self
-            where I: IntoIterator<Item = T> {
-        MinMaxHeap::from(iter.into_iter().collect::<Vec<T>>())
+    #[test]
+    fn count() {
+        let mut rng = rand::thread_rng();
+        for len in 0 .. 100 {
+            let vec: Vec<usize> = (0 .. len).map(|_| rng.gen_range(0 .. 10)).collect();
+            let heap = MinMaxHeap::from(vec.clone());
+            for x in 0 .. 11 {
+                let expected = vec.iter().filter(|&&e| e == x).count();
+                assert_eq!(expected, heap.count(&x));
+            }
+        }
     }
-}
 
-/// A draining iterator over the elements of the min-max-heap in
-/// ascending (min-first) order.
-///
-/// Note that each `next()` and `next_back()` operation is
-/// *O*(log *n*) time, so this currently provides no performance
-/// advantage over `pop_min()` and `pop_max()`.
-///
-/// This type is created with
-/// [`MinMaxHeap::drain_asc`](struct.MinMaxHeap.html#method.drain_asc).
-#[derive(Debug)]
-pub struct DrainAsc<'a, T: 'a>(&'a mut MinMaxHeap<T>);
+    #[test]
+    fn count_lt_gt() {
+        let mut rng = rand::thread_rng();
+        for len in 0 .. 100 {
+            let vec: Vec<usize> = (0 .. len).map(|_| rng.gen_range(0 .. 10)).collect();
+            let heap = MinMaxHeap::from(vec.clone());
+            for x in 0 .. 11 {
+                assert_eq!(vec.iter().filter(|&&e| e < x).count(), heap.count_lt(&x));
+                assert_eq!(vec.iter().filter(|&&e| e > x).count(), heap.count_gt(&x));
+            }
+        }
+    }
 
-/// A draining iterator over the elements of the min-max-heap in
-/// descending (max-first) order.
-///
-/// Note that each `next()` and `next_back()` operation is
-/// *O*(log *n*) time, so this currently provides no performance
-/// advantage over `pop_max()` and `pop_min()`.
-///
-/// This type is created with
-/// [`MinMaxHeap::drain_desc`](struct.MinMaxHeap.html#method.drain_desc).
-#[derive(Debug)]
-pub struct DrainDesc<'a, T: 'a>(&'a mut MinMaxHeap<T>);
+    #[test]
+    fn range_count() {
+        let mut rng = rand::thread_rng();
+        for len in 0 .. 100 {
+            let vec: Vec<usize> = (0 .. len).map(|_| rng.gen_range(0 .. 10)).collect();
+            let heap = MinMaxHeap::from(vec.clone());
+            let count = |f: &dyn Fn(usize) -> bool| vec.iter().filter(|&&e| f(e)).count();
+            for x in 0 .. 11 {
+                for y in x .. 11 {
+                    assert_eq!(count(&|e| x <= e && e < y), heap.range_count(x .. y));
+                    assert_eq!(count(&|e| x <= e && e <= y), heap.range_count(x ..= y));
+                    assert_eq!(count(&|e| x < e && e <= y),
+                               heap.range_count((Bound::Excluded(x), Bound::Included(y))));
+                }
+                assert_eq!(count(&|e| x <= e), heap.range_count(x ..));
+                assert_eq!(count(&|e| e < x), heap.range_count(.. x));
+            }
+            assert_eq!(len, heap.range_count(..));
+        }
+    }
 
-impl<'a, T> Drop for DrainAsc<'a, T> {
-    fn drop(&mut self) {
-        let _ = (self.0).0.drain(..);
+    #[test]
+    fn split_off_min_max() {
+        for len in 0 .. 40 {
+            for n in 0 .. len + 2 {
+                let mut heap = random_heap(len);
+                let low = heap.split_off_min(n);
+                let k = n.min(len);
+                assert_eq!((0 .. k).collect::<Vec<_>>(), low.into_vec_asc());
+                assert_eq!((k .. len).collect::<Vec<_>>(), heap.into_vec_asc());
+
+                let mut heap = random_heap(len);
+                let high = heap.split_off_max(n);
+                assert_eq!((len - k .. len).collect::<Vec<_>>(), high.into_vec_asc());
+                assert_eq!((0 .. len - k).collect::<Vec<_>>(), heap.into_vec_asc());
+            }
+        }
     }
-}
 
-impl<'a, T> Drop for DrainDesc<'a, T> {
-    fn drop(&mut self) {
-        let _ = (self.0).0.drain(..);
+    #[test]
+    fn split_off_ge_lt() {
+        for len in 0 .. 40 {
+            for bound in 0 .. len + 1 {
+                let mut heap = random_heap(len);
+                let high = heap.split_off_ge(&bound);
+                assert_eq!((bound .. len).collect::<Vec<_>>(), high.into_vec_asc());
+                assert_eq!((0 .. bound).collect::<Vec<_>>(), heap.into_vec_asc());
+
+                let mut heap = random_heap(len);
+                let low = heap.split_off_lt(&bound);
+                assert_eq!((0 .. bound).collect::<Vec<_>>(), low.into_vec_asc());
+                assert_eq!((bound .. len).collect::<Vec<_>>(), heap.into_vec_asc());
+            }
+        }
     }
-}
 
-impl<'a, T: Ord> Iterator for DrainAsc<'a, T> {
-    type Item = T;
+    #[test]
+    fn drop_below_above() {
+        for len in 0 .. 40 {
+            for bound in 0 .. len + 1 {
+                let mut heap = random_heap(len);
+                assert_eq!(bound, heap.drop_below(&bound));
+                assert_eq!((bound .. len).collect::<Vec<_>>(), heap.into_vec_asc());
+
+                let mut heap = random_heap(len);
+                assert_eq!(len.saturating_sub(bound + 1), heap.drop_above(&bound));
+                assert_eq!((0 .. len.min(bound + 1)).collect::<Vec<_>>(),
+                           heap.into_vec_asc());
+            }
+        }
+    }
 
-    fn next(&mut self) -> Option<T> {
-        self.0.pop_min()
+    #[test]
+    fn truncate_to_smallest_largest() {
+        for len in 0 .. 40 {
+            for n in 0 .. len + 2 {
+                let k = n.min(len);
+
+                let mut heap = random_heap(len);
+                heap.truncate_to_smallest(n);
+                assert_eq!((0 .. k).collect::<Vec<_>>(), heap.into_vec_asc());
+
+                let mut heap = random_heap(len);
+                heap.truncate_to_largest(n);
+                assert_eq!((len - k .. len).collect::<Vec<_>>(), heap.into_vec_asc());
+            }
+        }
     }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        (self.len(), Some(self.len()))
+    #[test]
+    fn try_push() {
+        let mut h = MinMaxHeap::new();
+        for i in random_vec(100) {
+            h.try_push(i).unwrap();
+            h.assert_valid();
+        }
+        assert_eq!((0 .. 100).collect::<Vec<_>>(), h.into_vec_asc());
     }
-}
 
-impl<'a, T: Ord> Iterator for DrainDesc<'a, T> {
-    type Item = T;
+    #[test]
+    fn push_within_capacity() {
+        let mut h = MinMaxHeap::new();
+        assert_eq!(Err(1), h.push_within_capacity(1));
 
-    fn next(&mut self) -> Option<T> {
-        self.0.pop_max()
+        h.reserve_exact(10);
+        let capacity = h.capacity();
+        for i in 0 .. capacity {
+            assert_eq!(Ok(()), h.push_within_capacity(capacity - i));
+        }
+        assert_eq!(Err(0), h.push_within_capacity(0));
+        assert_eq!(capacity, h.capacity());
+        assert_eq!((1 ..= capacity).collect::<Vec<_>>(), h.into_vec_asc());
     }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        (self.len(), Some(self.len()))
+    #[test]
+    fn iterator_clone_and_debug() {
+        let mut h: MinMaxHeap<u32> = vec![3, 1, 2].into();
+
+        let mut iter = h.iter();
+        iter.next();
+        assert_eq!("Iter([3, 2])", format!("{:?}", iter));
+        assert_eq!(vec![&3, &2], iter.clone().collect::<Vec<_>>());
+
+        let mut iter = h.iter_asc();
+        iter.next();
+        assert_eq!("IterAsc([2, 3])", format!("{:?}", iter));
+        assert_eq!(vec![&2, &3], iter.clone().collect::<Vec<_>>());
+        assert_eq!("IterDesc([3, 2, 1])", format!("{:?}", h.iter_desc()));
+
+        let mut iter = h.clone().into_iter();
+        iter.next();
+        assert_eq!("IntoIter([3, 2])", format!("{:?}", iter));
+        assert_eq!(vec![3, 2], iter.clone().collect::<Vec<_>>());
+
+        assert_eq!("Drain([1, 3, 2])", format!("{:?}", h.clone().drain()));
+        assert_eq!("DrainDesc([1, 3, 2])", format!("{:?}", h.clone().drain_desc()));
+
+        let mut drain = h.drain_asc();
+        drain.next();
+        assert_eq!("DrainAsc([2, 3])", format!("{:?}", drain));
     }
-}
 
-impl<'a, T: Ord> DoubleEndedIterator for DrainAsc<'a, T> {
-    fn next_back(&mut self) -> Option<T> {
-        self.0.pop_max()
+    #[test]
+    fn drain_nth() {
+        for &(len, skip) in &[(0, 0), (10, 0), (10, 3), (10, 9), (10, 10), (10, 20),
+                              (1000, 5), (1000, 700), (1000, 999)] {
+            let mut h: MinMaxHeap<usize> = (0 .. len).map(|i| i * 37 % len.max(1)).collect();
+            h.extend(0 .. len / 4);
+            let sorted = h.clone().into_vec_asc();
+            let rest = sorted.get(skip + 1 ..).unwrap_or(&[]);
+
+            let mut asc = h.clone();
+            let mut drain = asc.drain_asc();
+            assert_eq!(sorted.get(skip), drain.nth(skip).as_ref());
+            assert_eq!(rest.iter().rev().nth(2), drain.nth_back(2).as_ref());
+            assert_eq!(rest.len().saturating_sub(3), drain.len());
+            drop(drain);
+
+            let mut desc = h.clone();
+            let mut drain = desc.drain_desc();
+            assert_eq!(sorted.iter().rev().nth(skip), drain.nth(skip).as_ref());
+            let expected = &sorted[.. sorted.len().saturating_sub(skip + 1)];
+            assert_eq!(expected, &drain.rev().collect::<Vec<_>>()[..]);
+        }
     }
-}
 
-impl<'a, T: Ord> DoubleEndedIterator for DrainDesc<'a, T> {
-    fn next_back(&mut self) -> Option<T> {
-        self.0.pop_min()
+    #[test]
+    fn fused() {
+        fn assert_fused<I: FusedIterator>(_: I) { }
+
+        let mut h: MinMaxHeap<u32> = (0 .. 10).collect();
+        assert_fused(h.iter());
+        assert_fused(h.iter_asc());
+        assert_fused(h.iter_desc());
+        assert_fused(h.levels());
+        assert_fused(h.drain_asc());
+        assert_fused(h.drain_desc());
+        assert_fused(h.drain());
+        assert_fused(h.clone().into_iter());
+
+        let mut iter = vec![1].into_iter().collect::<MinMaxHeap<_>>().into_iter();
+        assert_eq!(Some(1), iter.next());
+        assert_eq!(None, iter.next());
+        assert_eq!(None, iter.next());
     }
-}
 
-impl<'a, T: Ord> ExactSizeIterator for DrainAsc<'a, T> {
-    fn len(&self) -> usize {
-        self.0.len()
+    // Validating the heap would add comparisons of its own.
+    #[cfg(not(feature = "paranoid"))]
+    #[test]
+    fn collect_is_linear() {
+        use core::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+
+        static COMPARISONS: AtomicUsize = AtomicUsize::new(0);
+
+        #[derive(PartialEq, Eq)]
+        struct Counted(usize);
+
+        impl PartialOrd for Counted {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for Counted {
+            fn cmp(&self, other: &Self) -> Ordering {
+                COMPARISONS.fetch_add(1, Relaxed);
+                self.0.cmp(&other.0)
+            }
+        }
+
+        let n = 1 << 14;
+        let h: MinMaxHeap<Counted> = (0 .. n).map(Counted).collect();
+        assert_eq!(n, h.len());
+        assert!((n / 2 .. 4 * n).contains(&COMPARISONS.load(Relaxed)));
     }
-}
 
-impl<'a, T: Ord> ExactSizeIterator for DrainDesc<'a, T> {
-    fn len(&self) -> usize {
-        self.0.len()
+    #[test]
+    fn extend_in_bulk() {
+        let mut h: MinMaxHeap<u32> = MinMaxHeap::new();
+        h.extend((0 .. 1000).filter(|i| i % 3 != 0).map(|i| i * 7 % 1000));
+        h.assert_valid();
+
+        h.extend(&[5000, 0]);
+        h.extend((0 .. 2000).rev());
+        h.assert_valid();
+        assert_eq!(Some(&0), h.peek_min());
+        assert_eq!(Some(&5000), h.peek_max());
+        assert_eq!(2668, h.len());
     }
-}
 
-//
-// From<Vec<_>>
-//
+    #[test]
+    fn extend_from_vec() {
+        let v = vec![4, 2, 7, 1];
+        let ptr = v.as_ptr();
+
+        let mut h: MinMaxHeap<u32> = MinMaxHeap::new();
+        h.extend_from_vec(v);
+        h.assert_valid();
+        assert_eq!(ptr, h.0.as_ptr());
+
+        h.extend_from_vec(vec![9]);
+        h.extend_from_vec((10 .. 100).rev().collect());
+        h.extend_from_vec(Vec::new());
+        h.assert_valid();
+        assert_eq!(95, h.len());
+        assert_eq!(Some(&1), h.peek_min());
+        assert_eq!(Some(&99), h.peek_max());
 
-impl<T: Ord> From<Vec<T>> for MinMaxHeap<T> {
-    fn from(vec: Vec<T>) -> Self {
-        let mut heap = MinMaxHeap(vec);
-        heap.rebuild();
-        heap
+        let mut h: MinMaxHeap<u32> = MinMaxHeap::with_capacity(100);
+        h.extend_from_vec(vec![3, 1, 2]);
+        assert!(h.capacity() >= 100);
+        assert_eq!(vec![1, 2, 3], h.into_vec_asc());
     }
-}
 
-//
-// Extend
-//
+    #[test]
+    fn extend_from_slice() {
+        let mut h: MinMaxHeap<u32> = MinMaxHeap::new();
+        h.extend_from_slice(&[]);
+        h.extend_from_slice(&[7, 3, 5]);
+        h.extend_from_slice(&(0 .. 100).rev().collect::<Vec<_>>());
+        h.extend_from_slice(&[200]);
+        h.assert_valid();
+        assert_eq!(104, h.len());
+        assert_eq!(Some(&200), h.peek_max());
+
+        let mut h: MinMaxHeap<Vec<u8>> = MinMaxHeap::new();
+        h.extend_from_slice(&[vec![2], vec![1, 5]]);
+        assert_eq!(Some(&vec![1, 5]), h.peek_min());
+    }
 
-impl<T: Ord> Extend<T> for MinMaxHeap<T> {
-    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
-        for elem in iter {
-            self.push(elem)
+    #[test]
+    fn push_sorted() {
+        for len in 0 .. 70 {
+            let mut h: MinMaxHeap<usize> = MinMaxHeap::new();
+            h.push_sorted_asc(0 .. len);
+            h.assert_valid();
+            assert_eq!((0 .. len).collect::<Vec<_>>(), h.into_vec_asc());
+
+            let mut h: MinMaxHeap<usize> = MinMaxHeap::new();
+            h.push_sorted_desc((0 .. len).rev());
+            h.assert_valid();
+            assert_eq!((0 .. len).collect::<Vec<_>>(), h.into_vec_asc());
         }
-    }
-}
 
-impl<'a, T: Ord + Clone + 'a> Extend<&'a T> for MinMaxHeap<T> {
-    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
-        for elem in iter {
-            self.push(elem.clone())
+        let mut h = MinMaxHeap::from(vec![50, 5]);
+        h.push_sorted_asc(vec![1, 20, 30]);
+        h.push_sorted_desc(vec![60, 40, 0]);
+        h.assert_valid();
+        assert_eq!(vec![0, 1, 5, 20, 30, 40, 50, 60], h.into_vec_asc());
+
+        // Runs that straddle the heap’s maximum or minimum.
+        for len in 1 .. 50 {
+            let mut expected: Vec<usize> = (0 .. len).map(|i| i * 7 % 50 + 10).collect();
+            let mut h = MinMaxHeap::from(expected.clone());
+            h.push_sorted_asc(40 .. 80);
+            h.assert_valid();
+            h.push_sorted_desc((0 .. 20).rev());
+            h.assert_valid();
+
+            expected.extend((40 .. 80).chain(0 .. 20));
+            expected.sort();
+            assert_eq!(expected, h.into_vec_asc());
         }
     }
-}
 
-/// Structure wrapping a mutable reference to the minimum item on a
-/// `MinMaxHeap`.
-///
-/// This `struct` is created by the [`peek_min_mut`] method on [`MinMaxHeap`]. See
-/// its documentation for more.
-///
-/// [`peek_min_mut`]: struct.MinMaxHeap.html#method.peek_min_mut
-/// [`MinMaxHeap`]: struct.MinMaxHeap.html
-pub struct PeekMinMut<'a, T: Ord> {
-    heap: &'a mut MinMaxHeap<T>,
-    sift: bool,
-}
+    // Validating the heap would add comparisons of its own.
+    #[cfg(not(feature = "paranoid"))]
+    #[test]
+    fn push_sorted_beyond_extremes() {
+        use core::cell::Cell;
 
-impl<T: Ord + fmt::Debug> fmt::Debug for PeekMinMut<'_, T> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.debug_tuple("PeekMinMut")
-         .field(&**self)
-         .finish()
+        let comparisons = Cell::new(0);
+        let cmp = FnComparator(|a: &usize, b: &usize| {
+            comparisons.set(comparisons.get() + 1);
+            a.cmp(b)
+        });
+
+        let elements = random_vec(1000).into_iter().map(|i| i + 1000).collect();
+        let mut h = MinMaxHeap::from_vec_with_comparator(elements, cmp);
+
+        comparisons.set(0);
+        h.push_sorted_asc(2000 .. 3000);
+        assert!(comparisons.get() <= 12, "{} comparisons", comparisons.get());
+
+        comparisons.set(0);
+        h.push_sorted_desc((0 .. 1000).rev());
+        assert!(comparisons.get() <= 12, "{} comparisons", comparisons.get());
+
+        assert_eq!(3000, h.len());
+        h.assert_valid();
     }
-}
 
-impl<'a, T: Ord> Drop for PeekMinMut<'a, T> {
-    fn drop(&mut self) {
-        if self.sift {
-            // SAFETY: `heap` is not empty
-            unsafe {
-                self.heap.trickle_down_min(0);
-            }
+    #[test]
+    fn from_sorted_vec() {
+        assert_eq!(Some(false), MinMaxHeap::sorted_order(&[1, 1, 2, 5], &Natural));
+        assert_eq!(Some(true), MinMaxHeap::sorted_order(&[3, 3, 2, 2], &Natural));
+        assert_eq!(Some(false), MinMaxHeap::sorted_order(&[4, 4], &Natural));
+        assert_eq!(Some(false), MinMaxHeap::<u32>::sorted_order(&[], &Natural));
+        assert_eq!(None, MinMaxHeap::sorted_order(&[1, 3, 2], &Natural));
+        assert_eq!(None, MinMaxHeap::sorted_order(&[3, 3, 1, 2], &Natural));
+
+        for len in 0 .. 40 {
+            let h = MinMaxHeap::from((0 .. len).collect::<Vec<_>>());
+            h.assert_valid();
+            assert_eq!((0 .. len).collect::<Vec<_>>(), h.into_vec_asc());
+
+            let h = MinMaxHeap::from((0 .. len).rev().collect::<Vec<_>>());
+            h.assert_valid();
+            assert_eq!((0 .. len).rev().collect::<Vec<_>>(), h.into_vec_desc());
         }
-    }
-}
 
-impl<'a, T: Ord> Deref for PeekMinMut<'a, T> {
-    type Target = T;
-    fn deref(&self) -> &T {
-        debug_assert!(!self.heap.is_empty());
-        // SAFE: PeekMinMut is only instantiated for non-empty heaps
-        unsafe { self.heap.0.get_unchecked(0) }
+        // Arranged in the vector’s own buffer.
+        let vec: Vec<u32> = (0 .. 5000).map(|i| i / 3).collect();
+        let ptr = vec.as_ptr();
+        let h = MinMaxHeap::from(vec);
+        assert_eq!(ptr, h.0.as_ptr());
+        h.assert_valid();
     }
-}
 
-impl<'a, T: Ord> DerefMut for PeekMinMut<'a, T> {
-    fn deref_mut(&mut self) -> &mut T {
-        debug_assert!(!self.heap.is_empty());
-        self.sift = true;
-        // SAFE: PeekMinMut is only instantiated for non-empty heaps
-        unsafe { self.heap.0.get_unchecked_mut(0) }
+    #[test]
+    #[cfg(not(feature = "safe"))]
+    fn from_vec_unchecked() {
+        let heap = random_heap(50);
+        let vec = heap.clone().into_vec();
+        let back = unsafe { MinMaxHeap::from_vec_unchecked(vec.clone()) };
+        assert_eq!(vec, back.clone().into_vec());
+        assert_eq!(heap.into_vec_asc(), back.into_vec_asc());
     }
-}
 
-impl<'a, T: Ord> PeekMinMut<'a, T> {
-    /// Removes the peeked value from the heap and returns it.
-    pub fn pop(mut self) -> T {
-        // Sift is unnecessary since pop_min() already reorders heap
-        self.sift = false;
-        self.heap.pop_min().unwrap()
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "not in heap order")]
+    #[cfg(not(feature = "safe"))]
+    fn from_vec_unchecked_invalid() {
+        let _ = unsafe { MinMaxHeap::from_vec_unchecked(vec![5, 4, 6]) };
     }
-}
 
-/// Structure wrapping a mutable reference to the maximum item on a
-/// `MinMaxHeap`.
-///
-/// This `struct` is created by the [`peek_max_mut`] method on [`MinMaxHeap`]. See
-/// its documentation for more.
-///
-/// [`peek_max_mut`]: struct.MinMaxHeap.html#method.peek_max_mut
-/// [`MinMaxHeap`]: struct.MinMaxHeap.html
-pub struct PeekMaxMut<'a, T: Ord> {
-    heap: &'a mut MinMaxHeap<T>,
-    max_index: usize,
-    sift: bool,
-}
+    #[test]
+    fn from_sorted_iter() {
+        let h = MinMaxHeap::from_sorted_iter_asc((0 .. 30).map(|i| i / 3));
+        h.assert_valid();
+        assert_eq!((0 .. 30).map(|i| i / 3).collect::<Vec<_>>(), h.into_vec_asc());
+
+        let h = MinMaxHeap::from_sorted_iter_desc((0 .. 30).rev());
+        h.assert_valid();
+        assert_eq!((0 .. 30).rev().collect::<Vec<_>>(), h.into_vec_desc());
+
+        assert!(MinMaxHeap::<u32>::from_sorted_iter_asc(None).is_empty());
+
+        // Past `ARRANGE_SORTED_MAX_BYTES`, as well as below it.
+        for &len in &[1, 2, 5, 64, 100, 257, 80_000] {
+            let h = MinMaxHeap::from_sorted_iter_asc(0 .. len);
+            h.assert_valid();
+            assert_eq!((0 .. len).collect::<Vec<u64>>(), h.into_vec_asc());
+        }
+    }
 
-impl<T: Ord + fmt::Debug> fmt::Debug for PeekMaxMut<'_, T> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.debug_tuple("PeekMaxMut")
-         .field(&**self)
-         .finish()
+    #[test]
+    fn from_array() {
+        let heap = MinMaxHeap::from([5, 9, 1, 7]);
+        heap.assert_valid();
+        assert_eq!(vec![1, 5, 7, 9], heap.into_vec_asc());
+
+        let heap = MinMaxHeap::<u8>::from([]);
+        assert!(heap.is_empty());
     }
-}
 
-impl<'a, T: Ord> Drop for PeekMaxMut<'a, T> {
-    fn drop(&mut self) {
-        if self.sift {
-            // SAFETY: `max_index` is a valid index in `heap`
-            let mut hole = unsafe { Hole::new(&mut self.heap.0, self.max_index) };
+    #[test]
+    fn from_collections() {
+        let set: BTreeSet<usize> = random_vec(40).into_iter().collect();
+        let heap = MinMaxHeap::from(set.clone());
+        heap.assert_valid();
+        assert_eq!(set.into_iter().collect::<Vec<_>>(), heap.into_vec_asc());
+
+        // Large enough to be heapified rather than arranged.
+        let set: BTreeSet<u64> = (0 .. 50_000).map(|i| i * 7919 % 50_000).collect();
+        let heap = MinMaxHeap::from(set);
+        heap.assert_valid();
+        assert_eq!((0 .. 50_000).collect::<Vec<_>>(), heap.into_vec_asc());
+
+        let mut deque: VecDeque<usize> = (10 .. 20).collect();
+        deque.extend(0 .. 5);
+        deque.push_front(7);
+        let heap = MinMaxHeap::from(deque);
+        heap.assert_valid();
+        assert_eq!(Some(&0), heap.peek_min());
+        assert_eq!(Some(&19), heap.peek_max());
+        assert_eq!(16, heap.len());
+    }
 
-            if let Some(mut parent) = hole.get_parent() {
-                if parent.hole_element() < parent.other_element() {
-                   parent.swap_with();
-                }
-            }
+    #[test]
+    fn from_btree_map_keys() {
+        use alloc::collections::BTreeMap;
+
+        let map: BTreeMap<usize, usize> =
+            random_vec(40).into_iter().map(|k| (k, 2 * k)).collect();
+        let keys: Vec<usize> = map.keys().copied().collect();
+        let heap = MinMaxHeap::from_sorted_iter_asc(map.into_keys());
+        heap.assert_valid();
+        assert_eq!(keys, heap.into_vec_asc());
+    }
 
-            hole.trickle_down_max();
+    #[test]
+    fn into_vec_and_box() {
+        fn elements<V: Into<Vec<usize>>>(v: V) -> Vec<usize> {
+            let mut vec = v.into();
+            vec.sort_unstable();
+            vec
         }
+
+        let heap = random_heap(30);
+        let layout = heap.clone().into_vec();
+        assert_eq!(layout, Vec::from(heap.clone()));
+        assert_eq!(layout, Box::<[usize]>::from(heap.clone()).into_vec());
+        assert_eq!(heap.clone().into_vec_asc(), elements(heap));
     }
-}
 
-impl<'a, T: Ord> Deref for PeekMaxMut<'a, T> {
-    type Target = T;
-    fn deref(&self) -> &T {
-        debug_assert!(self.max_index < self.heap.len());
-        // SAFE: PeekMaxMut is only instantiated for non-empty heaps
-        unsafe { self.heap.0.get_unchecked(self.max_index) }
+    #[test]
+    fn into_boxed_slice() {
+        let mut heap = random_heap(30);
+        heap.reserve(100);
+        let asc = heap.clone().into_vec_asc();
+
+        assert_eq!(asc, heap.clone().into_boxed_slice_asc().into_vec());
+        let desc = heap.into_boxed_slice_desc().into_vec();
+        assert_eq!(30, desc.capacity());
+        assert!(desc.iter().eq(asc.iter().rev()));
     }
-}
 
-impl<'a, T: Ord> DerefMut for PeekMaxMut<'a, T> {
-    fn deref_mut(&mut self) -> &mut T {
-        debug_assert!(self.max_index < self.heap.len());
-        self.sift = true;
-        // SAFE: PeekMaxMut is only instantiated for non-empty heaps
-        unsafe { self.heap.0.get_unchecked_mut(self.max_index) }
+    #[test]
+    fn multiset_eq() {
+        let a = random_heap(40);
+        let mut reversed = a.clone().into_vec();
+        reversed.reverse();
+        let mut b = MinMaxHeap::from(reversed);
+        assert_ne!(a.clone().into_vec(), b.clone().into_vec());
+        assert_eq!(a, b);
+
+        b.push_pop_min(a.peek_max().unwrap() + 1);
+        assert_ne!(a, b);
+
+        let mut c = a.clone();
+        c.pop_max();
+        assert_ne!(a, c);
+        assert_eq!(MinMaxHeap::<usize>::new(), MinMaxHeap::new());
     }
-}
 
-impl<'a, T: Ord> PeekMaxMut<'a, T> {
-    /// Removes the peeked value from the heap and returns it.
-    pub fn pop(mut self) -> T {
-        // Sift is unnecessary since pop_max() already reorders heap
-        self.sift = false;
-        self.heap.pop_max().unwrap()
+    #[test]
+    fn binary_heap() {
+        let vec = random_vec(50);
+        let ptr = vec.as_ptr();
+
+        let heap = MinMaxHeap::from(BinaryHeap::from(vec));
+        heap.assert_valid();
+        assert_eq!(ptr, heap.0.as_ptr());
+        let sorted = heap.clone().into_vec_asc();
+
+        let binary = BinaryHeap::from(heap);
+        assert_eq!(50, binary.len());
+        assert_eq!(sorted.last(), binary.peek());
+
+        let vec = binary.into_sorted_vec();
+        assert_eq!(ptr, vec.as_ptr());
+        assert_eq!(sorted, vec);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    extern crate rand;
+    #[test]
+    fn extend_panic() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let mut h: MinMaxHeap<u32> = (50 .. 60).collect();
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            h.extend((0 .. 40).rev().map(|i| if i == 10 { panic!() } else { i }));
+        }));
+
+        assert!(result.is_err());
+        h.assert_valid();
+        assert_eq!(Some(&11), h.peek_min());
+        assert_eq!(39, h.len());
+    }
 
-    use super::*;
-    use self::rand::seq::SliceRandom;
+    #[test]
+    fn allocated_bytes() {
+        let base = mem::size_of::<MinMaxHeap<u32>>();
+        let mut h: MinMaxHeap<u32> = MinMaxHeap::new();
+        assert_eq!(base, h.allocated_bytes());
+
+        h.reserve_exact(8);
+        h.extend(0 .. 3);
+        assert_eq!(base + 4 * h.capacity(), h.allocated_bytes());
+        assert_eq!(h.allocated_bytes() + 3, h.allocated_bytes_with(|_| 1));
+
+        let mut h: MinMaxHeap<Vec<u8>> = MinMaxHeap::new();
+        h.push(vec![0; 10]);
+        h.push(Vec::with_capacity(5));
+        assert_eq!(h.allocated_bytes() + 15, h.allocated_bytes_with(Vec::capacity));
+    }
 
     #[test]
-    fn example() {
-        let mut h = MinMaxHeap::new();
-        assert!(h.is_empty());
+    fn shrink_to() {
+        let mut h: MinMaxHeap<u32> = MinMaxHeap::with_capacity(100);
+        h.extend(0 .. 20);
+
+        h.shrink_to(50);
+        assert!((50 .. 100).contains(&h.capacity()));
+        h.shrink_to(0);
+        assert!((20 .. 50).contains(&h.capacity()));
+        assert_eq!((0 .. 20).collect::<Vec<_>>(), h.into_vec_asc());
+    }
 
-        h.push(5);
-        assert!(!h.is_empty());
-        assert_eq!(Some(&5), h.peek_min());
-        assert_eq!(Some(&5), h.peek_max());
+    #[test]
+    fn try_reserve() {
+        let mut h: MinMaxHeap<u32> = MinMaxHeap::new();
+        h.try_reserve_exact(7).unwrap();
+        assert!(h.capacity() >= 7);
+        h.try_reserve(20).unwrap();
+        assert!(h.capacity() >= 20);
+
+        assert!(h.try_reserve(usize::MAX).is_err());
+        assert!(h.try_reserve_exact(usize::MAX / 2).is_err());
+    }
 
-        h.push(7);
-        assert_eq!(Some(&5), h.peek_min());
-        assert_eq!(Some(&7), h.peek_max());
+    #[test]
+    fn push_capped() {
+        let mut h = MinMaxHeap::new();
+        assert_eq!(Some(1), h.push_capped_min(0, 1));
+        assert!(h.is_empty());
 
-        h.push(6);
-        assert_eq!(Some(&5), h.peek_min());
-        assert_eq!(Some(&7), h.peek_max());
+        for i in random_vec(100) {
+            h.push_capped_min(10, i);
+        }
+        assert_eq!((90 .. 100).collect::<Vec<_>>(), h.clone().into_vec_asc());
 
-        assert_eq!(Some(5), h.pop_min());
-        assert_eq!(Some(7), h.pop_max());
-        assert_eq!(Some(6), h.pop_max());
-        assert_eq!(None, h.pop_min());
+        let mut h = MinMaxHeap::new();
+        for i in random_vec(100) {
+            h.push_capped_max(10, i);
+        }
+        assert_eq!((0 .. 10).collect::<Vec<_>>(), h.into_vec_asc());
     }
 
     #[test]
-    fn drain_asc() {
-        let mut h = MinMaxHeap::from(vec![3, 2, 4, 1]);
-        let mut i = h.drain_asc();
-        assert_eq!( i.next(), Some(1) );
-        assert_eq!( i.next(), Some(2) );
-        assert_eq!( i.next(), Some(3) );
-        assert_eq!( i.next(), Some(4) );
-        assert_eq!( i.next(), None );
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "already over its cap")]
+    fn push_capped_over_cap() {
+        let mut h = MinMaxHeap::from(vec![1, 2, 3]);
+        h.push_capped_max(2, 0);
     }
 
-    // This test catches a lot:
     #[test]
-    fn random_vectors() {
-        for i in 0 .. 300 {
-            check_heap(&random_heap(i));
+    fn pop_batch() {
+        for len in 0 .. 40 {
+            for n in 0 .. len + 2 {
+                let k = n.min(len);
+
+                let mut heap = random_heap(len);
+                let mut out = vec![100];
+                assert_eq!(k, heap.pop_min_batch(&mut out, n));
+                assert_eq!(Some(&100), out.first());
+                assert_eq!((0 .. k).collect::<Vec<_>>(), out[1 ..]);
+                assert_eq!((k .. len).collect::<Vec<_>>(), heap.into_vec_asc());
+
+                let mut heap = random_heap(len);
+                let mut out = Vec::new();
+                assert_eq!(k, heap.pop_max_batch(&mut out, n));
+                assert_eq!((len - k .. len).rev().collect::<Vec<_>>(), out);
+                assert_eq!((0 .. len - k).collect::<Vec<_>>(), heap.into_vec_asc());
+            }
         }
     }
 
     #[test]
-    fn from_vector() {
-        for i in 0 .. 300 {
-            check_heap(&MinMaxHeap::from(random_vec(i)))
+    fn into_k_smallest_largest() {
+        for len in 0 .. 40 {
+            for k in 0 .. len + 2 {
+                let heap = random_heap(len);
+                let k_len = k.min(len);
+                assert_eq!((0 .. k_len).collect::<Vec<_>>(),
+                           heap.clone().into_k_smallest(k));
+                assert_eq!((len - k_len .. len).rev().collect::<Vec<_>>(),
+                           heap.into_k_largest(k));
+            }
         }
     }
 
-    fn check_heap(heap: &MinMaxHeap<usize>) {
-        let asc  = iota_asc(heap.len());
-        let desc = iota_desc(heap.len());
-
-        assert_eq!(asc, into_vec_asc(heap.clone()));
-        assert_eq!(desc, into_vec_desc(heap.clone()));
-        assert_eq!(asc, heap.clone().into_vec_asc());
-        assert_eq!(desc, heap.clone().into_vec_desc());
+    #[test]
+    fn peek_k_min_max() {
+        for len in 0 .. 40 {
+            let heap = random_heap(len);
+            for k in 0 .. len + 2 {
+                let k_len = k.min(len);
+                assert_eq!((0 .. k_len).collect::<Vec<_>>(),
+                           heap.peek_k_min(k).into_iter().cloned().collect::<Vec<_>>());
+                assert_eq!((len - k_len .. len).rev().collect::<Vec<_>>(),
+                           heap.peek_k_max(k).into_iter().cloned().collect::<Vec<_>>());
+            }
+        }
     }
 
-    fn random_vec(len: usize) -> Vec<usize> {
-        let mut result = (0 .. len).collect::<Vec<_>>();
-        result.shuffle(&mut rand::thread_rng());
-        result
+    #[test]
+    fn peek_kth_min_max() {
+        for len in 0 .. 40 {
+            let heap = random_heap(len);
+            for k in 0 .. len {
+                assert_eq!(Some(&k), heap.peek_kth_min(k));
+                assert_eq!(Some(&(len - 1 - k)), heap.peek_kth_max(k));
+            }
+            assert_eq!(None, heap.peek_kth_min(len));
+            assert_eq!(None, heap.peek_kth_max(len));
+        }
     }
 
-    fn random_heap(len: usize) -> MinMaxHeap<usize> {
-        MinMaxHeap::from_iter(random_vec(len))
-    }
+    #[test]
+    fn select_nth_min_max() {
+        for len in 0 .. 40 {
+            for k in 0 .. len {
+                let mut heap = random_heap(len);
+                assert_eq!(Some(&k), heap.select_nth_min(k));
+                check_heap(&heap);
+                assert_eq!(Some(&(len - 1 - k)), heap.select_nth_max(k));
+                check_heap(&heap);
+            }
 
-    fn into_vec_asc(mut heap: MinMaxHeap<usize>) -> Vec<usize> {
-        let mut result = Vec::with_capacity(heap.len());
-        while let Some(elem) = heap.pop_min() {
-            result.push(elem)
+            let mut heap = random_heap(len);
+            assert_eq!(None, heap.select_nth_min(len));
+            assert_eq!(None, heap.select_nth_max(len));
         }
-        result
     }
 
-    fn into_vec_desc(mut heap: MinMaxHeap<usize>) -> Vec<usize> {
-        let mut result = Vec::with_capacity(heap.len());
-        while let Some(elem) = heap.pop_max() {
-            result.push(elem)
+    #[test]
+    fn iter_asc_desc() {
+        for len in 0 .. 100 {
+            let heap = random_heap(len);
+
+            let mut iter = heap.iter_asc();
+            assert_eq!(len, iter.len());
+            assert_eq!(iota_asc(len), iter.by_ref().cloned().collect::<Vec<_>>());
+            assert_eq!(0, iter.len());
+
+            assert_eq!(iota_desc(len), heap.iter_desc().cloned().collect::<Vec<_>>());
         }
-        result
     }
 
-    fn iota_asc(len: usize) -> Vec<usize> {
-        (0 .. len).collect()
+    #[test]
+    fn replace_min_max_with() {
+        let mut h = MinMaxHeap::<usize>::new();
+        assert!(!h.replace_min_with(|_| unreachable!()));
+        assert!(!h.replace_max_with(|_| unreachable!()));
+
+        for len in 1 .. 40 {
+            let mut h = random_heap(len);
+            assert!(h.replace_min_with(|min| min + len));
+            let mut expected: Vec<_> = (1 .. len + 1).collect();
+            expected.sort();
+            assert_eq!(expected, h.clone().into_vec_asc());
+
+            let mut h = random_heap(len);
+            assert!(h.replace_max_with(|max| max + 1 - len));
+            let mut expected: Vec<_> = (0 .. len - 1).collect();
+            expected.push(0);
+            expected.sort();
+            assert_eq!(expected, h.into_vec_asc());
+        }
     }
 
-    fn iota_desc(len: usize) -> Vec<usize> {
-        let mut result = (0 .. len).collect::<Vec<_>>();
-        result.reverse();
-        result
+    #[test]
+    fn replace_with_panic() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        for len in 1 .. 20 {
+            let mut h = random_heap(len);
+            let result = catch_unwind(AssertUnwindSafe(|| {
+                h.replace_max_with(|_| panic!("oops"))
+            }));
+            assert!(result.is_err());
+            assert_eq!(iota_asc(len - 1), h.into_vec_asc());
+
+            let mut h = random_heap(len);
+            let result = catch_unwind(AssertUnwindSafe(|| {
+                h.replace_min_with(|_| panic!("oops"))
+            }));
+            assert!(result.is_err());
+            assert_eq!((1 .. len).collect::<Vec<_>>(), h.into_vec_asc());
+        }
     }
 
     #[test]
-    fn replace_min() {
-        let mut h = MinMaxHeap::from(vec![1, 2]);
-        assert_eq!(Some(1), h.replace_min(0));
-        assert_eq!(Some(&0), h.peek_min());
-        assert_eq!(Some(&2), h.peek_max());
+    fn get_mut_by() {
+        for len in 1 .. 40 {
+            for target in 0 .. len {
+                for &new in &[0, target / 2, target + len / 2, 2 * len] {
+                    let mut h = random_heap(len);
+                    *h.get_mut_by(|&x| x == target).unwrap() = new;
+
+                    let mut expected = iota_asc(len);
+                    expected[target] = new;
+                    expected.sort();
+                    assert_eq!(expected, h.into_vec_asc());
+                }
+            }
 
-        assert_eq!(Some(0), h.replace_min(3));
-        assert_eq!(Some(&2), h.peek_min());
-        assert_eq!(Some(&3), h.peek_max());
+            let mut h = random_heap(len);
+            assert!(h.get_mut_by(|&x| x == len).is_none());
+        }
     }
 
     #[test]
-    fn replace_min_edge_cases() {
-        let mut empty_heap = MinMaxHeap::new();
-        assert_eq!(None, empty_heap.replace_min(1));
-        assert_eq!(Some(1), empty_heap.pop_min());
-        assert_eq!(None, empty_heap.pop_min());
-
-        let mut one_element_heap = MinMaxHeap::from(vec![2]);
-        assert_eq!(Some(2), one_element_heap.replace_min(1));
-        assert_eq!(Some(1), one_element_heap.pop_min());
-        assert_eq!(None, one_element_heap.pop_min());
+    fn swap_remove_index() {
+        for len in 1 .. 40 {
+            for target in 0 .. len {
+                let mut h = random_heap(len);
+                let index = h.iter().position(|&x| x == target).unwrap();
+                assert_eq!(target, h.swap_remove_index(index));
+
+                let mut expected = iota_asc(len);
+                expected.remove(target);
+                assert_eq!(expected, h.clone().into_vec_asc());
+
+                let mut h = random_heap(len);
+                assert_eq!(target, h.get_mut_by(|&x| x == target).unwrap().pop());
+                assert_eq!(expected, h.into_vec_asc());
+            }
+        }
     }
 
     #[test]
-    fn replace_max() {
-        let mut h = MinMaxHeap::from(vec![1, 2]);
-        assert_eq!(Some(2), h.replace_max(3));
-        assert_eq!(Some(&1), h.peek_min());
-        assert_eq!(Some(&3), h.peek_max());
-
-        assert_eq!(Some(3), h.replace_max(0));
-        assert_eq!(Some(&0), h.peek_min());
-        assert_eq!(Some(&1), h.peek_max());
+    #[should_panic]
+    fn swap_remove_index_out_of_bounds() {
+        MinMaxHeap::from(vec![1, 2, 3]).swap_remove_index(3);
     }
 
     #[test]
-    fn replace_max_edge_cases() {
-        let mut empty_heap = MinMaxHeap::new();
-        assert_eq!(None, empty_heap.replace_max(1));
-        assert_eq!(Some(1), empty_heap.pop_max());
-        assert_eq!(None, empty_heap.pop_max());
+    fn dedup() {
+        let mut rng = rand::thread_rng();
+        for len in 0 .. 100 {
+            let vec: Vec<usize> = (0 .. len).map(|_| rng.gen_range(0 .. 20)).collect();
+            let mut h = MinMaxHeap::from(vec.clone());
+            h.dedup();
+
+            let mut expected = vec;
+            expected.sort();
+            expected.dedup();
+            assert_eq!(expected, h.into_vec_asc());
+        }
+    }
 
-        let mut one_element_heap = MinMaxHeap::from(vec![1]);
-        assert_eq!(Some(1), one_element_heap.replace_max(2));
-        assert_eq!(Some(2), one_element_heap.pop_max());
-        assert_eq!(None, one_element_heap.pop_max());
+    fn random_multiset(len: usize) -> Vec<usize> {
+        let mut rng = rand::thread_rng();
+        (0 .. len).map(|_| rng.gen_range(0 .. 8)).collect()
+    }
+
+    fn multiplicities(vec: &[usize]) -> Vec<usize> {
+        let mut result = vec![0; 8];
+        for &x in vec {
+            result[x] += 1;
+        }
+        result
     }
 
     #[test]
-    fn peek_min_mut() {
-        let mut h = MinMaxHeap::from(vec![2, 3, 4]);
-        *h.peek_min_mut().unwrap() = 1;
-        assert_eq!(Some(&1), h.peek_min());
-        assert_eq!(Some(&4), h.peek_max());
+    fn difference_intersection() {
+        for len in 0 .. 40 {
+            let a = random_multiset(len);
+            let b = random_multiset(len / 2 + 3);
+            let (ha, hb) = (MinMaxHeap::from(a.clone()), MinMaxHeap::from(b.clone()));
+            let (ma, mb) = (multiplicities(&a), multiplicities(&b));
+
+            let difference = ha.difference(&hb).into_vec();
+            let intersection = ha.intersection(&hb).into_vec();
+            for x in 0 .. 8 {
+                assert_eq!(ma[x].saturating_sub(mb[x]), multiplicities(&difference)[x]);
+                assert_eq!(ma[x].min(mb[x]), multiplicities(&intersection)[x]);
+            }
+        }
+    }
 
-        *h.peek_min_mut().unwrap() = 8;
-        assert_eq!(Some(&3), h.peek_min());
-        assert_eq!(Some(&8), h.peek_max());
+    #[test]
+    fn multiset_relations() {
+        for len in 0 .. 40 {
+            let a = random_multiset(len);
+            let b = random_multiset(len / 2 + 3);
+            let (ha, hb) = (MinMaxHeap::from(a.clone()), MinMaxHeap::from(b.clone()));
+            let (ma, mb) = (multiplicities(&a), multiplicities(&b));
+
+            let subset = (0 .. 8).all(|x| ma[x] <= mb[x]);
+            let superset = (0 .. 8).all(|x| ma[x] >= mb[x]);
+            let disjoint = (0 .. 8).all(|x| ma[x] == 0 || mb[x] == 0);
+
+            assert_eq!(subset, ha.is_subset(&hb));
+            assert_eq!(superset, ha.is_superset(&hb));
+            assert_eq!(disjoint, ha.is_disjoint(&hb));
+            assert!(ha.is_subset(&ha));
+        }
+    }
 
-        assert_eq!(3, h.peek_min_mut().unwrap().pop());
-        assert_eq!(Some(&4), h.peek_min());
-        assert_eq!(Some(&8), h.peek_max());
+    #[test]
+    fn map() {
+        for len in 0 .. 40 {
+            let h = random_heap(len).map(|x| (len - x) as isize * -2);
+            let expected: Vec<_> = (1 .. len + 1).rev().map(|x| x as isize * -2).collect();
+            assert_eq!(expected, h.into_vec_asc());
+        }
     }
 
     #[test]
-    fn peek_max_mut() {
-        let mut h = MinMaxHeap::from(vec![1, 2]);
-        *h.peek_max_mut().unwrap() = 3;
-        assert_eq!(Some(&1), h.peek_min());
-        assert_eq!(Some(&3), h.peek_max());
+    fn modify_in_place() {
+        for len in 0 .. 40 {
+            let mut h = random_heap(len);
+            h.modify_in_place(|x| *x = (*x + 7) % len.max(1));
+            assert_eq!(iota_asc(len), h.into_vec_asc());
+        }
+    }
 
-        *h.peek_max_mut().unwrap() = 0;
-        assert_eq!(Some(&0), h.peek_min());
-        assert_eq!(Some(&1), h.peek_max());
+    #[test]
+    fn split_at_median() {
+        for len in 0 .. 40 {
+            let (lower, upper) = random_heap(len).split_at_median();
+            assert_eq!(iota_asc(len / 2), lower.into_vec_asc());
+            assert_eq!((len / 2 .. len).collect::<Vec<_>>(), upper.into_vec_asc());
+        }
+    }
 
-        assert_eq!(1, h.peek_max_mut().unwrap().pop());
-        assert_eq!(Some(&0), h.peek_min());
-        assert_eq!(Some(&0), h.peek_max());
+    #[test]
+    fn levels() {
+        for len in 0 .. 40 {
+            let heap = random_heap(len);
+            let levels = heap.levels();
+            let count = levels.len();
+
+            let mut pos = 0;
+            for (depth, level) in levels.enumerate() {
+                assert_eq!(depth % 2 == 0, level.is_min());
+                assert_eq!(depth % 2 == 1, level.is_max());
+                for element in level.elements() {
+                    assert_eq!(&heap.0[pos], element);
+                    assert_eq!(pos.is_min_level(), level.is_min());
+                    pos += 1;
+                }
+            }
 
-        *h.peek_max_mut().unwrap() = 1;
-        assert_eq!(Some(&1), h.peek_min());
-        assert_eq!(Some(&1), h.peek_max());
+            assert_eq!(len, pos);
+            assert_eq!(count, heap.levels().count());
+        }
     }
 
     #[test]
-    fn peek_max_mut_one() {
-        let mut h = MinMaxHeap::from(vec![1]);
-        {
-            let mut max = h.peek_max_mut().unwrap();
-            assert_eq!(*max, 1);
-            *max = 2;
+    fn is_valid() {
+        for len in 0 .. 40 {
+            let heap = random_heap(len);
+            assert!(heap.is_valid());
+            heap.assert_valid();
         }
-        assert_eq!(h.peek_max(), Some(&2));
+
+        // Root larger than a max-level child:
+        let heap = MinMaxHeap(vec![5, 4, 6], Natural);
+        assert!(!heap.is_valid());
+        assert_eq!(Some((1, 0)), heap.find_violation());
+
+        // Min-level grandchild smaller than the root:
+        let heap = MinMaxHeap(vec![1, 5, 6, 0], Natural);
+        assert!(!heap.is_valid());
+        assert_eq!(Some((3, 0)), heap.find_violation());
+
+        // Min-level grandchild larger than its max-level parent:
+        let heap = MinMaxHeap(vec![1, 5, 6, 7], Natural);
+        assert_eq!(Some((3, 1)), heap.find_violation());
     }
 
     #[test]
-    fn push_pop_max() {
-        let mut h = MinMaxHeap::from(vec![1, 2]);
-        assert_eq!(3, h.push_pop_max(3));
-        assert_eq!(2, h.push_pop_max(0));
-        assert_eq!(Some(&0), h.peek_min());
-        assert_eq!(Some(&1), h.peek_max());
+    #[should_panic(expected = "element at position 3 is less than its ancestor at position 0")]
+    fn assert_valid_panics() {
+        MinMaxHeap(vec![1, 5, 6, 0], Natural).assert_valid();
     }
 
     #[test]
@@ -1084,5 +5155,71 @@ mod tests {
         let mut h = MinMaxHeap::from(vec![1, 2, 3]);
         assert_eq!("PeekMinMut(1)", format!("{:?}", h.peek_min_mut().unwrap()));
         assert_eq!("PeekMaxMut(3)", format!("{:?}", h.peek_max_mut().unwrap()));
+        assert_eq!("ElementMut(2)", format!("{:?}", h.get_mut_by(|&x| x == 2).unwrap()));
+    }
+
+    #[cfg(feature = "paranoid")]
+    #[test]
+    #[should_panic(expected = "element at position 3 is less than its ancestor at position 0")]
+    fn paranoid_check() {
+        let mut heap = MinMaxHeap(vec![1, 5, 6, 0], Natural);
+        heap.push(7);
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn tracing_spans() {
+        use std::sync::{Arc, Mutex};
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata, Subscriber};
+
+        /// Records the name and `len` of every span. This has to be the
+        /// global subscriber, since `tracing` is built without `std`, so
+        /// it also sees spans from other tests.
+        struct Recorder(Arc<Mutex<Vec<(&'static str, u64)>>>);
+
+        struct Len(u64);
+
+        impl Visit for Len {
+            fn record_u64(&mut self, field: &Field, value: u64) {
+                if field.name() == "len" {
+                    self.0 = value;
+                }
+            }
+
+            fn record_debug(&mut self, _: &Field, _: &dyn fmt::Debug) { }
+        }
+
+        impl Subscriber for Recorder {
+            fn enabled(&self, _: &Metadata<'_>) -> bool { true }
+
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                let mut len = Len(u64::MAX);
+                span.record(&mut len);
+                let mut spans = self.0.lock().unwrap();
+                spans.push((span.metadata().name(), len.0));
+                Id::from_u64(spans.len() as u64)
+            }
+
+            fn record(&self, _: &Id, _: &Record<'_>) { }
+            fn record_follows_from(&self, _: &Id, _: &Id) { }
+            fn event(&self, _: &Event<'_>) { }
+            fn enter(&self, _: &Id) { }
+            fn exit(&self, _: &Id) { }
+        }
+
+        let spans = Arc::new(Mutex::new(Vec::new()));
+        tracing::subscriber::set_global_default(Recorder(spans.clone())).unwrap();
+
+        let mut heap = MinMaxHeap::from(vec![10_001, 10_000, 10_002]);
+        heap.push(10_003);
+        heap.pop_min();
+        heap.pop_max();
+
+        let spans = spans.lock().unwrap();
+        for &span in &[("rebuild", 3), ("push", 3), ("pop_min", 4), ("pop_max", 3)] {
+            assert!(spans.contains(&span), "missing span {:?}", span);
+        }
     }
 }
@@ -0,0 +1,265 @@
+use alloc::vec::Vec;
+use core::iter::FromIterator;
+
+use super::MinMaxHeap;
+
+/// A priority queue that tracks its median.
+///
+/// The elements are kept in two min-max-heaps, one holding the smaller
+/// half and one the larger half, so the median sits at the inner end of
+/// one of them. Because both halves are double-ended, the minimum and
+/// maximum are also available.
+///
+/// When the number of elements is even, the *median* is the smaller of
+/// the two middle elements.
+///
+/// # Examples
+///
+/// ```
+/// use min_max_heap::MedianHeap;
+///
+/// let mut heap = MedianHeap::new();
+/// for x in vec![5, 1, 8, 3, 9] {
+///     heap.push(x);
+/// }
+///
+/// assert_eq!(heap.peek_median(), Some(&5));
+/// assert_eq!(heap.pop_max(), Some(9));
+/// assert_eq!(heap.peek_medians(), Some((&3, &5)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct MedianHeap<T> {
+    // Invariant: every element of `lower` is at most every element of
+    // `upper`, and `upper` has as many elements as `lower` or one more.
+    lower: MinMaxHeap<T>,
+    upper: MinMaxHeap<T>,
+}
+
+impl<T> Default for MedianHeap<T> {
+    fn default() -> Self {
+        MedianHeap::new()
+    }
+}
+
+impl<T> MedianHeap<T> {
+    /// Creates a new, empty `MedianHeap`.
+    ///
+    /// *O*(1).
+    pub fn new() -> Self {
+        MedianHeap {
+            lower: MinMaxHeap::new(),
+            upper: MinMaxHeap::new(),
+        }
+    }
+
+    /// The number of elements in the heap.
+    ///
+    /// *O*(1).
+    pub fn len(&self) -> usize {
+        self.lower.len() + self.upper.len()
+    }
+
+    /// Is the heap empty?
+    ///
+    /// *O*(1).
+    pub fn is_empty(&self) -> bool {
+        self.upper.is_empty()
+    }
+
+    /// Drops all items from the heap.
+    ///
+    /// *O*(*n*).
+    pub fn clear(&mut self) {
+        self.lower.clear();
+        self.upper.clear();
+    }
+}
+
+impl<T: Ord> MedianHeap<T> {
+    /// Adds an element to the heap.
+    ///
+    /// *O*(log *n*).
+    pub fn push(&mut self, element: T) {
+        match self.upper.peek_min() {
+            Some(min) if element >= *min => self.upper.push(element),
+            _ => self.lower.push(element),
+        }
+
+        self.rebalance();
+    }
+
+    /// Gets a reference to the median element, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_median(&self) -> Option<&T> {
+        if self.upper.len() > self.lower.len() {
+            self.upper.peek_min()
+        } else {
+            self.lower.peek_max()
+        }
+    }
+
+    /// Gets references to the two middle elements, if any.
+    ///
+    /// If the number of elements is odd, both references are to the
+    /// median.
+    ///
+    /// *O*(1).
+    pub fn peek_medians(&self) -> Option<(&T, &T)> {
+        let upper = self.upper.peek_min()?;
+        if self.upper.len() > self.lower.len() {
+            Some((upper, upper))
+        } else {
+            self.lower.peek_max().map(|lower| (lower, upper))
+        }
+    }
+
+    /// Removes the median element, if any.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_median(&mut self) -> Option<T> {
+        let result = if self.upper.len() > self.lower.len() {
+            self.upper.pop_min()
+        } else {
+            self.lower.pop_max()
+        };
+
+        self.rebalance();
+        result
+    }
+
+    /// Gets a reference to the minimum element, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_min(&self) -> Option<&T> {
+        self.lower.peek_min().or_else(|| self.upper.peek_min())
+    }
+
+    /// Gets a reference to the maximum element, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_max(&self) -> Option<&T> {
+        self.upper.peek_max()
+    }
+
+    /// Removes the minimum element, if any.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_min(&mut self) -> Option<T> {
+        let result = if self.lower.is_empty() {
+            self.upper.pop_min()
+        } else {
+            self.lower.pop_min()
+        };
+
+        self.rebalance();
+        result
+    }
+
+    /// Removes the maximum element, if any.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_max(&mut self) -> Option<T> {
+        let result = self.upper.pop_max();
+        self.rebalance();
+        result
+    }
+
+    /// Returns an ascending (sorted) vector.
+    ///
+    /// *O*(*n* log *n*).
+    pub fn into_vec_asc(self) -> Vec<T> {
+        let mut result = self.lower.into_vec_asc();
+        result.extend(self.upper.into_vec_asc());
+        result
+    }
+
+    fn rebalance(&mut self) {
+        if self.upper.len() > self.lower.len() + 1 {
+            let element = self.upper.pop_min().unwrap();
+            self.lower.push(element);
+        } else if self.lower.len() > self.upper.len() {
+            let element = self.lower.pop_max().unwrap();
+            self.upper.push(element);
+        }
+    }
+}
+
+impl<T: Ord> From<MinMaxHeap<T>> for MedianHeap<T> {
+    fn from(heap: MinMaxHeap<T>) -> Self {
+        let (lower, upper) = heap.split_at_median();
+        MedianHeap { lower, upper }
+    }
+}
+
+impl<T: Ord> FromIterator<T> for MedianHeap<T> {
+    fn from_iter<I>(iter: I) -> Self
+            where I: IntoIterator<Item = T> {
+        MedianHeap::from(MinMaxHeap::from_iter(iter))
+    }
+}
+
+impl<T: Ord> Extend<T> for MedianHeap<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for element in iter {
+            self.push(element);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn push_and_pop_median() {
+        let mut heap = MedianHeap::new();
+        assert_eq!(None, heap.peek_median());
+        assert_eq!(None, heap.peek_medians());
+
+        let mut sorted = Vec::new();
+        for &x in &[5, 3, 9, 1, 1, 7, 4, 8, 2, 6] {
+            heap.push(x);
+            sorted.push(x);
+            sorted.sort();
+
+            let mid = (sorted.len() - 1) / 2;
+            assert_eq!(Some(&sorted[mid]), heap.peek_median());
+            assert_eq!(Some((&sorted[mid], &sorted[sorted.len() / 2])),
+                       heap.peek_medians());
+            assert_eq!(sorted.first(), heap.peek_min());
+            assert_eq!(sorted.last(), heap.peek_max());
+        }
+
+        while !sorted.is_empty() {
+            let mid = (sorted.len() - 1) / 2;
+            assert_eq!(Some(sorted.remove(mid)), heap.pop_median());
+            assert_eq!(sorted.len(), heap.len());
+        }
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn pop_min_max() {
+        let mut heap: MedianHeap<_> = (0 .. 10).collect();
+        assert_eq!(Some(0), heap.pop_min());
+        assert_eq!(Some(9), heap.pop_max());
+        assert_eq!(Some(8), heap.pop_max());
+        assert_eq!(Some(&4), heap.peek_median());
+        assert_eq!(Some(1), heap.pop_min());
+        assert_eq!(Some(&4), heap.peek_median());
+        assert_eq!(vec![2, 3, 4, 5, 6, 7], heap.into_vec_asc());
+    }
+
+    #[test]
+    fn from_heap() {
+        for len in 0 .. 20 {
+            let heap = MedianHeap::from(MinMaxHeap::from_iter((0 .. len).rev()));
+            assert_eq!(len, heap.len());
+            if len > 0 {
+                assert_eq!(Some(&((len - 1) / 2)), heap.peek_median());
+            }
+            assert_eq!((0 .. len).collect::<Vec<_>>(), heap.into_vec_asc());
+        }
+    }
+}
@@ -0,0 +1,357 @@
+use alloc::vec::Vec;
+use core::cmp;
+use core::fmt;
+use core::iter::FromIterator;
+use alloc::sync::Arc;
+
+/// An immutable double-ended priority queue whose versions share
+/// structure.
+///
+/// Pushing or popping returns a new heap and leaves the old one intact,
+/// copying only the *O*(log *n*) nodes on one path and sharing the rest,
+/// so keeping every version around (for undo, or for backtracking
+/// search) costs *O*(log *n*) space per operation instead of the *O*(*n*)
+/// of cloning a `MinMaxHeap`. Cloning a `PersistentMinMaxHeap` is *O*(1).
+///
+/// Internally, this is a balanced (AVL) binary search tree with shared,
+/// reference-counted nodes. Since nodes on a copied path are rebuilt
+/// rather than moved, elements must be `Clone`.
+///
+/// # Examples
+///
+/// ```
+/// use min_max_heap::PersistentMinMaxHeap;
+///
+/// let empty = PersistentMinMaxHeap::new();
+/// let one = empty.push(5);
+/// let two = one.push(2).push(8);
+///
+/// let (max, rest) = two.pop_max().unwrap();
+/// assert_eq!(max, 8);
+/// assert_eq!(rest.peek_max(), Some(&5));
+///
+/// // Older versions are unaffected.
+/// assert_eq!(two.len(), 3);
+/// assert_eq!(one.peek_min(), Some(&5));
+/// assert!(empty.is_empty());
+/// ```
+pub struct PersistentMinMaxHeap<T> {
+    root: Link<T>,
+}
+
+type Link<T> = Option<Arc<Node<T>>>;
+
+struct Node<T> {
+    element: T,
+    left:    Link<T>,
+    right:   Link<T>,
+    height:  u8,
+    len:     usize,
+}
+
+fn height<T>(link: &Link<T>) -> u8 {
+    link.as_ref().map_or(0, |node| node.height)
+}
+
+fn len<T>(link: &Link<T>) -> usize {
+    link.as_ref().map_or(0, |node| node.len)
+}
+
+fn node<T>(element: T, left: Link<T>, right: Link<T>) -> Arc<Node<T>> {
+    Arc::new(Node {
+        height: 1 + cmp::max(height(&left), height(&right)),
+        len:    1 + len(&left) + len(&right),
+        element,
+        left,
+        right,
+    })
+}
+
+/// Builds a node whose subtrees differ in height by at most 2, rotating
+/// to restore the AVL balance.
+fn balance<T: Clone>(element: T, left: Link<T>, right: Link<T>) -> Arc<Node<T>> {
+    let (hl, hr) = (height(&left), height(&right));
+
+    if hl > hr + 1 {
+        let l = left.unwrap();
+        if height(&l.left) >= height(&l.right) {
+            node(l.element.clone(),
+                 l.left.clone(),
+                 Some(node(element, l.right.clone(), right)))
+        } else {
+            let lr = l.right.as_ref().unwrap();
+            node(lr.element.clone(),
+                 Some(node(l.element.clone(), l.left.clone(), lr.left.clone())),
+                 Some(node(element, lr.right.clone(), right)))
+        }
+    } else if hr > hl + 1 {
+        let r = right.unwrap();
+        if height(&r.right) >= height(&r.left) {
+            node(r.element.clone(),
+                 Some(node(element, left, r.left.clone())),
+                 r.right.clone())
+        } else {
+            let rl = r.left.as_ref().unwrap();
+            node(rl.element.clone(),
+                 Some(node(element, left, rl.left.clone())),
+                 Some(node(r.element.clone(), rl.right.clone(), r.right.clone())))
+        }
+    } else {
+        node(element, left, right)
+    }
+}
+
+fn insert<T: Ord + Clone>(link: &Link<T>, element: T) -> Arc<Node<T>> {
+    match link {
+        None => node(element, None, None),
+        Some(n) if element < n.element =>
+            balance(n.element.clone(), Some(insert(&n.left, element)), n.right.clone()),
+        Some(n) =>
+            balance(n.element.clone(), n.left.clone(), Some(insert(&n.right, element))),
+    }
+}
+
+fn remove_min<T: Clone>(n: &Node<T>) -> (T, Link<T>) {
+    match &n.left {
+        None       => (n.element.clone(), n.right.clone()),
+        Some(left) => {
+            let (min, left) = remove_min(left);
+            (min, Some(balance(n.element.clone(), left, n.right.clone())))
+        }
+    }
+}
+
+fn remove_max<T: Clone>(n: &Node<T>) -> (T, Link<T>) {
+    match &n.right {
+        None        => (n.element.clone(), n.left.clone()),
+        Some(right) => {
+            let (max, right) = remove_max(right);
+            (max, Some(balance(n.element.clone(), n.left.clone(), right)))
+        }
+    }
+}
+
+impl<T> Clone for PersistentMinMaxHeap<T> {
+    fn clone(&self) -> Self {
+        PersistentMinMaxHeap { root: self.root.clone() }
+    }
+}
+
+impl<T> Default for PersistentMinMaxHeap<T> {
+    fn default() -> Self {
+        PersistentMinMaxHeap::new()
+    }
+}
+
+impl<T> PersistentMinMaxHeap<T> {
+    /// Creates a new, empty `PersistentMinMaxHeap`.
+    ///
+    /// *O*(1).
+    pub fn new() -> Self {
+        PersistentMinMaxHeap { root: None }
+    }
+
+    /// The number of elements in the heap.
+    ///
+    /// *O*(1).
+    pub fn len(&self) -> usize {
+        len(&self.root)
+    }
+
+    /// Is the heap empty?
+    ///
+    /// *O*(1).
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Gets a reference to the minimum element, if any.
+    ///
+    /// *O*(log *n*).
+    pub fn peek_min(&self) -> Option<&T> {
+        let mut node = self.root.as_ref()?;
+        while let Some(left) = &node.left {
+            node = left;
+        }
+        Some(&node.element)
+    }
+
+    /// Gets a reference to the maximum element, if any.
+    ///
+    /// *O*(log *n*).
+    pub fn peek_max(&self) -> Option<&T> {
+        let mut node = self.root.as_ref()?;
+        while let Some(right) = &node.right {
+            node = right;
+        }
+        Some(&node.element)
+    }
+
+    /// Returns an iterator over the elements in ascending order.
+    ///
+    /// *O*(1) on creation, and amortized *O*(1) for each `next()`
+    /// operation.
+    pub fn iter_asc(&self) -> PersistentIterAsc<'_, T> {
+        let mut iter = PersistentIterAsc {
+            stack: Vec::new(),
+            len:   self.len(),
+        };
+        iter.push_left(&self.root);
+        iter
+    }
+}
+
+impl<T: Ord + Clone> PersistentMinMaxHeap<T> {
+    /// Returns a new heap with `element` added.
+    ///
+    /// *O*(log *n*).
+    pub fn push(&self, element: T) -> Self {
+        PersistentMinMaxHeap { root: Some(insert(&self.root, element)) }
+    }
+
+    /// Returns the minimum element and a new heap without it, or `None`
+    /// if the heap is empty.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_min(&self) -> Option<(T, Self)> {
+        let (min, root) = remove_min(self.root.as_ref()?);
+        Some((min, PersistentMinMaxHeap { root }))
+    }
+
+    /// Returns the maximum element and a new heap without it, or `None`
+    /// if the heap is empty.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_max(&self) -> Option<(T, Self)> {
+        let (max, root) = remove_max(self.root.as_ref()?);
+        Some((max, PersistentMinMaxHeap { root }))
+    }
+}
+
+impl<T: Ord + Clone> FromIterator<T> for PersistentMinMaxHeap<T> {
+    fn from_iter<I>(iter: I) -> Self
+            where I: IntoIterator<Item = T> {
+        iter.into_iter().fold(PersistentMinMaxHeap::new(), |heap, element| heap.push(element))
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for PersistentMinMaxHeap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PersistentMinMaxHeap")
+         .field(&self.iter_asc().collect::<Vec<_>>())
+         .finish()
+    }
+}
+
+/// An iterator over the elements of a [`PersistentMinMaxHeap`] in
+/// ascending order.
+///
+/// This type is created with
+/// [`PersistentMinMaxHeap::iter_asc`](struct.PersistentMinMaxHeap.html#method.iter_asc).
+///
+/// [`PersistentMinMaxHeap`]: struct.PersistentMinMaxHeap.html
+#[derive(Debug)]
+pub struct PersistentIterAsc<'a, T: 'a> {
+    stack: Vec<&'a Node<T>>,
+    len:   usize,
+}
+
+impl<'a, T> PersistentIterAsc<'a, T> {
+    fn push_left(&mut self, mut link: &'a Link<T>) {
+        while let Some(node) = link {
+            self.stack.push(node);
+            link = &node.left;
+        }
+    }
+}
+
+impl<'a, T> Iterator for PersistentIterAsc<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.push_left(&node.right);
+        self.len -= 1;
+        Some(&node.element)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for PersistentIterAsc<'a, T> { }
+
+impl<T: fmt::Debug> fmt::Debug for Node<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.element.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::test_model::{self, ModelHeap};
+
+    fn check<T: Ord>(link: &Link<T>) -> (u8, usize) {
+        match link {
+            None    => (0, 0),
+            Some(n) => {
+                let (hl, ll) = check(&n.left);
+                let (hr, lr) = check(&n.right);
+                assert!((hl as i32 - hr as i32).abs() <= 1);
+                assert_eq!(n.height, 1 + cmp::max(hl, hr));
+                assert_eq!(n.len, 1 + ll + lr);
+                if let Some(left) = &n.left { assert!(left.element <= n.element); }
+                if let Some(right) = &n.right { assert!(right.element >= n.element); }
+                (n.height, n.len)
+            }
+        }
+    }
+
+    impl ModelHeap for PersistentMinMaxHeap<u32> {
+        fn push(&mut self, element: u32) { *self = PersistentMinMaxHeap::push(self, element) }
+        fn peek_min(&self) -> Option<u32> { PersistentMinMaxHeap::peek_min(self).copied() }
+        fn peek_max(&self) -> Option<u32> { PersistentMinMaxHeap::peek_max(self).copied() }
+        fn len(&self) -> usize { PersistentMinMaxHeap::len(self) }
+
+        fn pop_min(&mut self) -> Option<u32> {
+            let (min, rest) = PersistentMinMaxHeap::pop_min(self)?;
+            *self = rest;
+            Some(min)
+        }
+
+        fn pop_max(&mut self) -> Option<u32> {
+            let (max, rest) = PersistentMinMaxHeap::pop_max(self)?;
+            *self = rest;
+            Some(max)
+        }
+    }
+
+    #[test]
+    fn random() {
+        let mut heap = PersistentMinMaxHeap::new();
+        let mut versions = Vec::new();
+        test_model::random(&mut heap, 2000, usize::MAX, |heap| {
+            check(&heap.root);
+            versions.push((heap.clone(), heap.iter_asc().copied().collect::<Vec<_>>()));
+        });
+
+        // Later versions left the earlier ones as they were.
+        for (heap, contents) in versions {
+            assert_eq!(contents, heap.iter_asc().copied().collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn shares_structure() {
+        let heap: PersistentMinMaxHeap<_> = (0 .. 1000).collect();
+        let next = heap.push(500);
+        let root = heap.root.as_ref().unwrap();
+        let next_root = next.root.as_ref().unwrap();
+        let shared = |a: &Link<i32>, b: &Link<i32>| Arc::ptr_eq(a.as_ref().unwrap(), b.as_ref().unwrap());
+
+        // The new element went down one side, and the other is shared.
+        assert!(shared(&root.left, &next_root.left) || shared(&root.right, &next_root.right));
+    }
+}
@@ -0,0 +1,51 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use quickcheck::{Arbitrary, Gen};
+
+use super::{Compare, MinMaxHeap};
+
+// A heap is generated and shrunk as a `Vec` and then heapified, so every
+// heap a property sees is valid, and shrinking removes elements (or
+// shrinks them) just as for vectors.
+
+impl<T, C> Arbitrary for MinMaxHeap<T, C>
+    where T: Arbitrary,
+          C: Compare<T> + Clone + Default + Send + 'static
+{
+    fn arbitrary(g: &mut Gen) -> Self {
+        MinMaxHeap::from_vec_with_comparator(Vec::arbitrary(g), C::default())
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        Box::new(self.0.shrink()
+                     .map(|vec| MinMaxHeap::from_vec_with_comparator(vec, C::default())))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quickcheck::quickcheck;
+
+    quickcheck! {
+        fn prop_valid(heap: MinMaxHeap<i32>) -> bool {
+            heap.assert_valid();
+            true
+        }
+
+        fn prop_shrinks_valid(heap: MinMaxHeap<u8>) -> bool {
+            heap.shrink().take(20).all(|smaller| {
+                smaller.assert_valid();
+                smaller.len() <= heap.len()
+            })
+        }
+    }
+
+    #[test]
+    fn shrinks_by_removing() {
+        let heap: MinMaxHeap<u8> = vec![3, 1, 2].into_iter().collect();
+        assert!(heap.shrink().any(|smaller| smaller.len() < heap.len()));
+        assert!(MinMaxHeap::<u8>::new().shrink().next().is_none());
+    }
+}
@@ -0,0 +1,320 @@
+use alloc::vec::Vec;
+use core::mem;
+
+use rayon::iter::{FromParallelIterator, IntoParallelIterator, IntoParallelRefMutIterator,
+                  ParallelDrainRange, ParallelExtend, ParallelIterator};
+
+use super::{Compare, MinMaxHeap, Natural, rebuild_slice};
+
+/// Below this many elements, a parallel rebuild is not worth the
+/// shuffling.
+const PAR_REBUILD_MIN: usize = 1 << 15;
+
+// The heap’s elements are a Vec in heap order, so parallel iteration
+// hands that Vec (or a slice of it) to rayon.
+
+impl<T: Send, C> IntoParallelIterator for MinMaxHeap<T, C> {
+    type Iter = rayon::vec::IntoIter<T>;
+    type Item = T;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.0.into_par_iter()
+    }
+}
+
+impl<'a, T: Sync, C> IntoParallelIterator for &'a MinMaxHeap<T, C> {
+    type Iter = rayon::slice::Iter<'a, T>;
+    type Item = &'a T;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.0.as_slice().into_par_iter()
+    }
+}
+
+// Collecting gathers the elements into the heap’s Vec in parallel, and
+// then heapifies once, in *O*(*n*) time.
+
+impl<T: Ord + Send> FromParallelIterator<T> for MinMaxHeap<T> {
+    fn from_par_iter<I>(par_iter: I) -> Self
+            where I: IntoParallelIterator<Item = T> {
+        MinMaxHeap::par_from_vec(Vec::from_par_iter(par_iter))
+    }
+}
+
+impl<T: Send, C: Compare<T> + Sync> ParallelExtend<T> for MinMaxHeap<T, C> {
+    fn par_extend<I>(&mut self, par_iter: I)
+            where I: IntoParallelIterator<Item = T> {
+        let start = self.len();
+        self.0.par_extend(par_iter);
+
+        // As for `Extend`, but the rebuild is parallel only when the new
+        // elements alone are enough to be worth it.
+        let len = self.len();
+        if !MinMaxHeap::<T, C>::rebuilds_tail(start, len) {
+            self.bubble_up_tail(start);
+        } else if len - start >= PAR_REBUILD_MIN {
+            self.par_rebuild();
+        } else {
+            self.rebuild();
+        }
+    }
+}
+
+impl<T: Ord + Send> MinMaxHeap<T> {
+    /// Creates a `MinMaxHeap` from a vector, like `From<Vec<T>>`, but
+    /// heapifying on several threads when the vector is large.
+    ///
+    /// Requires the `rayon` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    ///
+    /// let heap = MinMaxHeap::par_from_vec((0 .. 1_000_000).rev().collect());
+    ///
+    /// assert_eq!(heap.peek_min(), Some(&0));
+    /// assert_eq!(heap.peek_max(), Some(&999_999));
+    /// ```
+    ///
+    /// *O*(*n*).
+    pub fn par_from_vec(vec: Vec<T>) -> Self {
+        MinMaxHeap::par_from_vec_with_comparator(vec, Natural)
+    }
+}
+
+impl<T: Send, C: Compare<T> + Sync> MinMaxHeap<T, C> {
+    /// Creates a `MinMaxHeap` ordered by `cmp` from a vector, heapifying
+    /// on several threads when the vector is large.
+    ///
+    /// Requires the `rayon` feature.
+    ///
+    /// *O*(*n*).
+    pub fn par_from_vec_with_comparator(vec: Vec<T>, cmp: C) -> Self {
+        let mut heap = MinMaxHeap(vec, cmp);
+        heap.par_rebuild();
+        heap
+    }
+
+    /// Heapifies the subtrees rooted at one level in parallel, and then
+    /// the levels above them.
+    ///
+    /// Since a subtree is spread across the levels of the array, each is
+    /// gathered into a vector of its own, heapified there, and scattered
+    /// back, which takes *O*(*n*) moves and as much extra space.
+    fn par_rebuild(&mut self) {
+        self.par_rebuild_for(rayon::current_num_threads());
+    }
+
+    /// Does `par_rebuild`, sized for a pool of `threads` threads.
+    fn par_rebuild_for(&mut self, threads: usize) {
+        let len = self.len();
+        let roots = subtree_roots(len, threads);
+        if len < PAR_REBUILD_MIN || threads == 1 || roots == 1 {
+            self.rebuild();
+            return;
+        }
+        let first_root = roots - 1;
+
+        let mut vec = mem::take(&mut self.0);
+        let mut subtrees: Vec<Vec<T>> = (0 .. roots).map(|_| Vec::new()).collect();
+        {
+            let mut below = vec.drain(first_root ..);
+            let mut width = 1;
+            'levels: loop {
+                for subtree in &mut subtrees {
+                    for _ in 0 .. width {
+                        match below.next() {
+                            Some(element) => subtree.push(element),
+                            None          => break 'levels,
+                        }
+                    }
+                }
+                width *= 2;
+            }
+        }
+
+        let cmp = &self.1;
+        subtrees.par_iter_mut().for_each(|subtree| rebuild_slice(subtree, cmp));
+
+        let mut subtrees: Vec<_> = subtrees.into_iter().map(Vec::into_iter).collect();
+        let mut width = 1;
+        while vec.len() < len {
+            for subtree in &mut subtrees {
+                vec.extend(subtree.by_ref().take(width));
+            }
+            width *= 2;
+        }
+        self.0 = vec;
+
+        for pos in (0 .. first_root).rev() {
+            // SAFETY: `pos < first_root <= len`
+            unchecked! {
+                Self::trickle_down_slice(&mut self.0, pos, &self.1);
+            }
+        }
+        paranoid_check!(self);
+    }
+}
+
+/// The number of subtrees for `par_rebuild` to heapify in parallel.
+///
+/// The roots are on a min level, so that each subtree is itself a
+/// min-max-heap; a few per thread balances the load. But every root must
+/// be below `len`, so a small heap in a large pool gets fewer.
+fn subtree_roots(len: usize, threads: usize) -> usize {
+    let mut roots = 1;
+    // The next level of roots would start at `4 * roots - 1` and hold
+    // `4 * roots` of them.
+    while roots < 4 * threads && 8 * roots - 1 <= len {
+        roots *= 4;
+    }
+    roots
+}
+
+impl<T: Send, C> MinMaxHeap<T, C> {
+    /// Returns a parallel iterator that removes all the elements of the
+    /// heap, in arbitrary order. The heap is empty afterward, even if
+    /// the iterator is not used up.
+    ///
+    /// Requires the `rayon` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use min_max_heap::MinMaxHeap;
+    /// use rayon::prelude::*;
+    ///
+    /// let mut heap: MinMaxHeap<u64> = (1 ..= 100).collect();
+    /// let sum: u64 = heap.par_drain().sum();
+    ///
+    /// assert_eq!(sum, 5050);
+    /// assert!(heap.is_empty());
+    /// ```
+    ///
+    /// *O*(*n*).
+    pub fn par_drain(&mut self) -> rayon::vec::Drain<'_, T> {
+        self.0.par_drain(..)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate rand;
+
+    use super::*;
+    use self::rand::Rng;
+    use super::super::FnComparator;
+    use rayon::iter::IntoParallelRefIterator;
+    use rayon::slice::ParallelSlice;
+
+    #[test]
+    fn par_iter() {
+        let heap: MinMaxHeap<u32> = (0 .. 1000).rev().collect();
+
+        let max = heap.par_iter().map(|&x| x * 2).max();
+        assert_eq!(Some(1998), max);
+
+        let mut all: Vec<_> = heap.into_par_iter().collect();
+        all.sort();
+        assert_eq!((0 .. 1000).collect::<Vec<_>>(), all);
+    }
+
+    #[test]
+    fn par_drain() {
+        let mut heap: MinMaxHeap<u32> = (0 .. 1000).collect();
+        assert_eq!(500, heap.par_drain().filter(|x| x % 2 == 0).count());
+        assert!(heap.is_empty());
+
+        heap.push(4);
+        assert_eq!(Some(&4), heap.peek_max());
+    }
+
+    #[test]
+    fn collect() {
+        let data: Vec<u32> = (0 .. 10_000).map(|i| i * 7919 % 10_007).collect();
+
+        let heap: MinMaxHeap<_> = data.par_iter().cloned().collect();
+        heap.assert_valid();
+        let mut expected = data.clone();
+        expected.sort();
+        assert_eq!(expected, heap.clone().into_vec_asc());
+
+        let mut heap = heap;
+        heap.par_extend(data.par_chunks(100).map(|chunk| chunk[0]));
+        heap.assert_valid();
+        assert_eq!(10_100, heap.len());
+    }
+
+    #[test]
+    fn par_extend_large() {
+        let mut heap: MinMaxHeap<u32> = (0 .. 50).collect();
+        heap.par_extend((0 .. 2 * PAR_REBUILD_MIN as u32).into_par_iter().map(|i| i * 7 % 1000));
+        heap.assert_valid();
+        assert_eq!(2 * PAR_REBUILD_MIN + 50, heap.len());
+
+        heap.par_extend(vec![u32::MAX, 7]);
+        heap.assert_valid();
+        assert_eq!(Some(&u32::MAX), heap.peek_max());
+    }
+
+    #[test]
+    fn par_rebuild() {
+        let mut rng = rand::thread_rng();
+
+        for &len in &[0, 1, 100, PAR_REBUILD_MIN, 3 * PAR_REBUILD_MIN + 17] {
+            let vec: Vec<u32> = (0 .. len).map(|_| rng.gen_range(0 .. 1000)).collect();
+            let heap = MinMaxHeap::par_from_vec(vec.clone());
+            heap.assert_valid();
+
+            let mut expected = vec;
+            expected.sort();
+            assert_eq!(expected, heap.into_vec_asc());
+        }
+
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(20).build().unwrap();
+        let vec: Vec<u32> = (0 .. 200_000).map(|_| rng.gen_range(0 .. 1000)).collect();
+        let heap = pool.install(|| {
+            MinMaxHeap::par_from_vec_with_comparator(vec, FnComparator(|a: &u32, b: &u32| b.cmp(a)))
+        });
+        heap.assert_valid();
+    }
+
+    #[test]
+    fn subtree_roots_fit() {
+        assert_eq!(4, subtree_roots(1 << 20, 1));
+        assert_eq!(16, subtree_roots(1 << 20, 4));
+        assert_eq!(64, subtree_roots(1 << 20, 5));
+        assert_eq!(1 << 18, subtree_roots(1 << 20, 1 << 20));
+        assert_eq!(1 << 14, subtree_roots(PAR_REBUILD_MIN, 1 << 20));
+        assert_eq!(1, subtree_roots(6, 1 << 20));
+
+        for len in 0 .. 1000 {
+            let roots = subtree_roots(len, usize::MAX / 8);
+            assert!(roots == 1 || 2 * roots - 1 <= len);
+        }
+    }
+
+    #[test]
+    fn par_rebuild_large_pool() {
+        let mut rng = rand::thread_rng();
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(256).build().unwrap();
+
+        for &len in &[100, PAR_REBUILD_MIN, PAR_REBUILD_MIN + 1] {
+            let vec: Vec<u32> = (0 .. len).map(|_| rng.gen_range(0 .. 1000)).collect();
+            let heap = pool.install(|| MinMaxHeap::par_from_vec(vec.clone()));
+            heap.assert_valid();
+
+            let mut expected = vec;
+            expected.sort();
+            assert_eq!(expected, heap.into_vec_asc());
+        }
+
+        // Sized for a pool far larger than the heap has room for.
+        let vec: Vec<u32> = (0 .. PAR_REBUILD_MIN as u32).rev().collect();
+        let mut heap = MinMaxHeap(vec, Natural);
+        heap.par_rebuild_for(1 << 20);
+        heap.assert_valid();
+        assert_eq!((0 .. PAR_REBUILD_MIN as u32).collect::<Vec<_>>(), heap.into_vec_asc());
+    }
+}
@@ -0,0 +1,208 @@
+use core::fmt;
+
+use rkyv::bytecheck::{CheckBytes, Verify};
+use rkyv::munge::munge;
+use rkyv::rancor::{Fallible, Source};
+use rkyv::ser::{Allocator, Writer};
+use rkyv::vec::{ArchivedVec, VecResolver};
+use rkyv::{Archive, Deserialize, Place, Portable, Serialize};
+
+use super::{Compare, Iter, MinMaxHeap, Natural, find_max_slice};
+
+// A heap archives as its elements in heap order, like its serde
+// representation, so that an archive can be used as a heap in place.
+// Checked access verifies that order under the archived elements’ own
+// `Ord`, and deserializing rebuilds under the heap’s comparator.
+
+/// The archived form of a [`MinMaxHeap`], which can be read in place
+/// from an `rkyv` buffer.
+///
+/// Accessing an archive with `rkyv::access` checks that its elements are
+/// in heap order according to `T: Ord`, so that a corrupt or tampered
+/// buffer is rejected rather than giving wrong answers. That order must
+/// agree with the comparator of the heap that was archived.
+///
+/// Requires the `rkyv` feature.
+///
+/// # Examples
+///
+/// ```
+/// use min_max_heap::{ArchivedMinMaxHeap, MinMaxHeap};
+/// use rkyv::rancor::Error;
+/// use rkyv::rend::u32_le;
+///
+/// let heap: MinMaxHeap<u32> = vec![5, 1, 9, 3].into_iter().collect();
+/// let bytes = rkyv::to_bytes::<Error>(&heap).unwrap();
+///
+/// let archived = rkyv::access::<ArchivedMinMaxHeap<u32_le>, Error>(&bytes).unwrap();
+/// assert_eq!(archived.len(), 4);
+/// assert_eq!(archived.peek_min().map(|x| x.to_native()), Some(1));
+/// assert_eq!(archived.peek_max().map(|x| x.to_native()), Some(9));
+///
+/// let heap: MinMaxHeap<u32> = rkyv::deserialize::<_, Error>(archived).unwrap();
+/// assert_eq!(heap.into_vec_asc(), vec![1, 3, 5, 9]);
+/// ```
+///
+/// [`MinMaxHeap`]: struct.MinMaxHeap.html
+#[derive(CheckBytes, Portable)]
+#[bytecheck(crate = rkyv::bytecheck, verify)]
+#[repr(transparent)]
+pub struct ArchivedMinMaxHeap<T> {
+    data: ArchivedVec<T>,
+}
+
+impl<T> ArchivedMinMaxHeap<T> {
+    /// The number of elements in the heap.
+    ///
+    /// *O*(1).
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Is the heap empty?
+    ///
+    /// *O*(1).
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Gets a reference to the minimum element, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_min(&self) -> Option<&T> {
+        self.data.as_slice().first()
+    }
+
+    /// Gets a reference to the maximum element, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_max(&self) -> Option<&T>
+        where T: Ord
+    {
+        let slice = self.data.as_slice();
+        find_max_slice(slice, &Natural).map(|max| &slice[max])
+    }
+
+    /// Returns an iterator over the elements of the heap in arbitrary
+    /// order.
+    ///
+    /// *O*(1) on creation, and *O*(1) for each `next()` operation.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter(self.data.as_slice().iter())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArchivedMinMaxHeap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ArchivedMinMaxHeap")
+         .field(&self.data.as_slice())
+         .finish()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ArchivedMinMaxHeap<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter { self.iter() }
+}
+
+/// The error from checking an archived heap whose elements are out of
+/// order.
+#[derive(Debug)]
+struct OutOfOrder {
+    pos:      usize,
+    ancestor: usize,
+}
+
+impl fmt::Display for OutOfOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "archived MinMaxHeap element at position {} is out of order \
+                   with its ancestor at position {}",
+               self.pos, self.ancestor)
+    }
+}
+
+impl core::error::Error for OutOfOrder { }
+
+// SAFETY: `verify` only adds a check on top of the derived ones, so it
+// never accepts a value that they reject.
+unsafe impl<T: Ord, C> Verify<C> for ArchivedMinMaxHeap<T>
+    where C: Fallible + ?Sized,
+          C::Error: Source
+{
+    fn verify(&self, _context: &mut C) -> Result<(), C::Error> {
+        match MinMaxHeap::find_violation_slice(self.data.as_slice(), &Natural) {
+            Some((pos, ancestor)) => Err(C::Error::new(OutOfOrder { pos, ancestor })),
+            None                  => Ok(()),
+        }
+    }
+}
+
+impl<T: Archive, C> Archive for MinMaxHeap<T, C> {
+    type Archived = ArchivedMinMaxHeap<T::Archived>;
+    type Resolver = VecResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        munge!(let ArchivedMinMaxHeap { data } = out);
+        ArchivedVec::resolve_from_slice(&self.0, resolver, data);
+    }
+}
+
+impl<T, C, S> Serialize<S> for MinMaxHeap<T, C>
+    where T: Serialize<S>,
+          S: Fallible + Allocator + Writer + ?Sized
+{
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedVec::serialize_from_slice(&self.0, serializer)
+    }
+}
+
+impl<T, C, D> Deserialize<MinMaxHeap<T, C>, D> for ArchivedMinMaxHeap<T::Archived>
+    where T: Archive,
+          T::Archived: Deserialize<T, D>,
+          C: Compare<T> + Default,
+          D: Fallible + ?Sized
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<MinMaxHeap<T, C>, D::Error> {
+        let vec = self.data.as_slice().iter()
+            .map(|element| element.deserialize(deserializer))
+            .collect::<Result<_, _>>()?;
+        Ok(MinMaxHeap::from_vec_with_comparator(vec, C::default()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec::Vec;
+    use rkyv::rancor::Error;
+    use rkyv::rend::u32_le;
+
+    #[test]
+    fn round_trip() {
+        let heap: MinMaxHeap<u32> = (0 .. 100).map(|i| i * 37 % 101).collect();
+        let bytes = rkyv::to_bytes::<Error>(&heap).unwrap();
+
+        let archived = rkyv::access::<ArchivedMinMaxHeap<u32_le>, Error>(&bytes).unwrap();
+        assert_eq!(heap.len(), archived.len());
+        assert_eq!(heap.peek_min().copied(), archived.peek_min().map(|x| x.to_native()));
+        assert_eq!(heap.peek_max().copied(), archived.peek_max().map(|x| x.to_native()));
+
+        let back: MinMaxHeap<u32> = rkyv::deserialize::<_, Error>(archived).unwrap();
+        assert_eq!(heap.into_vec(), back.into_vec());
+    }
+
+    #[test]
+    fn rejects_out_of_order() {
+        let heap = MinMaxHeap(vec![3u32, 5, 9, 1, 2], Natural);
+        let bytes = rkyv::to_bytes::<Error>(&heap).unwrap();
+
+        let result = rkyv::access::<ArchivedMinMaxHeap<u32_le>, Error>(&bytes);
+        assert!(result.is_err());
+
+        let empty: MinMaxHeap<u32> = MinMaxHeap::new();
+        let bytes = rkyv::to_bytes::<Error>(&empty).unwrap();
+        let archived = rkyv::access::<ArchivedMinMaxHeap<u32_le>, Error>(&bytes).unwrap();
+        assert_eq!(Vec::<&u32_le>::new(), archived.iter().collect::<Vec<_>>());
+    }
+}
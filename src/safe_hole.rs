@@ -0,0 +1,292 @@
+use super::compare::Compare;
+use super::index::*;
+use super::stats;
+
+// The `safe` feature’s stand-in for `hole.rs`, with the same interface.
+// Rather than moving its element out and leaving a gap, this `Hole`
+// leaves the element in the slice and swaps it along as the hole moves.
+// That costs an extra write per step, but needs no `unsafe`.
+pub struct Hole<'a, T: 'a, K: Track<T> = ()> {
+    data: &'a mut [T],
+    pos: usize,
+    track: K,
+}
+
+/// Told where each element lands as a `Hole` moves elements about, for
+/// heaps that keep track of their elements’ positions.
+pub trait Track<T> {
+    fn moved(&mut self, element: &T, pos: usize);
+}
+
+impl<T> Track<T> for () {
+    #[inline]
+    fn moved(&mut self, _: &T, _: usize) {}
+}
+
+enum Generation {
+    Child,
+    Grandchild,
+}
+
+impl<'a, T> Hole<'a, T> {
+    /// Create a new Hole at index `pos`.
+    ///
+    /// Panics if `pos` is not a valid index in `data`.
+    pub fn new(data: &'a mut [T], pos: usize) -> Self {
+        Hole::with_track(data, pos, ())
+    }
+}
+
+impl<'a, T, K: Track<T>> Hole<'a, T, K> {
+    /// Create a new Hole at index `pos` that tells `track` where the
+    /// elements it moves end up.
+    ///
+    /// Panics if `pos` is not a valid index in `data`.
+    pub fn with_track(data: &'a mut [T], pos: usize, track: K) -> Self {
+        assert!(pos < data.len());
+        stats::sift();
+        Hole { data, pos, track }
+    }
+
+    #[inline]
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// Return a reference to the element at the hole
+    #[inline]
+    pub fn element(&self) -> &T {
+        &self.data[self.pos]
+    }
+
+    #[inline]
+    pub fn get_parent(&mut self) -> Option<HoleSwap<'a, '_, T, K>> {
+        if self.pos().has_parent() {
+            let parent = self.pos().parent();
+            Some(HoleSwap::new(self, parent))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn get_grandparent(&mut self) -> Option<HoleSwap<'a, '_, T, K>> {
+        if self.pos().has_grandparent() {
+            let grandparent = self.pos().grandparent();
+            Some(HoleSwap::new(self, grandparent))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn on_min_level(&self) -> bool {
+        self.pos().is_min_level()
+    }
+
+    #[inline]
+    fn best_child_or_grandchild<F>(&mut self, f: F)
+        -> Option<(HoleSwap<'a, '_, T, K>, Generation)>
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        let data = &*self.data;
+        let here = self.pos();
+
+        let mut best    = None;
+        let mut element = &data[here];
+
+        {
+            let mut check = |index, generation| {
+                data.get(index).map(|candidate| {
+                    if stats::compared(f(candidate, element)) {
+                        best = Some((index, generation));
+                        element = candidate;
+                    }
+                })
+            };
+
+            (|| {
+                check(here.child1(), Generation::Child)?;
+                check(here.child2(), Generation::Child)?;
+                check(here.grandchild1(), Generation::Grandchild)?;
+                check(here.grandchild2(), Generation::Grandchild)?;
+                check(here.grandchild3(), Generation::Grandchild)?;
+                check(here.grandchild4(), Generation::Grandchild)?;
+                Some(())
+            })();
+        }
+
+        best.map(move |(index, generation)| (HoleSwap::new(self, index), generation))
+    }
+
+    fn trickle_down_best<F>(&mut self, f: F) where F: Fn(&T, &T) -> bool {
+        while let Some((best, generation)) = self.best_child_or_grandchild(&f) {
+            best.move_to();
+            match generation {
+                Generation::Grandchild => {
+                    let parent = self.pos().parent();
+                    let mut parent = HoleSwap::new(self, parent);
+                    if stats::compared(f(parent.other_element(), parent.hole_element())) {
+                        parent.swap_with();
+                    }
+                }
+                Generation::Child => return,
+            }
+        }
+    }
+}
+
+impl<'a, T, K: Track<T>> Hole<'a, T, K> {
+    pub fn bubble_up<C: Compare<T>>(&mut self, cmp: &C) {
+        if self.on_min_level() {
+            match self.get_parent() {
+                Some(parent) if stats::compared(cmp.compares_gt(parent.hole_element(),
+                                                                parent.other_element())) => {
+                    parent.move_to();
+                    self.bubble_up_max(cmp);
+                }
+                _ => self.bubble_up_min(cmp),
+            }
+        } else {
+            match self.get_parent() {
+                Some(parent) if stats::compared(cmp.compares_lt(parent.hole_element(),
+                                                                parent.other_element())) => {
+                    parent.move_to();
+                    self.bubble_up_min(cmp);
+                }
+                _ => self.bubble_up_max(cmp),
+            }
+        }
+    }
+
+    /// Moves the element to the top of the max levels (if `max`) or the
+    /// min levels without comparing it, for an element known to be at
+    /// least (or at most) every other element in the heap.
+    #[cfg(feature = "alloc")]
+    pub fn bubble_up_extreme(&mut self, max: bool) {
+        if self.on_min_level() == max {
+            if let Some(parent) = self.get_parent() {
+                parent.move_to();
+            }
+        }
+
+        while let Some(grandparent) = self.get_grandparent() {
+            grandparent.move_to();
+        }
+    }
+
+    fn bubble_up_grandparent<F>(&mut self, f: F) where F: Fn(&T, &T) -> bool {
+        while let Some(grandparent) = self.get_grandparent() {
+            if stats::compared(f(grandparent.hole_element(), grandparent.other_element())) {
+                grandparent.move_to();
+            } else {
+                return;
+            }
+        }
+    }
+
+    fn bubble_up_min<C: Compare<T>>(&mut self, cmp: &C) {
+        self.bubble_up_grandparent(|a, b| cmp.compares_lt(a, b));
+    }
+
+    fn bubble_up_max<C: Compare<T>>(&mut self, cmp: &C) {
+        self.bubble_up_grandparent(|a, b| cmp.compares_gt(a, b));
+    }
+
+    pub fn trickle_down<C: Compare<T>>(&mut self, cmp: &C) {
+        if self.on_min_level() {
+            self.trickle_down_min(cmp);
+        } else {
+            self.trickle_down_max(cmp);
+        }
+    }
+
+    pub fn trickle_down_min<C: Compare<T>>(&mut self, cmp: &C) {
+        self.trickle_down_best(|a, b| cmp.compares_lt(a, b));
+    }
+
+    pub fn trickle_down_max<C: Compare<T>>(&mut self, cmp: &C) {
+        self.trickle_down_best(|a, b| cmp.compares_gt(a, b));
+    }
+}
+
+impl<'a, T, K: Track<T>> Drop for Hole<'a, T, K> {
+    fn drop(&mut self) {
+        self.track.moved(&self.data[self.pos], self.pos);
+    }
+}
+
+/// A hole, along with a potential new position to move it to.
+pub struct HoleSwap<'a, 'b, T, K: Track<T> = ()> {
+    hole: &'b mut Hole<'a, T, K>,
+    index: usize,
+}
+
+impl<'a, 'b, T, K: Track<T>> HoleSwap<'a, 'b, T, K> {
+    fn new(hole: &'b mut Hole<'a, T, K>, index: usize) -> Self {
+        debug_assert!(index != hole.pos());
+        debug_assert!(index < hole.data.len());
+        HoleSwap { hole, index }
+    }
+
+    /// The element at the hole.
+    pub fn hole_element(&self) -> &T {
+        self.hole.element()
+    }
+
+    /// The element at the index to potentially move to.
+    pub fn other_element(&self) -> &T {
+        &self.hole.data[self.index]
+    }
+
+    /// Swaps `other_element()` into the current hole
+    /// and moves the hole to where `other_element()` was.
+    /// This invalidates the `HoleSwap`.
+    pub fn move_to(self) {
+        stats::moved(self.hole.pos, self.index);
+        self.hole.data.swap(self.hole.pos, self.index);
+        self.hole.track.moved(&self.hole.data[self.hole.pos], self.hole.pos);
+        self.hole.pos = self.index;
+    }
+
+    /// Swaps `hole_element()` with `other_element()`, without moving the hole
+    pub fn swap_with(&mut self) {
+        stats::swap();
+        self.hole.data.swap(self.hole.pos, self.index);
+        self.hole.track.moved(&self.hole.data[self.index], self.index);
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hole() {
+        let mut v = vec![0, 1, 2, 3, 4, 5];
+        {
+            let mut h = Hole::new(&mut v, 1);
+
+            assert_eq!(1, h.pos());
+            assert_eq!(1, *h.element());
+            assert_eq!(2, h.data[2]);
+
+            HoleSwap::new(&mut h, 4).move_to();
+
+            assert_eq!(4, h.pos());
+            assert_eq!(1, *h.element());
+            assert_eq!(4, h.data[1]);
+            assert_eq!(2, h.data[2]);
+        }
+
+        assert_eq!(vec![0, 4, 2, 3, 1, 5], v);
+    }
+
+    #[test]
+    #[should_panic]
+    fn out_of_bounds() {
+        let mut v = vec![0, 1, 2];
+        Hole::new(&mut v, 3);
+    }
+}
@@ -0,0 +1,40 @@
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+
+use super::MinMaxHeap;
+
+// A heap serializes as a sequence of its elements, both by default and
+// with `serde_sorted`, so its schema is the schema of a `Vec`.
+
+impl<T: JsonSchema, C> JsonSchema for MinMaxHeap<T, C> {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        Vec::<T>::schema_name()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        Vec::<T>::schema_id()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        Vec::<T>::json_schema(generator)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use schemars::schema_for;
+
+    #[test]
+    fn array_schema() {
+        let schema = schema_for!(MinMaxHeap<u32>);
+        assert_eq!(schema_for!(Vec<u32>), schema);
+        assert_eq!(Some("array"), schema.get("type").and_then(|ty| ty.as_str()));
+    }
+}
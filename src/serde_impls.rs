@@ -0,0 +1,65 @@
+use alloc::vec::Vec;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{Compare, MinMaxHeap};
+
+// The comparator is not serialized, so a heap has the same
+// representation as it did before comparators were added: a newtype
+// around its elements in heap order.
+//
+// Deserializing does not trust that order, since the input may be stale,
+// hand-written, or hostile, or may have been serialized under another
+// comparator. It heapifies the elements as `from_vec_with_comparator`
+// does instead, in *O*(*n*) time, which leaves elements that are already
+// in heap order where they are, unless they are also sorted.
+
+#[derive(Serialize)]
+#[serde(rename = "MinMaxHeap")]
+struct SerRepr<'a, T>(&'a [T]);
+
+#[derive(Deserialize)]
+#[serde(rename = "MinMaxHeap")]
+struct DeRepr<T>(Vec<T>);
+
+impl<T: Serialize, C> Serialize for MinMaxHeap<T, C> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerRepr(&self.0).serialize(serializer)
+    }
+}
+
+impl<'de, T, C> Deserialize<'de> for MinMaxHeap<T, C>
+    where T: Deserialize<'de>,
+          C: Compare<T> + Default
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let DeRepr(vec) = DeRepr::deserialize(deserializer)?;
+        Ok(MinMaxHeap::from_vec_with_comparator(vec, C::default()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde::de::value::{Error, SeqDeserializer};
+
+    fn from_layout(layout: Vec<u32>) -> MinMaxHeap<u32> {
+        let deserializer = SeqDeserializer::<_, Error>::new(vec![layout].into_iter());
+        MinMaxHeap::deserialize(deserializer).unwrap()
+    }
+
+    #[test]
+    fn keeps_valid_layout() {
+        let heap: MinMaxHeap<u32> = (0 .. 20).rev().collect();
+        let layout = heap.clone().into_vec();
+
+        let heap = from_layout(layout.clone());
+        assert_eq!(layout, heap.into_vec());
+    }
+
+    #[test]
+    fn rebuilds_invalid_layout() {
+        let heap = from_layout(vec![9, 1, 2, 8, 0, 7, 3]);
+        heap.assert_valid();
+        assert_eq!(vec![0, 1, 2, 3, 7, 8, 9], heap.into_vec_asc());
+    }
+}
@@ -0,0 +1,93 @@
+//! Serializes a heap as its elements in ascending order, for use with
+//! `#[serde(with = "min_max_heap::serde_sorted")]`.
+//!
+//! The usual representation of a [`MinMaxHeap`] is its internal layout,
+//! which depends on the order that elements were pushed. This module’s
+//! representation depends only on the elements, so equal heaps always
+//! serialize the same way, which suits golden files and content hashes.
+//! It costs *O*(*n* log *n*) time to sort when serializing, and a
+//! rebuild when deserializing.
+//!
+//! Elements that the comparator considers equal are written in layout
+//! order, so the output is deterministic only if such elements also
+//! serialize the same, as they do under a `T: Ord` that agrees with
+//! `Eq`.
+//!
+//! Requires the `serde` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use min_max_heap::MinMaxHeap;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Scores {
+//!     #[serde(with = "min_max_heap::serde_sorted")]
+//!     heap: MinMaxHeap<u32>,
+//! }
+//! ```
+//!
+//! [`MinMaxHeap`]: ../struct.MinMaxHeap.html
+
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{Compare, MinMaxHeap};
+
+/// Serializes `heap` as a sequence of its elements in ascending order.
+///
+/// *O*(*n* log *n*).
+pub fn serialize<T, C, S>(heap: &MinMaxHeap<T, C>, serializer: S) -> Result<S::Ok, S::Error>
+    where T: Serialize,
+          C: Compare<T>,
+          S: Serializer
+{
+    serializer.collect_seq(sorted(heap))
+}
+
+/// Deserializes a heap from a sequence of its elements, in any order.
+///
+/// *O*(*n*).
+pub fn deserialize<'de, T, C, D>(deserializer: D) -> Result<MinMaxHeap<T, C>, D::Error>
+    where T: Deserialize<'de>,
+          C: Compare<T> + Default,
+          D: Deserializer<'de>
+{
+    let vec = Vec::deserialize(deserializer)?;
+    Ok(MinMaxHeap::from_vec_with_comparator(vec, C::default()))
+}
+
+fn sorted<T, C: Compare<T>>(heap: &MinMaxHeap<T, C>) -> Vec<&T> {
+    let mut refs: Vec<&T> = heap.0.iter().collect();
+    refs.sort_by(|a, b| heap.1.compare(a, b));
+    refs
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde::de::value::{Error, SeqDeserializer};
+
+    #[test]
+    fn independent_of_push_order() {
+        let up: MinMaxHeap<u32> = (0 .. 20).map(|i| i * 7 % 20).collect();
+        let down: MinMaxHeap<u32> = (0 .. 20).rev().map(|i| i * 7 % 20).collect();
+        assert_ne!(up.clone().into_vec(), down.clone().into_vec());
+
+        let expected: Vec<u32> = (0 .. 20).collect();
+        assert_eq!(expected.iter().collect::<Vec<_>>(), sorted(&up));
+        assert_eq!(sorted(&up), sorted(&down));
+    }
+
+    #[test]
+    fn deserialize_any_order() {
+        let elements: Vec<u32> = (0 .. 20).rev().collect();
+
+        let heap: MinMaxHeap<u32> =
+            deserialize(SeqDeserializer::<_, Error>::new(elements.into_iter())).unwrap();
+        heap.assert_valid();
+        assert_eq!((0 .. 20).collect::<Vec<_>>(), heap.into_vec_asc());
+    }
+}
@@ -0,0 +1,285 @@
+use core::fmt;
+
+use super::{Compare, Iter, Natural, find_max_slice, rebuild_slice};
+use super::hole::Hole;
+
+/// A min-max-heap laid out in a borrowed slice.
+///
+/// The first [`len`] elements of the slice are the heap. Popping an
+/// element swaps it to just past the end of the heap and shrinks the
+/// heap by one, leaving it in the slice; pushing writes an element into
+/// the first slot past the heap and grows it by one. Nothing is ever
+/// allocated, so this works on buffers owned by someone else.
+///
+/// # Examples
+///
+/// Popping every maximum sorts the slice in place:
+///
+/// ```
+/// use min_max_heap::SliceMinMaxHeap;
+///
+/// let mut buffer = [4, 9, 1, 7, 3];
+///
+/// let mut heap = SliceMinMaxHeap::new(&mut buffer);
+/// assert_eq!(heap.peek_min(), Some(&1));
+/// while heap.pop_max().is_some() { }
+///
+/// assert_eq!(buffer, [1, 3, 4, 7, 9]);
+/// ```
+///
+/// [`len`]: struct.SliceMinMaxHeap.html#method.len
+pub struct SliceMinMaxHeap<'a, T, C = Natural> {
+    data: &'a mut [T],
+    len:  usize,
+    cmp:  C,
+}
+
+impl<'a, T: Ord> SliceMinMaxHeap<'a, T> {
+    /// Arranges all of `data` into a min-max-heap.
+    ///
+    /// *O*(*n*).
+    pub fn new(data: &'a mut [T]) -> Self {
+        SliceMinMaxHeap::with_comparator(data, Natural)
+    }
+}
+
+impl<'a, T, C: Compare<T>> SliceMinMaxHeap<'a, T, C> {
+    /// Arranges all of `data` into a min-max-heap ordered by `cmp`.
+    ///
+    /// *O*(*n*).
+    pub fn with_comparator(data: &'a mut [T], cmp: C) -> Self {
+        rebuild_slice(data, &cmp);
+        let len = data.len();
+        SliceMinMaxHeap { data, len, cmp }
+    }
+
+    /// Adds an element to the heap, overwriting (and dropping) the
+    /// element in the first slot past it, or returns the element if the
+    /// heap fills the slice.
+    ///
+    /// *O*(log *n*).
+    pub fn push(&mut self, element: T) -> Result<(), T> {
+        match self.data.get_mut(self.len) {
+            Some(slot) => *slot = element,
+            None       => return Err(element),
+        }
+
+        self.grow();
+        Ok(())
+    }
+
+    /// Adds the element in the first slot past the heap to the heap,
+    /// returning whether there was one. After a pop, this puts the
+    /// popped element back.
+    ///
+    /// *O*(log *n*).
+    pub fn push_next(&mut self) -> bool {
+        if self.is_full() {
+            false
+        } else {
+            self.grow();
+            true
+        }
+    }
+
+    /// Gets a reference to the minimum element, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_min(&self) -> Option<&T> {
+        self.as_slice().first()
+    }
+
+    /// Gets a reference to the maximum element, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_max(&self) -> Option<&T> {
+        let slice = self.as_slice();
+        find_max_slice(slice, &self.cmp).map(|max| &slice[max])
+    }
+
+    /// Removes the minimum element from the heap, if any, moving it to
+    /// the first slot past the heap and returning a reference to it
+    /// there.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_min(&mut self) -> Option<&mut T> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.shrink(0))
+        }
+    }
+
+    /// Removes the maximum element from the heap, if any, moving it to
+    /// the first slot past the heap and returning a reference to it
+    /// there.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_max(&mut self) -> Option<&mut T> {
+        let max = find_max_slice(self.as_slice(), &self.cmp)?;
+        Some(self.shrink(max))
+    }
+
+    fn grow(&mut self) {
+        let pos = self.len;
+        self.len += 1;
+        // SAFETY: `pos < self.len`
+        unchecked! {
+            Hole::new(&mut self.data[.. self.len], pos).bubble_up(&self.cmp);
+        }
+    }
+
+    /// Swaps the element at `pos` past the end of the heap, shrinks the
+    /// heap, and restores it. Assumes that `pos` is a min or the max.
+    fn shrink(&mut self, pos: usize) -> &mut T {
+        self.len -= 1;
+        let last = self.len;
+        self.data.swap(pos, last);
+
+        if pos < last {
+            let heap = &mut self.data[.. last];
+            // SAFETY: `pos < last`, the length of `heap`
+            unchecked! {
+                if pos == 0 {
+                    Hole::new(heap, pos).trickle_down_min(&self.cmp);
+                } else {
+                    Hole::new(heap, pos).trickle_down_max(&self.cmp);
+                }
+            }
+        }
+
+        &mut self.data[last]
+    }
+}
+
+impl<'a, T, C> SliceMinMaxHeap<'a, T, C> {
+    /// The number of elements in the heap.
+    ///
+    /// *O*(1).
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// The length of the underlying slice, which the heap cannot
+    /// outgrow.
+    ///
+    /// *O*(1).
+    pub fn capacity(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Is the heap empty?
+    ///
+    /// *O*(1).
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Does the heap fill the slice?
+    ///
+    /// *O*(1).
+    pub fn is_full(&self) -> bool {
+        self.len == self.data.len()
+    }
+
+    /// Returns an iterator over the elements of the heap in arbitrary
+    /// order.
+    ///
+    /// *O*(1) on creation, and *O*(1) for each `next()` operation.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter(self.as_slice().iter())
+    }
+
+    /// The elements of the heap, in heap order.
+    ///
+    /// *O*(1).
+    pub fn as_slice(&self) -> &[T] {
+        &self.data[.. self.len]
+    }
+
+    /// The elements of the slice past the heap. Popped elements are
+    /// here, most recently popped first.
+    ///
+    /// *O*(1).
+    pub fn rest(&self) -> &[T] {
+        &self.data[self.len ..]
+    }
+
+    /// Gives back the whole underlying slice.
+    ///
+    /// *O*(1).
+    pub fn into_slice(self) -> &'a mut [T] {
+        self.data
+    }
+}
+
+impl<'a, T: fmt::Debug, C> fmt::Debug for SliceMinMaxHeap<'a, T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SliceMinMaxHeap")
+         .field(&self.as_slice())
+         .finish()
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    extern crate rand;
+
+    use super::*;
+    use super::super::MinMaxHeap;
+    use alloc::vec::Vec;
+    use self::rand::Rng;
+
+    #[test]
+    fn random() {
+        let mut rng = rand::thread_rng();
+        let mut buffer = [0u32; 24];
+        let mut model = MinMaxHeap::new();
+
+        for x in buffer.iter_mut() {
+            *x = rng.gen_range(0 .. 50);
+            model.push(*x);
+        }
+
+        let mut heap = SliceMinMaxHeap::new(&mut buffer);
+
+        for _ in 0 .. 2000 {
+            match rng.gen_range(0 .. 5) {
+                0 => {
+                    let x = rng.gen_range(0 .. 50);
+                    if heap.is_full() {
+                        assert_eq!(Err(x), heap.push(x));
+                    } else {
+                        assert_eq!(Ok(()), heap.push(x));
+                        model.push(x);
+                    }
+                }
+                1 => {
+                    let next = heap.rest().first().copied();
+                    assert_eq!(next.is_some(), heap.push_next());
+                    model.extend(next);
+                }
+                2 => assert_eq!(model.pop_min().as_ref(), heap.pop_min().map(|x| &*x)),
+                _ => assert_eq!(model.pop_max().as_ref(), heap.pop_max().map(|x| &*x)),
+            }
+
+            assert_eq!(model.len(), heap.len());
+            assert_eq!(model.peek_min(), heap.peek_min());
+            assert_eq!(model.peek_max(), heap.peek_max());
+            MinMaxHeap(heap.as_slice().to_vec(), Natural).assert_valid();
+        }
+    }
+
+    #[test]
+    fn sorts_descending() {
+        let mut buffer: Vec<i32> = (0 .. 100).map(|i| (i * 37) % 101).collect();
+        let mut expected = buffer.clone();
+        expected.sort_by(|a, b| b.cmp(a));
+
+        let mut heap = SliceMinMaxHeap::new(&mut buffer);
+        while heap.pop_min().is_some() { }
+        assert_eq!(100, heap.rest().len());
+
+        assert_eq!(expected, buffer);
+    }
+}
@@ -0,0 +1,229 @@
+use alloc::collections::{vec_deque, VecDeque};
+
+/// The minimum and maximum of the most recent `window` elements of a
+/// stream.
+///
+/// Pushing an element evicts the oldest one once the window is full.
+/// Rather than a heap with tombstones for the evicted elements, this
+/// keeps two monotonic queues of positions: one whose elements increase
+/// from the front, for the minimum, and one whose elements decrease, for
+/// the maximum. Each element enters and leaves each queue at most once.
+///
+/// # Examples
+///
+/// ```
+/// use min_max_heap::SlidingWindowMinMax;
+///
+/// let mut window = SlidingWindowMinMax::new(3);
+/// window.push(4);
+/// window.push(1);
+/// window.push(7);
+/// assert_eq!(window.peek_min(), Some(&1));
+/// assert_eq!(window.peek_max(), Some(&7));
+///
+/// assert_eq!(window.push(5), Some(4));
+/// assert_eq!(window.push(6), Some(1));
+/// assert_eq!(window.peek_min(), Some(&5));
+/// ```
+#[derive(Clone, Debug)]
+pub struct SlidingWindowMinMax<T> {
+    window:  usize,
+    // The number of elements evicted so far, which is the position of
+    // the front of `values`.
+    evicted: usize,
+    values:  VecDeque<T>,
+    mins:    VecDeque<usize>,
+    maxes:   VecDeque<usize>,
+}
+
+impl<T> SlidingWindowMinMax<T> {
+    /// Creates a new, empty window that retains the most recent
+    /// `window` elements.
+    ///
+    /// *O*(1).
+    pub fn new(window: usize) -> Self {
+        SlidingWindowMinMax {
+            window,
+            evicted: 0,
+            values:  VecDeque::new(),
+            mins:    VecDeque::new(),
+            maxes:   VecDeque::new(),
+        }
+    }
+
+    /// The maximum number of elements retained.
+    ///
+    /// *O*(1).
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// The number of elements currently in the window.
+    ///
+    /// *O*(1).
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Is the window empty?
+    ///
+    /// *O*(1).
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Drops all elements from the window.
+    ///
+    /// *O*(*n*).
+    pub fn clear(&mut self) {
+        self.evicted += self.values.len();
+        self.values.clear();
+        self.mins.clear();
+        self.maxes.clear();
+    }
+
+    /// Returns an iterator over the elements in the window, from oldest
+    /// to newest.
+    ///
+    /// *O*(1) on creation, and *O*(1) for each `next()` operation.
+    pub fn iter(&self) -> vec_deque::Iter<'_, T> {
+        self.values.iter()
+    }
+
+    fn get(&self, pos: usize) -> &T {
+        &self.values[pos - self.evicted]
+    }
+
+    fn evict(&mut self) -> Option<T> {
+        let result = self.values.pop_front()?;
+
+        if self.mins.front() == Some(&self.evicted) {
+            self.mins.pop_front();
+        }
+        if self.maxes.front() == Some(&self.evicted) {
+            self.maxes.pop_front();
+        }
+
+        self.evicted += 1;
+        Some(result)
+    }
+}
+
+impl<T: Ord> SlidingWindowMinMax<T> {
+    /// Adds an element to the window, evicting and returning the oldest
+    /// element if the window was full.
+    ///
+    /// Amortized *O*(1).
+    pub fn push(&mut self, element: T) -> Option<T> {
+        if self.window == 0 {
+            return Some(element);
+        }
+
+        let evicted = if self.values.len() == self.window {
+            self.evict()
+        } else {
+            None
+        };
+
+        while let Some(&pos) = self.mins.back() {
+            if *self.get(pos) > element {
+                self.mins.pop_back();
+            } else {
+                break;
+            }
+        }
+
+        while let Some(&pos) = self.maxes.back() {
+            if *self.get(pos) < element {
+                self.maxes.pop_back();
+            } else {
+                break;
+            }
+        }
+
+        let pos = self.evicted + self.values.len();
+        self.values.push_back(element);
+        self.mins.push_back(pos);
+        self.maxes.push_back(pos);
+
+        evicted
+    }
+
+    /// Removes the oldest element, if any.
+    ///
+    /// *O*(1).
+    pub fn pop_oldest(&mut self) -> Option<T> {
+        self.evict()
+    }
+
+    /// Gets a reference to the minimum element in the window, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_min(&self) -> Option<&T> {
+        self.mins.front().map(|&pos| self.get(pos))
+    }
+
+    /// Gets a reference to the maximum element in the window, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_max(&self) -> Option<&T> {
+        self.maxes.front().map(|&pos| self.get(pos))
+    }
+}
+
+impl<T: Ord> Extend<T> for SlidingWindowMinMax<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for element in iter {
+            self.push(element);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate rand;
+
+    use super::*;
+    use self::rand::Rng;
+
+    #[test]
+    fn random() {
+        let mut rng = rand::thread_rng();
+
+        for window in 0 .. 10 {
+            let mut sliding = SlidingWindowMinMax::new(window);
+            let mut naive = VecDeque::new();
+
+            for _ in 0 .. 200 {
+                let x = rng.gen_range(0 .. 20);
+
+                naive.push_back(x);
+                let expected = if naive.len() > window { naive.pop_front() } else { None };
+                assert_eq!(expected, sliding.push(x));
+
+                assert_eq!(naive.iter().min(), sliding.peek_min());
+                assert_eq!(naive.iter().max(), sliding.peek_max());
+                assert!(naive.iter().eq(sliding.iter()));
+
+                if rng.gen_range(0 .. 10) == 0 {
+                    assert_eq!(naive.pop_front(), sliding.pop_oldest());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn clear() {
+        let mut sliding = SlidingWindowMinMax::new(2);
+        sliding.extend(vec![3, 1, 2]);
+        assert_eq!(Some(&1), sliding.peek_min());
+
+        sliding.clear();
+        assert!(sliding.is_empty());
+        assert_eq!(None, sliding.peek_max());
+
+        sliding.push(5);
+        assert_eq!(Some(&5), sliding.peek_min());
+        assert_eq!(Some(&5), sliding.peek_max());
+    }
+}
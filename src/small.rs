@@ -0,0 +1,283 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FromIterator;
+
+use smallvec::{Array, SmallVec};
+
+use super::{Compare, DoubleEndedHeap, Iter, MinMaxHeap, Natural, find_max_slice, push_slice,
+            rebuild_slice, trickle_down_extreme_slice};
+
+/// A min-max-heap that stores up to a fixed number of elements inline,
+/// allocating only when it grows past them.
+///
+/// The inline storage is an array type `A`, as for [`SmallVec`]: a
+/// `SmallMinMaxHeap<[T; 16]>` holds up to 16 `T`s without allocating.
+/// Keeping many small heaps this way saves an allocation per heap, at the
+/// cost of making each heap as large as its inline array.
+///
+/// Requires the `smallvec` feature.
+///
+/// # Examples
+///
+/// ```
+/// use min_max_heap::SmallMinMaxHeap;
+///
+/// let mut heap = SmallMinMaxHeap::<[u32; 4]>::new();
+/// heap.extend(vec![5, 2, 8]);
+/// assert!(!heap.spilled());
+///
+/// heap.extend(vec![1, 9]);
+/// assert!(heap.spilled());
+///
+/// assert_eq!(heap.pop_min(), Some(1));
+/// assert_eq!(heap.pop_max(), Some(9));
+/// ```
+///
+/// [`SmallVec`]: https://docs.rs/smallvec/1/smallvec/struct.SmallVec.html
+pub struct SmallMinMaxHeap<A: Array, C = Natural> {
+    data: SmallVec<A>,
+    cmp:  C,
+}
+
+impl<A: Array> Default for SmallMinMaxHeap<A>
+    where A::Item: Ord
+{
+    fn default() -> Self {
+        SmallMinMaxHeap::new()
+    }
+}
+
+impl<A: Array> SmallMinMaxHeap<A>
+    where A::Item: Ord
+{
+    /// Creates a new, empty `SmallMinMaxHeap`.
+    ///
+    /// *O*(1).
+    pub fn new() -> Self {
+        SmallMinMaxHeap::with_comparator(Natural)
+    }
+}
+
+impl<A: Array, C> SmallMinMaxHeap<A, C> {
+    /// Creates a new, empty `SmallMinMaxHeap` ordered by `cmp`.
+    ///
+    /// *O*(1).
+    pub fn with_comparator(cmp: C) -> Self {
+        SmallMinMaxHeap {
+            data: SmallVec::new(),
+            cmp,
+        }
+    }
+
+    /// The number of elements in the heap.
+    ///
+    /// *O*(1).
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Is the heap empty?
+    ///
+    /// *O*(1).
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// The number of elements the heap can hold without allocating.
+    ///
+    /// *O*(1).
+    pub fn inline_size(&self) -> usize {
+        self.data.inline_size()
+    }
+
+    /// Has the heap moved its elements to an allocation?
+    ///
+    /// *O*(1).
+    pub fn spilled(&self) -> bool {
+        self.data.spilled()
+    }
+
+    /// Drops all elements from the heap.
+    ///
+    /// *O*(*n*).
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// Moves the elements back inline if they fit and the heap has
+    /// spilled, or otherwise shrinks the allocation to fit.
+    ///
+    /// *O*(*n*).
+    pub fn shrink_to_fit(&mut self) {
+        self.data.shrink_to_fit();
+    }
+
+    /// Returns an iterator over the elements of the heap in arbitrary
+    /// order.
+    ///
+    /// *O*(1) on creation, and *O*(1) for each `next()` operation.
+    pub fn iter(&self) -> Iter<'_, A::Item> {
+        Iter(self.data.iter())
+    }
+
+    /// Converts into a `MinMaxHeap`, which allocates if the elements
+    /// were inline.
+    ///
+    /// *O*(1) if the heap has spilled, and *O*(*n*) otherwise.
+    pub fn into_heap(self) -> MinMaxHeap<A::Item, C> {
+        MinMaxHeap(self.data.into_vec(), self.cmp)
+    }
+}
+
+impl<A: Array, C: Compare<A::Item>> SmallMinMaxHeap<A, C> {
+    /// Adds an element to the heap.
+    ///
+    /// Amortized *O*(log *n*); *O*(*n*) when it spills or the allocation
+    /// needs to grow.
+    pub fn push(&mut self, element: A::Item) {
+        self.data.push(element);
+        push_slice(&mut self.data, &self.cmp);
+    }
+
+    /// Gets a reference to the minimum element, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_min(&self) -> Option<&A::Item> {
+        self.data.first()
+    }
+
+    /// Gets a reference to the maximum element, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_max(&self) -> Option<&A::Item> {
+        find_max_slice(&self.data, &self.cmp).map(|max| &self.data[max])
+    }
+
+    /// Removes the minimum element, if any.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_min(&mut self) -> Option<A::Item> {
+        if self.data.is_empty() {
+            None
+        } else {
+            Some(self.remove(0))
+        }
+    }
+
+    /// Removes the maximum element, if any.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_max(&mut self) -> Option<A::Item> {
+        let max = find_max_slice(&self.data, &self.cmp)?;
+        Some(self.remove(max))
+    }
+
+    /// Returns an ascending (sorted) vector.
+    ///
+    /// *O*(*n* log *n*).
+    pub fn into_vec_asc(self) -> Vec<A::Item> {
+        self.into_heap().into_vec_asc()
+    }
+
+    /// Returns a descending (sorted) vector.
+    ///
+    /// *O*(*n* log *n*).
+    pub fn into_vec_desc(self) -> Vec<A::Item> {
+        self.into_heap().into_vec_desc()
+    }
+
+    /// Removes the element at `pos`, the minimum or the maximum.
+    fn remove(&mut self, pos: usize) -> A::Item {
+        let result = self.data.swap_remove(pos);
+        trickle_down_extreme_slice(&mut self.data, pos, &self.cmp);
+        result
+    }
+}
+
+impl<A: Array, C: Compare<A::Item>> DoubleEndedHeap for SmallMinMaxHeap<A, C> {
+    type Item = A::Item;
+
+    fn push(&mut self, element: A::Item) { SmallMinMaxHeap::push(self, element) }
+    fn peek_min(&self) -> Option<&A::Item> { SmallMinMaxHeap::peek_min(self) }
+    fn peek_max(&self) -> Option<&A::Item> { SmallMinMaxHeap::peek_max(self) }
+    fn pop_min(&mut self) -> Option<A::Item> { SmallMinMaxHeap::pop_min(self) }
+    fn pop_max(&mut self) -> Option<A::Item> { SmallMinMaxHeap::pop_max(self) }
+    fn len(&self) -> usize { SmallMinMaxHeap::len(self) }
+}
+
+impl<A: Array, C: Compare<A::Item>> Extend<A::Item> for SmallMinMaxHeap<A, C> {
+    fn extend<I: IntoIterator<Item = A::Item>>(&mut self, iter: I) {
+        for element in iter {
+            self.push(element);
+        }
+    }
+}
+
+impl<A: Array> FromIterator<A::Item> for SmallMinMaxHeap<A>
+    where A::Item: Ord
+{
+    fn from_iter<I>(iter: I) -> Self
+            where I: IntoIterator<Item = A::Item> {
+        let mut data: SmallVec<A> = iter.into_iter().collect();
+        rebuild_slice(&mut data, &Natural);
+        SmallMinMaxHeap { data, cmp: Natural }
+    }
+}
+
+impl<A: Array, C: Clone> Clone for SmallMinMaxHeap<A, C>
+    where A::Item: Clone
+{
+    fn clone(&self) -> Self {
+        SmallMinMaxHeap {
+            data: self.data.clone(),
+            cmp:  self.cmp.clone(),
+        }
+    }
+}
+
+impl<A: Array, C> fmt::Debug for SmallMinMaxHeap<A, C>
+    where A::Item: fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SmallMinMaxHeap")
+         .field(&self.data.as_slice())
+         .finish()
+    }
+}
+
+impl<'a, A: Array, C> IntoIterator for &'a SmallMinMaxHeap<A, C> {
+    type Item = &'a A::Item;
+    type IntoIter = Iter<'a, A::Item>;
+    fn into_iter(self) -> Self::IntoIter { self.iter() }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::test_model;
+
+    #[test]
+    fn random() {
+        let mut heap = SmallMinMaxHeap::<[u32; 8]>::new();
+        test_model::random(&mut heap, 3000, 12, |heap| {
+            MinMaxHeap(heap.data.to_vec(), Natural).assert_valid();
+        });
+    }
+
+    #[test]
+    fn collect() {
+        let heap: SmallMinMaxHeap<[i32; 16]> = (0 .. 10).map(|i| (i * 7) % 10).collect();
+        assert!(!heap.spilled());
+        assert_eq!((0 .. 10).collect::<Vec<_>>(), heap.clone().into_vec_asc());
+
+        let mut heap = heap;
+        heap.extend(10 .. 20);
+        assert!(heap.spilled());
+        for _ in 0 .. 10 {
+            heap.pop_max();
+        }
+        heap.shrink_to_fit();
+        assert!(!heap.spilled());
+        assert_eq!((0 .. 10).rev().collect::<Vec<_>>(), heap.into_vec_desc());
+    }
+}
@@ -0,0 +1,85 @@
+use core::iter::TrustedLen;
+
+use alloc::vec;
+
+use super::{Compare, MinMaxHeap};
+
+// Specializations of `Extend` for the `unstable` feature, after the ones
+// for `BinaryHeap` in the standard library. Room for a source whose
+// length is known exactly is reserved once, and `Vec` then moves its
+// elements in without checking capacity; a `vec::IntoIter` is turned
+// back into a `Vec`, which reuses its buffer, and handed to
+// `extend_from_vec`. Any other source goes through the usual path.
+// (`FromIterator` needs nothing here: it collects into a `Vec`, which has
+// specializations of its own, and heapifies once already.)
+
+pub trait SpecExtend<I> {
+    fn spec_extend(&mut self, iter: I);
+}
+
+impl<T, C: Compare<T>, I: Iterator<Item = T>> SpecExtend<I> for MinMaxHeap<T, C> {
+    default fn spec_extend(&mut self, iter: I) {
+        self.extend_desugared(iter);
+    }
+}
+
+impl<T, C: Compare<T>, I: TrustedLen<Item = T>> SpecExtend<I> for MinMaxHeap<T, C> {
+    default fn spec_extend(&mut self, iter: I) {
+        if let (_, Some(additional)) = iter.size_hint() {
+            self.0.reserve(additional);
+        }
+        self.extend_desugared(iter);
+    }
+}
+
+impl<T, C: Compare<T>> SpecExtend<vec::IntoIter<T>> for MinMaxHeap<T, C> {
+    fn spec_extend(&mut self, iter: vec::IntoIter<T>) {
+        self.extend_from_vec(iter.collect());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn trusted_len() {
+        let mut h: MinMaxHeap<u32> = MinMaxHeap::new();
+        h.extend((0 .. 50).map(|i| i * 7 % 50));
+        assert_eq!(50, h.capacity());
+        h.assert_valid();
+
+        // A short tail on a large heap is bubbled up instead.
+        h.extend(50 .. 52);
+        h.extend(Some(100));
+        h.assert_valid();
+        assert_eq!(Some(&100), h.peek_max());
+        assert_eq!((0 .. 52).chain(Some(100)).collect::<Vec<_>>(), h.into_vec_asc());
+    }
+
+    #[test]
+    fn vec_into_iter() {
+        let v = vec![5, 3, 9, 1, 7];
+        let ptr = v.as_ptr();
+
+        let mut h: MinMaxHeap<u32> = MinMaxHeap::new();
+        h.extend(v);
+        h.assert_valid();
+
+        let v = h.into_vec();
+        assert_eq!(ptr, v.as_ptr());
+
+        let mut h: MinMaxHeap<u32> = v.into();
+        h.extend(vec![0, 10]);
+        assert_eq!(vec![0, 1, 3, 5, 7, 9, 10], h.into_vec_asc());
+    }
+
+    #[test]
+    fn not_trusted_len() {
+        let mut h: MinMaxHeap<u32> = MinMaxHeap::new();
+        h.extend((0 .. 20).filter(|i| i % 3 == 0));
+        h.assert_valid();
+        assert_eq!(vec![0, 3, 6, 9, 12, 15, 18], h.into_vec_asc());
+    }
+}
@@ -0,0 +1,441 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::iter::FromIterator;
+
+use super::index::HeapIndex;
+use super::{Compare, Iter, KeyComparator, MinMaxHeap, Natural};
+
+/// A min-max-heap that breaks ties between equal elements by insertion
+/// order.
+///
+/// Each element is stamped with a sequence number when it is pushed, and
+/// ties between equal elements go to the one with the earlier stamp, at
+/// either end. Equal elements therefore come out of both [`pop_min`] and
+/// [`pop_max`] first-in, first-out, as a scheduler popping either end
+/// expects.
+///
+/// # Examples
+///
+/// ```
+/// use min_max_heap::StableMinMaxHeap;
+///
+/// let mut heap = StableMinMaxHeap::new_by_key(|task: &(u8, &str)| task.0);
+/// heap.push((1, "first"));
+/// heap.push((5, "urgent"));
+/// heap.push((1, "second"));
+/// heap.push((1, "third"));
+/// heap.push((5, "also urgent"));
+///
+/// assert_eq!(heap.pop_min(), Some((1, "first")));
+/// assert_eq!(heap.pop_min(), Some((1, "second")));
+/// assert_eq!(heap.pop_max(), Some((5, "urgent")));
+/// assert_eq!(heap.pop_max(), Some((5, "also urgent")));
+/// ```
+///
+/// [`pop_min`]: struct.StableMinMaxHeap.html#method.pop_min
+/// [`pop_max`]: struct.StableMinMaxHeap.html#method.pop_max
+#[derive(Clone)]
+pub struct StableMinMaxHeap<T, C = Natural> {
+    heap: MinMaxHeap<Stamped<T>, Stable<C>>,
+    // The stamp of the next element pushed. A `u64` cannot run out in
+    // practice, even at one push per nanosecond.
+    next: u64,
+}
+
+#[derive(Clone)]
+struct Stamped<T> {
+    element: T,
+    stamp:   u64,
+}
+
+// Orders by element and then by stamp, so that no two elements are
+// equal and the heap pops the earliest of equal minima first. Equal
+// maxima would come out last-in, first-out, so `find_max` looks past
+// the heap's maximum for the earliest of them.
+#[derive(Clone)]
+struct Stable<C>(C);
+
+impl<T, C: Compare<T>> Compare<Stamped<T>> for Stable<C> {
+    fn compare(&self, a: &Stamped<T>, b: &Stamped<T>) -> Ordering {
+        self.0.compare(&a.element, &b.element)
+            .then(a.stamp.cmp(&b.stamp))
+    }
+}
+
+impl<T: fmt::Debug, C> fmt::Debug for StableMinMaxHeap<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("StableMinMaxHeap")
+         .field(&self.iter().collect::<Vec<_>>())
+         .finish()
+    }
+}
+
+impl<T: Ord> Default for StableMinMaxHeap<T> {
+    fn default() -> Self {
+        StableMinMaxHeap::new()
+    }
+}
+
+impl<T: Ord> StableMinMaxHeap<T> {
+    /// Creates a new, empty `StableMinMaxHeap`.
+    ///
+    /// *O*(1).
+    pub fn new() -> Self {
+        StableMinMaxHeap::with_comparator(Natural)
+    }
+}
+
+impl<T, K, F> StableMinMaxHeap<T, KeyComparator<F>>
+where
+    K: Ord,
+    F: Fn(&T) -> K,
+{
+    /// Creates a new, empty `StableMinMaxHeap` that orders elements by
+    /// the key that `key` extracts from them.
+    ///
+    /// *O*(1).
+    pub fn new_by_key(key: F) -> Self {
+        StableMinMaxHeap::with_comparator(KeyComparator(key))
+    }
+}
+
+impl<T, C> StableMinMaxHeap<T, C> {
+    /// Creates a new, empty `StableMinMaxHeap` ordered by `cmp`, with
+    /// ties broken by insertion order.
+    ///
+    /// *O*(1).
+    pub fn with_comparator(cmp: C) -> Self {
+        StableMinMaxHeap {
+            heap: MinMaxHeap::with_comparator(Stable(cmp)),
+            next: 0,
+        }
+    }
+
+    /// The number of elements in the heap.
+    ///
+    /// *O*(1).
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Is the heap empty?
+    ///
+    /// *O*(1).
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Drops all elements from the heap.
+    ///
+    /// *O*(*n*).
+    pub fn clear(&mut self) {
+        self.heap.clear();
+    }
+
+    /// Returns an iterator over the elements of the heap in arbitrary
+    /// order.
+    ///
+    /// *O*(1) on creation, and *O*(1) for each `next()` operation.
+    pub fn iter(&self) -> StableIter<'_, T> {
+        StableIter(self.heap.iter())
+    }
+}
+
+impl<T, C: Compare<T>> StableMinMaxHeap<T, C> {
+    /// Adds an element to the heap, after any equal elements already in
+    /// it.
+    ///
+    /// *O*(log *n*).
+    pub fn push(&mut self, element: T) {
+        let stamp = self.next;
+        self.next += 1;
+
+        self.heap.push(Stamped { element, stamp });
+    }
+
+    /// Gets a reference to the minimum element, if any. Among equal
+    /// minima, this is the one pushed first.
+    ///
+    /// *O*(1).
+    pub fn peek_min(&self) -> Option<&T> {
+        self.heap.peek_min().map(|stamped| &stamped.element)
+    }
+
+    /// Gets a reference to the maximum element, if any. Among equal
+    /// maxima, this is the one pushed first.
+    ///
+    /// *O*(*k*), where *k* is the number of maxima equal to it.
+    pub fn peek_max(&self) -> Option<&T> {
+        self.find_max().map(|max| &self.heap.0[max].element)
+    }
+
+    /// Removes the minimum element, if any. Among equal minima, this is
+    /// the one pushed first.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_min(&mut self) -> Option<T> {
+        self.heap.pop_min().map(|stamped| stamped.element)
+    }
+
+    /// Removes the maximum element, if any. Among equal maxima, this is
+    /// the one pushed first.
+    ///
+    /// *O*(log *n* + *k*), where *k* is the number of maxima equal to
+    /// the one removed.
+    pub fn pop_max(&mut self) -> Option<T> {
+        self.find_max().map(|max| self.heap.swap_remove_index(max).element)
+    }
+
+    // A max-level element is at least as large as everything below it,
+    // so the elements equal to the maximum lie on max levels connected
+    // to the top, or are children of those, or are the root. This walks
+    // just those and picks the earliest.
+    fn find_max(&self) -> Option<usize> {
+        let MinMaxHeap(elements, Stable(cmp)) = &self.heap;
+        let max = self.heap.find_max()?;
+        let ties = |pos: usize| {
+            pos < elements.len()
+                && cmp.compares_eq(&elements[pos].element, &elements[max].element)
+        };
+        let earlier = |a: usize, b: usize| {
+            if elements[b].stamp < elements[a].stamp { b } else { a }
+        };
+
+        let mut best = if ties(0) { 0 } else { max };
+        let mut stack: Vec<usize> = (1 .. 3).filter(|&pos| ties(pos)).collect();
+        while let Some(pos) = stack.pop() {
+            best = earlier(best, pos);
+            for child in pos.child1() ..= pos.child2() {
+                if ties(child) {
+                    best = earlier(best, child);
+                }
+            }
+            stack.extend((pos.grandchild1() ..= pos.grandchild4())
+                             .filter(|&pos| ties(pos)));
+        }
+
+        Some(best)
+    }
+
+    /// Returns an ascending (sorted) vector, with equal elements in
+    /// insertion order.
+    ///
+    /// *O*(*n* log *n*).
+    pub fn into_vec_asc(self) -> Vec<T> {
+        self.into_vec_sorted(false)
+    }
+
+    /// Returns a descending (sorted) vector, with equal elements in
+    /// insertion order.
+    ///
+    /// *O*(*n* log *n*).
+    pub fn into_vec_desc(self) -> Vec<T> {
+        self.into_vec_sorted(true)
+    }
+
+    // Sorting the heap itself would put equal elements in reverse
+    // insertion order when descending, so this sorts by element and then
+    // by ascending stamp.
+    fn into_vec_sorted(self, descending: bool) -> Vec<T> {
+        let MinMaxHeap(mut elements, Stable(cmp)) = self.heap;
+        elements.sort_unstable_by(|a, b| {
+            let order = if descending {
+                cmp.compare(&b.element, &a.element)
+            } else {
+                cmp.compare(&a.element, &b.element)
+            };
+            order.then(a.stamp.cmp(&b.stamp))
+        });
+        elements.into_iter().map(|stamped| stamped.element).collect()
+    }
+}
+
+impl<T, C: Compare<T>> Extend<T> for StableMinMaxHeap<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for element in iter {
+            self.push(element);
+        }
+    }
+}
+
+impl<T: Ord> FromIterator<T> for StableMinMaxHeap<T> {
+    fn from_iter<I>(iter: I) -> Self
+            where I: IntoIterator<Item = T> {
+        // Stamp the elements in order and heapify them all at once.
+        let elements: Vec<_> = iter.into_iter()
+            .zip(0 ..)
+            .map(|(element, stamp)| Stamped { element, stamp })
+            .collect();
+
+        let heap = MinMaxHeap::from_vec_with_comparator(elements, Stable(Natural));
+
+        StableMinMaxHeap {
+            next: heap.len() as u64,
+            heap,
+        }
+    }
+}
+
+/// A borrowed iterator over the elements of a [`StableMinMaxHeap`] in
+/// arbitrary order.
+///
+/// This type is created with
+/// [`StableMinMaxHeap::iter`](struct.StableMinMaxHeap.html#method.iter).
+///
+/// [`StableMinMaxHeap`]: struct.StableMinMaxHeap.html
+pub struct StableIter<'a, T: 'a>(Iter<'a, Stamped<T>>);
+
+impl<'a, T> Iterator for StableIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|stamped| &stamped.element)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for StableIter<'a, T> { }
+
+#[cfg(test)]
+mod test {
+    extern crate rand;
+
+    use super::*;
+    use self::rand::Rng;
+
+    #[test]
+    fn fifo_ties() {
+        let mut rng = rand::thread_rng();
+        let mut heap = StableMinMaxHeap::new_by_key(|pair: &(u32, usize)| pair.0);
+        let mut model = Vec::new();
+
+        for i in 0 .. 500 {
+            let pair = (rng.gen_range(0 .. 10), i);
+            heap.push(pair);
+            model.push(pair);
+        }
+
+        // `sort_by_key` is stable, so it is the expected order.
+        model.sort_by_key(|pair| pair.0);
+        assert_eq!(model, heap.clone().into_vec_asc());
+
+        for _ in 0 .. 100 {
+            assert_eq!(Some(model.remove(0)), heap.pop_min());
+            // The first of the largest elements, in insertion order.
+            let last = model.last().unwrap().0;
+            let first_max = model.iter().position(|pair| pair.0 == last).unwrap();
+            assert_eq!(Some(model.remove(first_max)), heap.pop_max());
+        }
+
+        assert_eq!(model.len(), heap.len());
+        model.sort_by_key(|pair| core::cmp::Reverse(pair.0));
+        assert_eq!(model, heap.into_vec_desc());
+    }
+
+    #[test]
+    fn fifo_ties_at_max() {
+        let mut heap = StableMinMaxHeap::new_by_key(|pair: &(u32, usize)| pair.0);
+        for i in 0 .. 50 {
+            heap.push((i as u32 % 3, i));
+        }
+
+        for i in (2 .. 50).step_by(3) {
+            assert_eq!(Some((2, i)), heap.pop_max());
+        }
+        for i in (1 .. 50).step_by(3) {
+            assert_eq!(Some((1, i)), heap.pop_max());
+        }
+        for i in (0 .. 50).step_by(3) {
+            assert_eq!(Some((0, i)), heap.pop_max());
+        }
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn fifo_ties_random() {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0 .. 50 {
+            let mut heap = StableMinMaxHeap::new_by_key(|pair: &(u32, usize)| pair.0);
+            // In insertion order, so the first of each key is the oldest.
+            let mut model: Vec<(u32, usize)> = Vec::new();
+
+            for i in 0 .. 300 {
+                match rng.gen_range(0 .. 4) {
+                    0 | 1 => {
+                        let pair = (rng.gen_range(0 .. 4), i);
+                        heap.push(pair);
+                        model.push(pair);
+                    }
+                    op => {
+                        let key = if op == 2 {
+                            model.iter().map(|pair| pair.0).min()
+                        } else {
+                            model.iter().map(|pair| pair.0).max()
+                        };
+                        let expected = key.map(|key| {
+                            model.remove(model.iter().position(|pair| pair.0 == key).unwrap())
+                        });
+                        let actual = if op == 2 { heap.pop_min() } else { heap.pop_max() };
+                        assert_eq!(expected, actual);
+                    }
+                }
+
+                heap.heap.assert_valid();
+                assert_eq!(model.len(), heap.len());
+            }
+        }
+    }
+
+    #[test]
+    fn fifo_ties_collected() {
+        let mut heap: StableMinMaxHeap<Tie> =
+            "abcdefghij".chars().enumerate().map(|(i, c)| Tie(i as u32 / 5, c)).collect();
+
+        let maxes: Vec<char> = (0 .. 5).map(|_| heap.pop_max().unwrap().1).collect();
+        assert_eq!("fghij".chars().collect::<Vec<_>>(), maxes);
+        let mins: Vec<char> = (0 .. 5).map(|_| heap.pop_min().unwrap().1).collect();
+        assert_eq!("abcde".chars().collect::<Vec<_>>(), mins);
+    }
+
+    #[test]
+    fn natural() {
+        let heap: StableMinMaxHeap<_> = vec![3, 1, 2].into_iter().collect();
+        assert_eq!(Some(&1), heap.peek_min());
+        assert_eq!(Some(&3), heap.peek_max());
+        assert_eq!(3, heap.iter().count());
+        assert_eq!(vec![1, 2, 3], heap.into_vec_asc());
+    }
+
+    // Equal whenever their first fields are, whatever the second.
+    #[derive(Debug)]
+    struct Tie(u32, char);
+
+    impl PartialEq for Tie {
+        fn eq(&self, other: &Self) -> bool { self.0 == other.0 }
+    }
+
+    impl Eq for Tie { }
+
+    impl PartialOrd for Tie {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+    }
+
+    impl Ord for Tie {
+        fn cmp(&self, other: &Self) -> Ordering { self.0.cmp(&other.0) }
+    }
+
+    #[test]
+    fn collect_keeps_order() {
+        let mut heap: StableMinMaxHeap<Tie> =
+            "abcdefgh".chars().enumerate().map(|(i, c)| Tie(i as u32 % 2, c)).collect();
+        heap.push(Tie(0, 'i'));
+
+        let order: Vec<char> = heap.into_vec_asc().into_iter().map(|tie| tie.1).collect();
+        assert_eq!("acegibdfh".chars().collect::<Vec<_>>(), order);
+    }
+}
@@ -0,0 +1,219 @@
+// Instrumentation for the sifting code. The recording functions below
+// are called from `hole.rs` and `safe_hole.rs` whether or not the
+// `stats` feature is enabled; without it, they do nothing.
+
+#[cfg(feature = "stats")]
+use std::cell::Cell;
+
+#[cfg(feature = "stats")]
+use super::index::*;
+
+/// Counts of the work that heap operations have done on the current
+/// thread.
+///
+/// The counts cover the sifting that restores the heap after it changes,
+/// as in [`push`], [`pop_min`], [`pop_max`], and rebuilding, along with
+/// finding the maximum. They accumulate per thread, across all heaps,
+/// until [`reset`]; to count a single operation, use [`measure`].
+///
+/// Requires the `stats` feature.
+///
+/// # Examples
+///
+/// ```
+/// use min_max_heap::{HeapStats, MinMaxHeap};
+///
+/// let mut heap: MinMaxHeap<u32> = (0 .. 1000).collect();
+///
+/// let (max, stats) = HeapStats::measure(|| heap.pop_max());
+/// assert_eq!(max, Some(999));
+/// assert_eq!(stats.sifts, 1);
+/// assert!(stats.comparisons > 0);
+/// assert!(stats.sift_levels <= 10);
+/// ```
+///
+/// [`push`]: struct.MinMaxHeap.html#method.push
+/// [`pop_min`]: struct.MinMaxHeap.html#method.pop_min
+/// [`pop_max`]: struct.MinMaxHeap.html#method.pop_max
+/// [`reset`]: struct.HeapStats.html#method.reset
+/// [`measure`]: struct.HeapStats.html#method.measure
+#[cfg(feature = "stats")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct HeapStats {
+    /// Comparisons between elements.
+    pub comparisons: u64,
+    /// Times that two elements changed places, either by a swap or by
+    /// moving an element into the gap left by the one being sifted.
+    pub swaps: u64,
+    /// Sifts started, each of which moves one element up or down.
+    pub sifts: u64,
+    /// Levels of the tree that sifted elements traveled, in total.
+    /// Dividing by `sifts` gives the average sift depth.
+    pub sift_levels: u64,
+}
+
+#[cfg(feature = "stats")]
+std::thread_local! {
+    static STATS: Cell<HeapStats> = Cell::new(HeapStats::default());
+}
+
+#[cfg(feature = "stats")]
+impl HeapStats {
+    /// The counts for the current thread since it started or last
+    /// called [`reset`].
+    ///
+    /// [`reset`]: struct.HeapStats.html#method.reset
+    pub fn current() -> Self {
+        STATS.with(Cell::get)
+    }
+
+    /// Sets the current thread’s counts to zero.
+    pub fn reset() {
+        STATS.with(|stats| stats.set(HeapStats::default()));
+    }
+
+    /// Calls `f`, returning its result along with the counts for the
+    /// heap operations it did. This leaves the thread’s running counts,
+    /// which include `f`’s, intact.
+    ///
+    /// If `f` calls [`reset`], its counts from before the reset are lost,
+    /// so the result may undercount, stopping at zero.
+    ///
+    /// [`reset`]: struct.HeapStats.html#method.reset
+    pub fn measure<R, F: FnOnce() -> R>(f: F) -> (R, Self) {
+        let before = Self::current();
+        let result = f();
+        let after = Self::current();
+
+        (result, HeapStats {
+            comparisons: after.comparisons.saturating_sub(before.comparisons),
+            swaps:       after.swaps.saturating_sub(before.swaps),
+            sifts:       after.sifts.saturating_sub(before.sifts),
+            sift_levels: after.sift_levels.saturating_sub(before.sift_levels),
+        })
+    }
+}
+
+#[cfg(feature = "stats")]
+fn record<F: FnOnce(&mut HeapStats)>(f: F) {
+    STATS.with(|cell| {
+        let mut stats = cell.get();
+        f(&mut stats);
+        cell.set(stats);
+    });
+}
+
+/// Records a comparison, returning its result.
+#[cfg(feature = "stats")]
+#[inline]
+pub fn compared(result: bool) -> bool {
+    record(|stats| stats.comparisons += 1);
+    result
+}
+
+/// Records the start of a sift.
+#[cfg(feature = "stats")]
+#[inline]
+pub fn sift() {
+    record(|stats| stats.sifts += 1);
+}
+
+/// Records a swap that leaves the sifted element in place.
+#[cfg(feature = "stats")]
+#[inline]
+pub fn swap() {
+    record(|stats| stats.swaps += 1);
+}
+
+/// Records moving the sifted element from `from` to `to`.
+#[cfg(feature = "stats")]
+#[inline]
+pub fn moved(from: usize, to: usize) {
+    let levels = if from.has_parent() && from.parent() == to ||
+                    to.has_parent() && to.parent() == from { 1 } else { 2 };
+    record(|stats| {
+        stats.swaps += 1;
+        stats.sift_levels += levels;
+    });
+}
+
+#[cfg(not(feature = "stats"))]
+#[inline(always)]
+pub fn compared(result: bool) -> bool {
+    result
+}
+
+#[cfg(not(feature = "stats"))]
+#[inline(always)]
+pub fn sift() { }
+
+#[cfg(not(feature = "stats"))]
+#[inline(always)]
+pub fn swap() { }
+
+#[cfg(not(feature = "stats"))]
+#[inline(always)]
+pub fn moved(_from: usize, _to: usize) { }
+
+#[cfg(all(test, feature = "stats"))]
+mod test {
+    use super::*;
+    use super::super::MinMaxHeap;
+
+    #[test]
+    fn push() {
+        let mut heap = MinMaxHeap::new();
+        heap.push(1);
+
+        let ((), stats) = HeapStats::measure(|| heap.push(0));
+        assert_eq!(HeapStats { comparisons: 1, swaps: 1, sifts: 1, sift_levels: 1 }, stats);
+
+        let ((), stats) = HeapStats::measure(|| heap.push(2));
+        assert_eq!(HeapStats { comparisons: 1, swaps: 0, sifts: 1, sift_levels: 0 }, stats);
+    }
+
+    #[test]
+    fn measure_across_reset() {
+        let mut heap: MinMaxHeap<u32> = (0 .. 100).collect();
+        for _ in 0 .. 10 {
+            heap.pop_min();
+        }
+
+        let ((), stats) = HeapStats::measure(|| {
+            heap.pop_min();
+            HeapStats::reset();
+            heap.pop_max();
+        });
+        assert!(stats.sifts <= 1);
+        assert_eq!(1, HeapStats::current().sifts);
+    }
+
+    #[test]
+    fn pop_depth() {
+        let mut heap: MinMaxHeap<u32> = (0 .. 1 << 12).collect();
+
+        HeapStats::reset();
+        for _ in 0 .. 100 {
+            heap.pop_min();
+            heap.pop_max();
+        }
+
+        let stats = HeapStats::current();
+        assert_eq!(200, stats.sifts);
+        assert!(stats.sift_levels <= 200 * 12);
+        assert!(stats.swaps >= stats.sift_levels / 2);
+    }
+
+    #[test]
+    fn per_thread() {
+        let heap: MinMaxHeap<u32> = (0 .. 100).map(|i| i * 37 % 100).collect();
+        assert!(HeapStats::current().comparisons > 0);
+
+        std::thread::spawn(move || {
+            assert_eq!(HeapStats::default(), HeapStats::current());
+            heap.peek_max();
+            assert_eq!(1, HeapStats::current().comparisons);
+        }).join().unwrap();
+    }
+}
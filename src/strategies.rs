@@ -0,0 +1,67 @@
+//! Strategies for generating heaps in `proptest` property tests.
+//!
+//! Requires the `proptest` feature.
+
+use alloc::vec::Vec;
+
+use proptest::collection::{vec, SizeRange};
+use proptest::strategy::Strategy;
+
+use super::MinMaxHeap;
+
+/// Creates a strategy that generates heaps of elements drawn from
+/// `element`, with a number of elements in `size`.
+///
+/// The heaps are generated as vectors and then heapified, so they are
+/// always valid, and they shrink as vectors do: by removing elements and
+/// by shrinking the elements that remain.
+///
+/// # Examples
+///
+/// ```
+/// use min_max_heap::strategies::min_max_heap;
+/// use proptest::prelude::*;
+/// use proptest::test_runner::TestRunner;
+///
+/// let mut runner = TestRunner::default();
+/// runner.run(&min_max_heap(any::<i32>(), 1 .. 100), |heap| {
+///     prop_assert!(heap.peek_min() <= heap.peek_max());
+///     Ok(())
+/// }).unwrap();
+/// ```
+pub fn min_max_heap<S>(element: S, size: impl Into<SizeRange>)
+    -> impl Strategy<Value = MinMaxHeap<S::Value>>
+    where S: Strategy,
+          S::Value: Ord
+{
+    vec(element, size).prop_map(|vec: Vec<S::Value>| MinMaxHeap::from(vec))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+    use proptest::strategy::ValueTree;
+    use proptest::test_runner::TestRunner;
+
+    proptest! {
+        #[test]
+        fn generates_valid(heap in min_max_heap(any::<u16>(), 0 .. 200)) {
+            heap.assert_valid();
+            prop_assert!(heap.len() < 200);
+        }
+    }
+
+    #[test]
+    fn shrinks_valid() {
+        let mut runner = TestRunner::deterministic();
+        let mut tree = min_max_heap(0 .. 1000u32, 10 .. 50)
+            .new_tree(&mut runner).unwrap();
+
+        while tree.simplify() {
+            let heap = tree.current();
+            heap.assert_valid();
+            assert!(heap.len() >= 10);
+        }
+    }
+}
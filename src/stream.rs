@@ -0,0 +1,189 @@
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use futures_core::stream::{FusedStream, Stream};
+
+use super::{Compare, MinMaxHeap, Natural};
+
+/// How many elements a stream yields before letting other tasks run.
+const BUDGET: usize = 64;
+
+impl<T, C: Compare<T>> MinMaxHeap<T, C> {
+    /// Returns a stream that yields the elements of the heap in
+    /// ascending (min-first) order.
+    ///
+    /// Elements are ready at once, but the stream returns `Pending`
+    /// (after waking its task) every 64 elements, so that draining a
+    /// large heap does not keep other tasks on the executor from
+    /// running.
+    ///
+    /// Requires the `stream` feature.
+    ///
+    /// *O*(1) on creation, and *O*(log *n*) for each element.
+    pub fn into_stream_asc(self) -> IntoStreamAsc<T, C> {
+        IntoStreamAsc { heap: self, budget: BUDGET }
+    }
+
+    /// Returns a stream that yields the elements of the heap in
+    /// descending (max-first) order.
+    ///
+    /// Elements are ready at once, but the stream returns `Pending`
+    /// (after waking its task) every 64 elements, so that draining a
+    /// large heap does not keep other tasks on the executor from
+    /// running.
+    ///
+    /// Requires the `stream` feature.
+    ///
+    /// *O*(1) on creation, and *O*(log *n*) for each element.
+    pub fn into_stream_desc(self) -> IntoStreamDesc<T, C> {
+        IntoStreamDesc { heap: self, budget: BUDGET }
+    }
+}
+
+/// A stream that yields the elements of a min-max-heap in ascending
+/// (min-first) order.
+///
+/// This type is created with
+/// [`MinMaxHeap::into_stream_asc`](struct.MinMaxHeap.html#method.into_stream_asc).
+#[derive(Clone, Debug)]
+pub struct IntoStreamAsc<T, C = Natural> {
+    heap:   MinMaxHeap<T, C>,
+    budget: usize,
+}
+
+/// A stream that yields the elements of a min-max-heap in descending
+/// (max-first) order.
+///
+/// This type is created with
+/// [`MinMaxHeap::into_stream_desc`](struct.MinMaxHeap.html#method.into_stream_desc).
+#[derive(Clone, Debug)]
+pub struct IntoStreamDesc<T, C = Natural> {
+    heap:   MinMaxHeap<T, C>,
+    budget: usize,
+}
+
+/// Spends one unit of `budget`, or, if it has run out, refills it and
+/// asks to be polled again later.
+fn spend(budget: &mut usize, cx: &mut Context<'_>) -> bool {
+    if *budget == 0 {
+        *budget = BUDGET;
+        cx.waker().wake_by_ref();
+        false
+    } else {
+        *budget -= 1;
+        true
+    }
+}
+
+// The streams never pin their fields.
+impl<T, C> Unpin for IntoStreamAsc<T, C> { }
+impl<T, C> Unpin for IntoStreamDesc<T, C> { }
+
+impl<T, C: Compare<T>> Stream for IntoStreamAsc<T, C> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let this = self.get_mut();
+        if this.heap.is_empty() {
+            Poll::Ready(None)
+        } else if spend(&mut this.budget, cx) {
+            Poll::Ready(this.heap.pop_min())
+        } else {
+            Poll::Pending
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.heap.len(), Some(self.heap.len()))
+    }
+}
+
+impl<T, C: Compare<T>> Stream for IntoStreamDesc<T, C> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let this = self.get_mut();
+        if this.heap.is_empty() {
+            Poll::Ready(None)
+        } else if spend(&mut this.budget, cx) {
+            Poll::Ready(this.heap.pop_max())
+        } else {
+            Poll::Pending
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.heap.len(), Some(self.heap.len()))
+    }
+}
+
+impl<T, C: Compare<T>> FusedStream for IntoStreamAsc<T, C> {
+    fn is_terminated(&self) -> bool {
+        self.heap.is_empty()
+    }
+}
+
+impl<T, C: Compare<T>> FusedStream for IntoStreamDesc<T, C> {
+    fn is_terminated(&self) -> bool {
+        self.heap.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec::Vec;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::task::Wake;
+
+    struct CountWakes(AtomicUsize);
+
+    impl Wake for CountWakes {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Polls `stream` to the end, returning its items and how many times
+    /// it was pending.
+    fn collect<S: Stream + Unpin>(mut stream: S) -> (Vec<S::Item>, usize) {
+        let wakes = Arc::new(CountWakes(AtomicUsize::new(0)));
+        let waker = Arc::clone(&wakes).into();
+        let mut cx = Context::from_waker(&waker);
+        let mut items = Vec::new();
+        let mut pending = 0;
+
+        loop {
+            match Pin::new(&mut stream).poll_next(&mut cx) {
+                Poll::Ready(Some(item)) => items.push(item),
+                Poll::Ready(None)       => break,
+                Poll::Pending           => pending += 1,
+            }
+        }
+
+        assert_eq!(pending, wakes.0.load(Ordering::Relaxed));
+        (items, pending)
+    }
+
+    #[test]
+    fn asc_and_desc() {
+        let heap: MinMaxHeap<_> = (0 .. 200).rev().collect();
+
+        let (asc, pending) = collect(heap.clone().into_stream_asc());
+        assert_eq!((0 .. 200).collect::<Vec<_>>(), asc);
+        assert_eq!(3, pending);
+
+        let stream = heap.into_stream_desc();
+        assert_eq!((200, Some(200)), stream.size_hint());
+        let (desc, _) = collect(stream);
+        assert_eq!((0 .. 200).rev().collect::<Vec<_>>(), desc);
+    }
+
+    #[test]
+    fn empty() {
+        let stream = MinMaxHeap::<u8>::new().into_stream_asc();
+        assert!(stream.is_terminated());
+        assert_eq!((vec![], 0), collect(stream));
+    }
+}
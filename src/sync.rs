@@ -0,0 +1,304 @@
+//! Thread-safe heaps, and a priority channel built on them.
+
+use std::fmt;
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::time::Duration;
+
+use super::{Compare, MinMaxHeap, Natural};
+
+#[cfg(feature = "async")]
+mod async_heap;
+mod channel;
+#[cfg(feature = "concurrent")]
+mod sharded;
+
+pub use self::channel::{priority_channel, OnFull, Receiver, RecvError, SendError, Sender,
+                        TryRecvError};
+#[cfg(feature = "async")]
+pub use self::async_heap::{AsyncMinMaxHeap, Pop};
+#[cfg(feature = "concurrent")]
+pub use self::sharded::ShardedMinMaxHeap;
+
+/// A min-max-heap that can be shared between threads, with pops that
+/// wait for an element to arrive.
+///
+/// This is a [`MinMaxHeap`] behind a `Mutex`, with a `Condvar` to wake
+/// waiting poppers, so it can serve as a multi-producer, multi-consumer
+/// work queue. Every operation takes the lock, so under heavy
+/// contention a sharded design may scale better.
+///
+/// # Panics
+///
+/// If a thread panics while holding the lock, such as in a comparator,
+/// the mutex is poisoned, and every later operation panics.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use min_max_heap::sync::SyncMinMaxHeap;
+///
+/// let jobs = Arc::new(SyncMinMaxHeap::new());
+///
+/// let worker = {
+///     let jobs = Arc::clone(&jobs);
+///     // Waits until there is a job.
+///     thread::spawn(move || jobs.pop_max_blocking())
+/// };
+///
+/// jobs.push(7);
+/// assert_eq!(worker.join().unwrap(), 7);
+/// assert_eq!(jobs.try_pop_min(), None);
+/// ```
+///
+/// [`MinMaxHeap`]: ../struct.MinMaxHeap.html
+pub struct SyncMinMaxHeap<T, C = Natural> {
+    heap:      Mutex<MinMaxHeap<T, C>>,
+    available: Condvar,
+}
+
+impl<T: Ord> Default for SyncMinMaxHeap<T> {
+    fn default() -> Self {
+        SyncMinMaxHeap::new()
+    }
+}
+
+impl<T: Ord> SyncMinMaxHeap<T> {
+    /// Creates a new, empty `SyncMinMaxHeap`.
+    ///
+    /// *O*(1).
+    pub fn new() -> Self {
+        SyncMinMaxHeap::from_heap(MinMaxHeap::new())
+    }
+}
+
+impl<T, C> SyncMinMaxHeap<T, C> {
+    /// Creates a new, empty `SyncMinMaxHeap` ordered by `cmp`.
+    ///
+    /// *O*(1).
+    pub fn with_comparator(cmp: C) -> Self {
+        SyncMinMaxHeap::from_heap(MinMaxHeap::with_comparator(cmp))
+    }
+
+    /// Creates a `SyncMinMaxHeap` holding the elements of `heap`.
+    ///
+    /// *O*(1).
+    pub fn from_heap(heap: MinMaxHeap<T, C>) -> Self {
+        SyncMinMaxHeap {
+            heap:      Mutex::new(heap),
+            available: Condvar::new(),
+        }
+    }
+
+    /// Returns the underlying `MinMaxHeap`.
+    ///
+    /// *O*(1).
+    pub fn into_inner(self) -> MinMaxHeap<T, C> {
+        self.heap.into_inner().unwrap()
+    }
+
+    /// Locks the heap, for operations that need several steps to happen
+    /// at once. Other threads’ operations wait until the guard is
+    /// dropped.
+    ///
+    /// Waiting poppers are not woken by elements pushed through the
+    /// guard; use [`push`](#method.push) for that.
+    pub fn lock(&self) -> MutexGuard<'_, MinMaxHeap<T, C>> {
+        self.heap.lock().unwrap()
+    }
+
+    /// The number of elements in the heap. Other threads may change it
+    /// at any moment.
+    ///
+    /// *O*(1).
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Is the heap empty? Other threads may change this at any moment.
+    ///
+    /// *O*(1).
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Drops all elements from the heap.
+    ///
+    /// *O*(*n*).
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+}
+
+impl<T, C: Compare<T>> SyncMinMaxHeap<T, C> {
+    /// Adds an element to the heap, waking a thread waiting to pop, if
+    /// any.
+    ///
+    /// *O*(log *n*).
+    pub fn push(&self, element: T) {
+        self.lock().push(element);
+        self.available.notify_one();
+    }
+
+    /// Removes the minimum element if there is one, without waiting.
+    ///
+    /// *O*(log *n*).
+    pub fn try_pop_min(&self) -> Option<T> {
+        self.lock().pop_min()
+    }
+
+    /// Removes the maximum element if there is one, without waiting.
+    ///
+    /// *O*(log *n*).
+    pub fn try_pop_max(&self) -> Option<T> {
+        self.lock().pop_max()
+    }
+
+    /// Removes the minimum element, waiting for one to be pushed if the
+    /// heap is empty.
+    ///
+    /// *O*(log *n*) once an element is available.
+    pub fn pop_min_blocking(&self) -> T {
+        self.wait(None).pop_min().unwrap()
+    }
+
+    /// Removes the maximum element, waiting for one to be pushed if the
+    /// heap is empty.
+    ///
+    /// *O*(log *n*) once an element is available.
+    pub fn pop_max_blocking(&self) -> T {
+        self.wait(None).pop_max().unwrap()
+    }
+
+    /// Removes the minimum element, waiting up to `timeout` for one to
+    /// be pushed if the heap is empty. Returns `None` if the time runs
+    /// out.
+    ///
+    /// *O*(log *n*) once an element is available.
+    pub fn pop_min_timeout(&self, timeout: Duration) -> Option<T> {
+        self.wait(Some(timeout)).pop_min()
+    }
+
+    /// Removes the maximum element, waiting up to `timeout` for one to
+    /// be pushed if the heap is empty. Returns `None` if the time runs
+    /// out.
+    ///
+    /// *O*(log *n*) once an element is available.
+    pub fn pop_max_timeout(&self, timeout: Duration) -> Option<T> {
+        self.wait(Some(timeout)).pop_max()
+    }
+
+    /// Locks the heap once it is non-empty, or once `timeout` has
+    /// passed.
+    fn wait(&self, timeout: Option<Duration>) -> MutexGuard<'_, MinMaxHeap<T, C>> {
+        let guard = self.lock();
+        let guard = match timeout {
+            None => self.available.wait_while(guard, |heap| heap.is_empty()).unwrap(),
+            Some(timeout) => {
+                let result = self.available.wait_timeout_while(guard, timeout,
+                                                               |heap| heap.is_empty());
+                result.unwrap().0
+            }
+        };
+
+        // The caller pops one element; if more remain, another waiter
+        // may take them.
+        if guard.len() > 1 {
+            self.available.notify_one();
+        }
+        guard
+    }
+}
+
+impl<T, C: Compare<T>> Extend<T> for &SyncMinMaxHeap<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let mut heap = self.lock();
+        heap.extend(iter);
+        if !heap.is_empty() {
+            self.available.notify_all();
+        }
+    }
+}
+
+impl<T: fmt::Debug, C> fmt::Debug for SyncMinMaxHeap<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.heap.try_lock() {
+            Ok(heap) => f.debug_tuple("SyncMinMaxHeap").field(&*heap).finish(),
+            Err(_)   => f.write_str("SyncMinMaxHeap(<locked>)"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::vec::Vec;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn producers_and_consumers() {
+        let heap = Arc::new(SyncMinMaxHeap::new());
+
+        let consumers: Vec<_> = (0 .. 4).map(|i| {
+            let heap = Arc::clone(&heap);
+            thread::spawn(move || {
+                let mut taken = Vec::new();
+                for _ in 0 .. 250 {
+                    taken.push(if i % 2 == 0 {
+                        heap.pop_min_blocking()
+                    } else {
+                        heap.pop_max_blocking()
+                    });
+                }
+                taken
+            })
+        }).collect();
+
+        let producers: Vec<_> = (0 .. 4).map(|i| {
+            let heap = Arc::clone(&heap);
+            thread::spawn(move || {
+                for j in 0 .. 250 {
+                    heap.push(i * 250 + j);
+                }
+            })
+        }).collect();
+
+        for producer in producers {
+            producer.join().unwrap();
+        }
+
+        let mut all: Vec<_> = consumers.into_iter()
+                                       .flat_map(|consumer| consumer.join().unwrap())
+                                       .collect();
+        all.sort();
+        assert_eq!((0 .. 1000).collect::<Vec<_>>(), all);
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn timeout() {
+        let heap = SyncMinMaxHeap::new();
+        assert_eq!(None, heap.pop_min_timeout(Duration::from_millis(10)));
+
+        (&heap).extend(vec![2, 7, 4]);
+        assert_eq!(Some(2), heap.pop_min_timeout(Duration::from_millis(10)));
+        assert_eq!(Some(7), heap.try_pop_max());
+        assert_eq!(Some(4), heap.pop_max_timeout(Duration::from_millis(10)));
+        assert_eq!(None, heap.try_pop_min());
+    }
+
+    #[test]
+    fn wakes_waiter() {
+        let heap = Arc::new(SyncMinMaxHeap::new());
+        let waiter = {
+            let heap = Arc::clone(&heap);
+            thread::spawn(move || heap.pop_max_timeout(Duration::from_secs(60)))
+        };
+
+        thread::sleep(Duration::from_millis(20));
+        heap.push(5);
+        assert_eq!(Some(5), waiter.join().unwrap());
+    }
+}
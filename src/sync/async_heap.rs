@@ -0,0 +1,373 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
+
+use super::super::{Compare, MinMaxHeap, Natural};
+
+/// A min-max-heap whose pops can be awaited, for use as a priority
+/// channel between async tasks.
+///
+/// [`pop_min`](#method.pop_min) and [`pop_max`](#method.pop_max) return
+/// futures that resolve once there is an element to take, and
+/// [`push`](#method.push) wakes a waiting task. Waiters are woken in the
+/// order they started waiting. This uses only `std`, so it works with
+/// any executor.
+///
+/// Requires the `async` feature.
+///
+/// # Examples
+///
+/// ```
+/// # fn block_on<F: std::future::Future>(f: F) -> F::Output {
+/// #     use std::{pin::Pin, sync::Arc, task::{Context, Poll, Wake}};
+/// #     struct Noop;
+/// #     impl Wake for Noop { fn wake(self: Arc<Self>) {} }
+/// #     let waker = Arc::new(Noop).into();
+/// #     let mut f = Box::pin(f);
+/// #     loop {
+/// #         if let Poll::Ready(x) = f.as_mut().poll(&mut Context::from_waker(&waker)) {
+/// #             return x;
+/// #         }
+/// #     }
+/// # }
+/// use min_max_heap::sync::AsyncMinMaxHeap;
+///
+/// let heap = AsyncMinMaxHeap::new();
+/// heap.push(3);
+/// heap.push(9);
+/// heap.push(1);
+///
+/// block_on(async {
+///     assert_eq!(heap.pop_max().await, 9);
+///     assert_eq!(heap.pop_min().await, 1);
+/// });
+/// ```
+pub struct AsyncMinMaxHeap<T, C = Natural> {
+    state: Mutex<State<T, C>>,
+}
+
+struct State<T, C> {
+    heap:    MinMaxHeap<T, C>,
+    // Waiting futures by the order they started waiting.
+    waiters: BTreeMap<u64, Waker>,
+    next_id: u64,
+}
+
+impl<T, C> State<T, C> {
+    // Takes the longest-waiting pop’s waker. Callers wake it after
+    // unlocking, so the woken task doesn’t run into the lock.
+    fn next_waiter(&mut self) -> Option<Waker> {
+        let first = self.waiters.keys().next().cloned();
+        first.and_then(|id| self.waiters.remove(&id))
+    }
+}
+
+impl<T: Ord> Default for AsyncMinMaxHeap<T> {
+    fn default() -> Self {
+        AsyncMinMaxHeap::new()
+    }
+}
+
+impl<T: Ord> AsyncMinMaxHeap<T> {
+    /// Creates a new, empty `AsyncMinMaxHeap`.
+    ///
+    /// *O*(1).
+    pub fn new() -> Self {
+        AsyncMinMaxHeap::from_heap(MinMaxHeap::new())
+    }
+}
+
+impl<T, C> AsyncMinMaxHeap<T, C> {
+    /// Creates a new, empty `AsyncMinMaxHeap` ordered by `cmp`.
+    ///
+    /// *O*(1).
+    pub fn with_comparator(cmp: C) -> Self {
+        AsyncMinMaxHeap::from_heap(MinMaxHeap::with_comparator(cmp))
+    }
+
+    /// Creates an `AsyncMinMaxHeap` holding the elements of `heap`.
+    ///
+    /// *O*(1).
+    pub fn from_heap(heap: MinMaxHeap<T, C>) -> Self {
+        AsyncMinMaxHeap {
+            state: Mutex::new(State {
+                heap,
+                waiters: BTreeMap::new(),
+                next_id: 0,
+            }),
+        }
+    }
+
+    /// Returns the underlying `MinMaxHeap`.
+    ///
+    /// *O*(1).
+    pub fn into_inner(self) -> MinMaxHeap<T, C> {
+        self.state.into_inner().unwrap().heap
+    }
+
+    /// The number of elements in the heap. Other tasks may change it at
+    /// any moment.
+    ///
+    /// *O*(1).
+    pub fn len(&self) -> usize {
+        self.lock().heap.len()
+    }
+
+    /// Is the heap empty? Other tasks may change this at any moment.
+    ///
+    /// *O*(1).
+    pub fn is_empty(&self) -> bool {
+        self.lock().heap.is_empty()
+    }
+
+    /// Drops all elements from the heap.
+    ///
+    /// *O*(*n*).
+    pub fn clear(&self) {
+        self.lock().heap.clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State<T, C>> {
+        self.state.lock().unwrap()
+    }
+}
+
+impl<T, C: Compare<T>> AsyncMinMaxHeap<T, C> {
+    /// Adds an element to the heap, waking the longest-waiting pop, if
+    /// any.
+    ///
+    /// *O*(log *n*).
+    pub fn push(&self, element: T) {
+        let waker = {
+            let mut state = self.lock();
+            state.heap.push(element);
+            state.next_waiter()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    /// Removes the minimum element if there is one, without waiting.
+    ///
+    /// *O*(log *n*).
+    pub fn try_pop_min(&self) -> Option<T> {
+        self.lock().heap.pop_min()
+    }
+
+    /// Removes the maximum element if there is one, without waiting.
+    ///
+    /// *O*(log *n*).
+    pub fn try_pop_max(&self) -> Option<T> {
+        self.lock().heap.pop_max()
+    }
+
+    /// Returns a future that removes the minimum element, waiting for
+    /// one to be pushed if the heap is empty.
+    ///
+    /// *O*(log *n*) once an element is available.
+    pub fn pop_min(&self) -> Pop<'_, T, C> {
+        Pop { heap: self, min: true, id: None }
+    }
+
+    /// Returns a future that removes the maximum element, waiting for
+    /// one to be pushed if the heap is empty.
+    ///
+    /// *O*(log *n*) once an element is available.
+    pub fn pop_max(&self) -> Pop<'_, T, C> {
+        Pop { heap: self, min: false, id: None }
+    }
+}
+
+impl<T: fmt::Debug, C> fmt::Debug for AsyncMinMaxHeap<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.state.try_lock() {
+            Ok(state) => f.debug_tuple("AsyncMinMaxHeap").field(&state.heap).finish(),
+            Err(_)    => f.write_str("AsyncMinMaxHeap(<locked>)"),
+        }
+    }
+}
+
+/// A future that removes the minimum or maximum element of an
+/// [`AsyncMinMaxHeap`] once there is one.
+///
+/// This type is created with
+/// [`AsyncMinMaxHeap::pop_min`](struct.AsyncMinMaxHeap.html#method.pop_min)
+/// and
+/// [`AsyncMinMaxHeap::pop_max`](struct.AsyncMinMaxHeap.html#method.pop_max).
+///
+/// [`AsyncMinMaxHeap`]: struct.AsyncMinMaxHeap.html
+pub struct Pop<'a, T: 'a, C: 'a = Natural> {
+    heap: &'a AsyncMinMaxHeap<T, C>,
+    min:  bool,
+    // Our key in the waiters, once we have waited.
+    id:   Option<u64>,
+}
+
+impl<'a, T, C: Compare<T>> Future for Pop<'a, T, C> {
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut state = self.heap.lock();
+
+        let element = if self.min { state.heap.pop_min() } else { state.heap.pop_max() };
+        if let Some(element) = element {
+            if let Some(id) = self.id.take() {
+                state.waiters.remove(&id);
+            }
+            return Poll::Ready(element);
+        }
+
+        let id = match self.id {
+            Some(id) => id,
+            None     => {
+                let id = state.next_id;
+                state.next_id += 1;
+                id
+            }
+        };
+        state.waiters.insert(id, cx.waker().clone());
+        drop(state);
+        self.id = Some(id);
+        Poll::Pending
+    }
+}
+
+impl<'a, T, C> Drop for Pop<'a, T, C> {
+    fn drop(&mut self) {
+        if let Some(id) = self.id {
+            let waker = {
+                let mut state = self.heap.lock();
+                // If we were woken but will never take the element, wake
+                // someone who will.
+                if state.waiters.remove(&id).is_none() && !state.heap.is_empty() {
+                    state.next_waiter()
+                } else {
+                    None
+                }
+            };
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl<'a, T, C> fmt::Debug for Pop<'a, T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pop")
+         .field("min", &self.min)
+         .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::boxed::Box;
+    use std::vec::Vec;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::task::Wake;
+    use std::thread::{self, Thread};
+
+    struct Unpark(Thread);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let waker = Arc::new(Unpark(thread::current())).into();
+        let mut cx = Context::from_waker(&waker);
+        let mut future = Box::pin(future);
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending       => thread::park(),
+            }
+        }
+    }
+
+    fn poll_once<F: Future + Unpin>(future: &mut F) -> Poll<F::Output> {
+        let waker = Arc::new(Unpark(thread::current())).into();
+        Pin::new(future).poll(&mut Context::from_waker(&waker))
+    }
+
+    #[test]
+    fn waits_for_push() {
+        let heap = Arc::new(AsyncMinMaxHeap::new());
+
+        let consumers: Vec<_> = (0 .. 4).map(|i| {
+            let heap = Arc::clone(&heap);
+            thread::spawn(move || {
+                (0 .. 100).map(|_| block_on(if i % 2 == 0 { heap.pop_min() } else { heap.pop_max() }))
+                          .collect::<Vec<_>>()
+            })
+        }).collect();
+
+        for i in 0 .. 400 {
+            heap.push(i);
+        }
+
+        let mut all: Vec<_> = consumers.into_iter()
+                                       .flat_map(|consumer| consumer.join().unwrap())
+                                       .collect();
+        all.sort();
+        assert_eq!((0 .. 400).collect::<Vec<_>>(), all);
+    }
+
+    #[test]
+    fn cancelled_waiter_passes_wakeup() {
+        let heap = AsyncMinMaxHeap::new();
+        let mut first = heap.pop_min();
+        let mut second = heap.pop_min();
+        assert_eq!(Poll::Pending, poll_once(&mut first));
+        assert_eq!(Poll::Pending, poll_once(&mut second));
+
+        heap.push(5);
+        // `first` was woken, and dropping it passes the wakeup on.
+        assert_eq!(1, heap.lock().waiters.len());
+        drop(first);
+        assert_eq!(0, heap.lock().waiters.len());
+        assert_eq!(Poll::Ready(5), poll_once(&mut second));
+    }
+
+    #[test]
+    fn wakes_after_unlocking() {
+        // Records whether the heap was unlocked when it was woken.
+        struct Probe(Arc<AsyncMinMaxHeap<u32>>, AtomicBool);
+
+        impl Wake for Probe {
+            fn wake(self: Arc<Self>) {
+                self.1.store(self.0.state.try_lock().is_ok(), Ordering::SeqCst);
+            }
+        }
+
+        let heap = Arc::new(AsyncMinMaxHeap::new());
+        let probe = Arc::new(Probe(Arc::clone(&heap), AtomicBool::new(false)));
+        let waker = Arc::clone(&probe).into();
+        let mut pop = heap.pop_min();
+        assert_eq!(Poll::Pending, Pin::new(&mut pop).poll(&mut Context::from_waker(&waker)));
+
+        heap.push(5);
+        assert!(probe.1.load(Ordering::SeqCst));
+        assert_eq!(Poll::Ready(5), poll_once(&mut pop));
+    }
+
+    #[test]
+    fn try_pop() {
+        let heap = AsyncMinMaxHeap::new();
+        assert_eq!(None, heap.try_pop_min());
+        heap.push(2);
+        heap.push(8);
+        assert_eq!(Some(8), heap.try_pop_max());
+        assert_eq!(Some(2), heap.try_pop_min());
+        assert!(heap.is_empty());
+    }
+}
@@ -0,0 +1,452 @@
+use std::error::Error;
+use std::fmt;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+
+use super::super::{Eviction, MinMaxHeap};
+use super::super::compare::ByFirst;
+
+/// Creates a bounded multi-producer, multi-consumer channel whose
+/// receivers take either the lowest- or the highest-priority item.
+///
+/// The channel holds at most `capacity` items; `on_full` says what
+/// [`Sender::send`] does when it is full. Both halves can be cloned and
+/// shared between threads. The channel disconnects when all senders or
+/// all receivers have been dropped, though receivers can still take the
+/// items that remain.
+///
+/// # Panics
+///
+/// Panics if `capacity` is 0.
+///
+/// # Examples
+///
+/// ```
+/// use std::thread;
+/// use min_max_heap::sync::{priority_channel, OnFull};
+///
+/// let (tx, rx) = priority_channel(16, OnFull::Block);
+///
+/// let producer = thread::spawn(move || {
+///     tx.send(2, "normal").unwrap();
+///     tx.send(9, "urgent").unwrap();
+///     tx.send(0, "whenever").unwrap();
+/// });
+/// producer.join().unwrap();
+///
+/// assert_eq!(rx.recv_max(), Ok((9, "urgent")));
+/// assert_eq!(rx.recv_min(), Ok((0, "whenever")));
+/// assert_eq!(rx.recv_min(), Ok((2, "normal")));
+/// assert!(rx.recv_min().is_err());
+/// ```
+///
+/// [`Sender::send`]: struct.Sender.html#method.send
+pub fn priority_channel<P: Ord, T>(capacity: usize, on_full: OnFull)
+                                   -> (Sender<P, T>, Receiver<P, T>) {
+    assert!(capacity > 0, "priority_channel: capacity must be positive");
+
+    let shared = Arc::new(Shared {
+        state:     Mutex::new(State {
+            heap:      MinMaxHeap::with_comparator(ByFirst),
+            senders:   1,
+            receivers: 1,
+        }),
+        not_empty: Condvar::new(),
+        not_full:  Condvar::new(),
+        capacity,
+        on_full,
+    });
+
+    (Sender(Arc::clone(&shared)), Receiver(shared))
+}
+
+/// What a [`Sender`] does when its channel is full.
+///
+/// [`Sender`]: struct.Sender.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OnFull {
+    /// Wait for a receiver to make room.
+    Block,
+    /// Make room according to the eviction policy, without waiting.
+    Evict(Eviction),
+}
+
+struct Shared<P, T> {
+    state:     Mutex<State<P, T>>,
+    not_empty: Condvar,
+    not_full:  Condvar,
+    capacity:  usize,
+    on_full:   OnFull,
+}
+
+struct State<P, T> {
+    heap:      MinMaxHeap<(P, T), ByFirst>,
+    senders:   usize,
+    receivers: usize,
+}
+
+impl<P, T> Shared<P, T> {
+    fn lock(&self) -> MutexGuard<'_, State<P, T>> {
+        self.state.lock().unwrap()
+    }
+}
+
+/// The sending half of a [`priority_channel`].
+///
+/// [`priority_channel`]: fn.priority_channel.html
+pub struct Sender<P, T>(Arc<Shared<P, T>>);
+
+/// The receiving half of a [`priority_channel`].
+///
+/// [`priority_channel`]: fn.priority_channel.html
+pub struct Receiver<P, T>(Arc<Shared<P, T>>);
+
+/// The error from sending on a [`priority_channel`], which gives back the
+/// item that was not sent.
+///
+/// [`priority_channel`]: fn.priority_channel.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SendError<P, T> {
+    /// The channel is full, and its policy is to reject new items or the
+    /// send would have to wait.
+    Full(P, T),
+    /// All receivers have been dropped.
+    Disconnected(P, T),
+}
+
+/// The error from receiving on a [`priority_channel`] whose senders have
+/// all been dropped and which is empty.
+///
+/// [`priority_channel`]: fn.priority_channel.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RecvError;
+
+/// The error from receiving on a [`priority_channel`] without waiting.
+///
+/// [`priority_channel`]: fn.priority_channel.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// The channel is empty.
+    Empty,
+    /// The channel is empty, and all senders have been dropped.
+    Disconnected,
+}
+
+impl<P: Ord, T> Sender<P, T> {
+    /// Sends `item` with priority `priority`.
+    ///
+    /// If the channel is full, this waits for room if the policy is
+    /// `OnFull::Block`, and otherwise applies the eviction policy,
+    /// returning the evicted item, which may be the one just sent. With
+    /// `Eviction::Reject`, the new item is returned as
+    /// `SendError::Full` instead.
+    ///
+    /// *O*(log *n*), once there is room.
+    pub fn send(&self, priority: P, item: T) -> Result<Option<(P, T)>, SendError<P, T>> {
+        self.send_inner(priority, item, true)
+    }
+
+    /// Sends `item` with priority `priority` without waiting. This is like
+    /// [`send`](#method.send), except that when the channel is full and
+    /// the policy is `OnFull::Block`, it returns `SendError::Full`.
+    ///
+    /// *O*(log *n*).
+    pub fn try_send(&self, priority: P, item: T) -> Result<Option<(P, T)>, SendError<P, T>> {
+        self.send_inner(priority, item, false)
+    }
+
+    fn send_inner(&self, priority: P, item: T, wait: bool)
+                  -> Result<Option<(P, T)>, SendError<P, T>> {
+        let shared = &*self.0;
+        let mut state = shared.lock();
+
+        if wait && shared.on_full == OnFull::Block {
+            state = shared.not_full
+                          .wait_while(state, |state| state.receivers > 0 &&
+                                                     state.heap.len() >= shared.capacity)
+                          .unwrap();
+        }
+
+        if state.receivers == 0 {
+            return Err(SendError::Disconnected(priority, item));
+        }
+
+        let full = state.heap.len() >= shared.capacity;
+        let evicted = match shared.on_full {
+            _ if !full => {
+                state.heap.push((priority, item));
+                None
+            }
+            OnFull::Evict(Eviction::EvictMax) =>
+                state.heap.push_capped_max(shared.capacity, (priority, item)),
+            OnFull::Evict(Eviction::EvictMin) =>
+                state.heap.push_capped_min(shared.capacity, (priority, item)),
+            OnFull::Block | OnFull::Evict(Eviction::Reject) =>
+                return Err(SendError::Full(priority, item)),
+        };
+
+        shared.not_empty.notify_one();
+        Ok(evicted)
+    }
+
+    /// The number of items in the channel.
+    ///
+    /// *O*(1).
+    pub fn len(&self) -> usize {
+        self.0.lock().heap.len()
+    }
+
+    /// Is the channel empty?
+    ///
+    /// *O*(1).
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The maximum number of items the channel holds.
+    ///
+    /// *O*(1).
+    pub fn capacity(&self) -> usize {
+        self.0.capacity
+    }
+}
+
+impl<P: Ord, T> Receiver<P, T> {
+    /// Takes the lowest-priority item, waiting for one if the channel is
+    /// empty. Fails only once the channel is empty and all senders have
+    /// been dropped.
+    ///
+    /// *O*(log *n*), once there is an item.
+    pub fn recv_min(&self) -> Result<(P, T), RecvError> {
+        self.recv(true)
+    }
+
+    /// Takes the highest-priority item, waiting for one if the channel
+    /// is empty. Fails only once the channel is empty and all senders
+    /// have been dropped.
+    ///
+    /// *O*(log *n*), once there is an item.
+    pub fn recv_max(&self) -> Result<(P, T), RecvError> {
+        self.recv(false)
+    }
+
+    /// Takes the lowest-priority item, if any, without waiting.
+    ///
+    /// *O*(log *n*).
+    pub fn try_recv_min(&self) -> Result<(P, T), TryRecvError> {
+        self.try_recv(true)
+    }
+
+    /// Takes the highest-priority item, if any, without waiting.
+    ///
+    /// *O*(log *n*).
+    pub fn try_recv_max(&self) -> Result<(P, T), TryRecvError> {
+        self.try_recv(false)
+    }
+
+    fn recv(&self, min: bool) -> Result<(P, T), RecvError> {
+        let shared = &*self.0;
+        let state = shared.lock();
+        let mut state = shared.not_empty
+                              .wait_while(state, |state| state.senders > 0 &&
+                                                         state.heap.is_empty())
+                              .unwrap();
+        let item = if min { state.heap.pop_min() } else { state.heap.pop_max() };
+        if item.is_some() {
+            shared.not_full.notify_one();
+        }
+        item.ok_or(RecvError)
+    }
+
+    fn try_recv(&self, min: bool) -> Result<(P, T), TryRecvError> {
+        let shared = &*self.0;
+        let mut state = shared.lock();
+        match if min { state.heap.pop_min() } else { state.heap.pop_max() } {
+            Some(item) => {
+                shared.not_full.notify_one();
+                Ok(item)
+            }
+            None if state.senders == 0 => Err(TryRecvError::Disconnected),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    /// The number of items in the channel.
+    ///
+    /// *O*(1).
+    pub fn len(&self) -> usize {
+        self.0.lock().heap.len()
+    }
+
+    /// Is the channel empty?
+    ///
+    /// *O*(1).
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The maximum number of items the channel holds.
+    ///
+    /// *O*(1).
+    pub fn capacity(&self) -> usize {
+        self.0.capacity
+    }
+}
+
+impl<P, T> Clone for Sender<P, T> {
+    fn clone(&self) -> Self {
+        self.0.lock().senders += 1;
+        Sender(Arc::clone(&self.0))
+    }
+}
+
+impl<P, T> Clone for Receiver<P, T> {
+    fn clone(&self) -> Self {
+        self.0.lock().receivers += 1;
+        Receiver(Arc::clone(&self.0))
+    }
+}
+
+impl<P, T> Drop for Sender<P, T> {
+    fn drop(&mut self) {
+        let mut state = self.0.lock();
+        state.senders -= 1;
+        if state.senders == 0 {
+            self.0.not_empty.notify_all();
+        }
+    }
+}
+
+impl<P, T> Drop for Receiver<P, T> {
+    fn drop(&mut self) {
+        let mut state = self.0.lock();
+        state.receivers -= 1;
+        if state.receivers == 0 {
+            self.0.not_full.notify_all();
+        }
+    }
+}
+
+impl<P, T> fmt::Debug for Sender<P, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Sender { .. }")
+    }
+}
+
+impl<P, T> fmt::Debug for Receiver<P, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Receiver { .. }")
+    }
+}
+
+impl<P, T> fmt::Display for SendError<P, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SendError::Full(..)         => f.write_str("sending on a full channel"),
+            SendError::Disconnected(..) => f.write_str("sending on a disconnected channel"),
+        }
+    }
+}
+
+impl<P: fmt::Debug, T: fmt::Debug> Error for SendError<P, T> { }
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("receiving on an empty and disconnected channel")
+    }
+}
+
+impl Error for RecvError { }
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryRecvError::Empty        => f.write_str("receiving on an empty channel"),
+            TryRecvError::Disconnected =>
+                f.write_str("receiving on an empty and disconnected channel"),
+        }
+    }
+}
+
+impl Error for TryRecvError { }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::vec::Vec;
+    use std::thread;
+
+    #[test]
+    fn many_threads() {
+        let (tx, rx) = priority_channel(8, OnFull::Block);
+
+        let producers: Vec<_> = (0 .. 4).map(|i| {
+            let tx = tx.clone();
+            thread::spawn(move || {
+                for j in 0 .. 200 {
+                    assert_eq!(Ok(None), tx.send(i * 200 + j, ()));
+                }
+            })
+        }).collect();
+        drop(tx);
+
+        let consumers: Vec<_> = (0 .. 3).map(|i| {
+            let rx = rx.clone();
+            thread::spawn(move || {
+                let mut taken = Vec::new();
+                loop {
+                    let item = if i == 0 { rx.recv_max() } else { rx.recv_min() };
+                    match item {
+                        Ok((p, ())) => taken.push(p),
+                        Err(RecvError) => return taken,
+                    }
+                }
+            })
+        }).collect();
+
+        for producer in producers {
+            producer.join().unwrap();
+        }
+
+        let mut all: Vec<_> = consumers.into_iter()
+                                       .flat_map(|consumer| consumer.join().unwrap())
+                                       .collect();
+        all.sort();
+        assert_eq!((0 .. 800).collect::<Vec<_>>(), all);
+        assert_eq!(Err(TryRecvError::Disconnected), rx.try_recv_min());
+    }
+
+    #[test]
+    fn eviction() {
+        let (tx, rx) = priority_channel(2, OnFull::Evict(Eviction::EvictMin));
+        assert_eq!(Ok(None), tx.send(5, 'a'));
+        assert_eq!(Ok(None), tx.send(3, 'b'));
+        assert_eq!(Ok(Some((3, 'b'))), tx.send(4, 'c'));
+        assert_eq!(Ok((5, 'a')), rx.try_recv_max());
+
+        let (tx, rx) = priority_channel(1, OnFull::Evict(Eviction::Reject));
+        assert_eq!(Ok(None), tx.send(1, 'a'));
+        assert_eq!(Err(SendError::Full(2, 'b')), tx.send(2, 'b'));
+        assert_eq!(Ok((1, 'a')), rx.recv_min());
+        assert_eq!(Err(TryRecvError::Empty), rx.try_recv_min());
+    }
+
+    #[test]
+    fn backpressure() {
+        let (tx, rx) = priority_channel(1, OnFull::Block);
+        tx.send(1, ()).unwrap();
+        assert_eq!(Err(SendError::Full(2, ())), tx.try_send(2, ()));
+
+        let blocked = thread::spawn(move || tx.send(2, ()));
+        assert_eq!(Ok((1, ())), rx.recv_min());
+        assert_eq!(Ok(None), blocked.join().unwrap());
+        assert_eq!(Ok((2, ())), rx.recv_min());
+    }
+
+    #[test]
+    fn disconnect() {
+        let (tx, rx) = priority_channel(1, OnFull::Block);
+        tx.send(1, ()).unwrap();
+        drop(rx);
+        assert_eq!(Err(SendError::Disconnected(2, ())), tx.send(2, ()));
+    }
+}
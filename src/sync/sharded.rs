@@ -0,0 +1,408 @@
+use std::boxed::Box;
+use std::vec::Vec;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+use super::super::{Compare, MinMaxHeap, Natural};
+
+/// A concurrent min-max-heap split into independently locked shards, so
+/// that many threads can push and pop at once.
+///
+/// Each push goes to whichever shard is free, and each pop looks at the
+/// minima (or maxima) of two shards and takes the better one, as in the
+/// MultiQueue design. Pops are therefore *relaxed*: a popped element is
+/// close to the extreme, and is the extreme of at least two shards, but
+/// may not be the overall minimum or maximum. That trade buys throughput
+/// that stays roughly flat as threads are added, where a single
+/// [`SyncMinMaxHeap`] serializes everything on one lock. For exact pops,
+/// use [`pop_min_exact`](#method.pop_min_exact) and
+/// [`pop_max_exact`](#method.pop_max_exact), which look at every shard.
+///
+/// Requires the `concurrent` feature.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use min_max_heap::sync::ShardedMinMaxHeap;
+///
+/// let heap = Arc::new(ShardedMinMaxHeap::new());
+///
+/// let producers: Vec<_> = (0 .. 4).map(|i| {
+///     let heap = Arc::clone(&heap);
+///     thread::spawn(move || {
+///         for j in 0 .. 100 {
+///             heap.push(i * 100 + j);
+///         }
+///     })
+/// }).collect();
+///
+/// for producer in producers {
+///     producer.join().unwrap();
+/// }
+///
+/// assert_eq!(heap.len(), 400);
+/// assert_eq!(heap.pop_min_exact(), Some(0));
+/// assert_eq!(heap.pop_max_exact(), Some(399));
+/// ```
+///
+/// [`SyncMinMaxHeap`]: struct.SyncMinMaxHeap.html
+pub struct ShardedMinMaxHeap<T, C = Natural> {
+    shards: Box<[Mutex<MinMaxHeap<T, C>>]>,
+    next:   AtomicUsize,
+    len:    AtomicUsize,
+}
+
+/// The number of shards made by `ShardedMinMaxHeap::new`.
+const DEFAULT_SHARDS: usize = 16;
+
+/// Scrambles a counter value, so that successive pops pick unrelated
+/// pairs of shards. (This is the SplitMix64 finalizer.)
+fn mix(x: usize) -> usize {
+    let mut z = (x as u64).wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    (z ^ (z >> 31)) as usize
+}
+
+impl<T: Ord> Default for ShardedMinMaxHeap<T> {
+    fn default() -> Self {
+        ShardedMinMaxHeap::new()
+    }
+}
+
+impl<T: Ord> ShardedMinMaxHeap<T> {
+    /// Creates a new, empty `ShardedMinMaxHeap` with 16 shards.
+    ///
+    /// *O*(1).
+    pub fn new() -> Self {
+        ShardedMinMaxHeap::with_shards(DEFAULT_SHARDS)
+    }
+
+    /// Creates a new, empty `ShardedMinMaxHeap` with `shards` shards.
+    /// About twice as many shards as threads works well.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is 0.
+    ///
+    /// *O*(`shards`).
+    pub fn with_shards(shards: usize) -> Self {
+        ShardedMinMaxHeap::with_shards_and_comparator(shards, Natural)
+    }
+}
+
+impl<T, C: Clone> ShardedMinMaxHeap<T, C> {
+    /// Creates a new, empty `ShardedMinMaxHeap` with `shards` shards,
+    /// ordered by `cmp`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is 0.
+    ///
+    /// *O*(`shards`).
+    pub fn with_shards_and_comparator(shards: usize, cmp: C) -> Self {
+        assert!(shards > 0, "ShardedMinMaxHeap: must have at least one shard");
+        ShardedMinMaxHeap {
+            shards: (0 .. shards)
+                .map(|_| Mutex::new(MinMaxHeap::with_comparator(cmp.clone())))
+                .collect(),
+            next:   AtomicUsize::new(0),
+            len:    AtomicUsize::new(0),
+        }
+    }
+}
+
+impl<T, C> ShardedMinMaxHeap<T, C> {
+    /// The number of shards.
+    ///
+    /// *O*(1).
+    pub fn shards(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// The number of elements in the heap. Other threads may change it
+    /// at any moment.
+    ///
+    /// *O*(1).
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    /// Is the heap empty? Other threads may change this at any moment.
+    ///
+    /// *O*(1).
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops all elements from the heap.
+    ///
+    /// *O*(*n*).
+    pub fn clear(&self) {
+        for i in 0 .. self.shards.len() {
+            let mut shard = self.lock(i);
+            self.len.fetch_sub(shard.len(), Ordering::Relaxed);
+            shard.clear();
+        }
+    }
+
+    fn lock(&self, i: usize) -> MutexGuard<'_, MinMaxHeap<T, C>> {
+        self.shards[i].lock().unwrap()
+    }
+
+    /// Picks a shard to start from, spreading successive calls out.
+    fn start(&self) -> usize {
+        mix(self.next.fetch_add(1, Ordering::Relaxed)) % self.shards.len()
+    }
+}
+
+impl<T, C: Compare<T>> ShardedMinMaxHeap<T, C> {
+    /// Creates a `ShardedMinMaxHeap` holding the elements of `heap`,
+    /// spread across `shards` shards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is 0.
+    ///
+    /// *O*(*n*).
+    pub fn from_heap(heap: MinMaxHeap<T, C>, shards: usize) -> Self
+        where C: Clone
+    {
+        assert!(shards > 0, "ShardedMinMaxHeap: must have at least one shard");
+        let cmp = heap.1.clone();
+        let mut parts: Vec<Vec<T>> = (0 .. shards).map(|_| Vec::new()).collect();
+        let len = heap.len();
+        for (i, element) in heap.0.into_iter().enumerate() {
+            parts[i % shards].push(element);
+        }
+
+        ShardedMinMaxHeap {
+            shards: parts.into_iter()
+                .map(|part| Mutex::new(MinMaxHeap::from_vec_with_comparator(part, cmp.clone())))
+                .collect(),
+            next:   AtomicUsize::new(0),
+            len:    AtomicUsize::new(len),
+        }
+    }
+
+    /// Merges the shards into one `MinMaxHeap`.
+    ///
+    /// *O*(*n*).
+    pub fn into_heap(self) -> MinMaxHeap<T, C> {
+        let mut shards = self.shards.into_vec().into_iter().map(|shard| shard.into_inner().unwrap());
+        let first = shards.next().unwrap();
+        let cmp = first.1;
+        let mut vec = first.0;
+        for shard in shards {
+            vec.extend(shard.0);
+        }
+        MinMaxHeap::from_vec_with_comparator(vec, cmp)
+    }
+
+    /// Adds an element to the heap, in the first free shard.
+    ///
+    /// *O*(log *n*).
+    pub fn push(&self, element: T) {
+        let start = self.start();
+        let n = self.shards.len();
+
+        let mut shard = (0 .. n)
+            .filter_map(|k| self.shards[(start + k) % n].try_lock().ok())
+            .next()
+            .unwrap_or_else(|| self.lock(start));
+        shard.push(element);
+        self.len.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Removes a small element, if any: the lesser of two shards’
+    /// minima. Returns `None` only if every shard was empty when looked
+    /// at.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_min(&self) -> Option<T> {
+        self.pop_relaxed(true)
+    }
+
+    /// Removes a large element, if any: the greater of two shards’
+    /// maxima. Returns `None` only if every shard was empty when looked
+    /// at.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_max(&self) -> Option<T> {
+        self.pop_relaxed(false)
+    }
+
+    /// Removes the minimum element, if any, locking every shard to find
+    /// it.
+    ///
+    /// *O*(`shards` + log *n*).
+    pub fn pop_min_exact(&self) -> Option<T> {
+        self.pop_exact(true)
+    }
+
+    /// Removes the maximum element, if any, locking every shard to find
+    /// it.
+    ///
+    /// *O*(`shards` + log *n*).
+    pub fn pop_max_exact(&self) -> Option<T> {
+        self.pop_exact(false)
+    }
+
+    fn pop_relaxed(&self, min: bool) -> Option<T> {
+        let n = self.shards.len();
+        let i = self.start();
+
+        if n > 1 {
+            let j = (i + 1 + self.start() % (n - 1)) % n;
+            // Locking in index order cannot deadlock.
+            let mut a = self.lock(i.min(j));
+            let mut b = self.lock(i.max(j));
+
+            let take_a = match (peek(&a, min), peek(&b, min)) {
+                (Some(x), Some(y)) => !before(&a.1, y, x, min),
+                (x, _)             => x.is_some(),
+            };
+            let result = pop(if take_a { &mut a } else { &mut b }, min);
+            if result.is_some() {
+                self.len.fetch_sub(1, Ordering::Relaxed);
+                return result;
+            }
+        }
+
+        // Both were empty, but others may not be.
+        let result = (0 .. n).filter_map(|k| pop(&mut self.lock((i + k) % n), min)).next();
+        if result.is_some() {
+            self.len.fetch_sub(1, Ordering::Relaxed);
+        }
+        result
+    }
+
+    fn pop_exact(&self, min: bool) -> Option<T> {
+        // Locking in index order cannot deadlock.
+        let mut shards: Vec<_> = (0 .. self.shards.len()).map(|i| self.lock(i)).collect();
+
+        let mut best: Option<usize> = None;
+        for i in 0 .. shards.len() {
+            if let Some(x) = peek(&shards[i], min) {
+                best = match best {
+                    Some(b) if !before(&shards[i].1, x, peek(&shards[b], min).unwrap(), min)
+                        => Some(b),
+                    _   => Some(i),
+                };
+            }
+        }
+
+        let result = pop(&mut shards[best?], min);
+        self.len.fetch_sub(1, Ordering::Relaxed);
+        result
+    }
+}
+
+fn peek<T, C: Compare<T>>(heap: &MinMaxHeap<T, C>, min: bool) -> Option<&T> {
+    if min { heap.peek_min() } else { heap.peek_max() }
+}
+
+fn pop<T, C: Compare<T>>(heap: &mut MinMaxHeap<T, C>, min: bool) -> Option<T> {
+    if min { heap.pop_min() } else { heap.pop_max() }
+}
+
+/// Does `x` come before `y` at the min end if `min`, and at the max end
+/// otherwise?
+fn before<T, C: Compare<T>>(cmp: &C, x: &T, y: &T, min: bool) -> bool {
+    if min { cmp.compares_lt(x, y) } else { cmp.compares_gt(x, y) }
+}
+
+impl<T, C: Compare<T>> Extend<T> for &ShardedMinMaxHeap<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for element in iter {
+            self.push(element);
+        }
+    }
+}
+
+impl<T, C> fmt::Debug for ShardedMinMaxHeap<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShardedMinMaxHeap")
+         .field("shards", &self.shards.len())
+         .field("len", &self.len())
+         .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::super::test_model::{self, ModelHeap};
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn threads() {
+        let heap = Arc::new(ShardedMinMaxHeap::with_shards(8));
+
+        let workers: Vec<_> = (0 .. 8).map(|i| {
+            let heap = Arc::clone(&heap);
+            thread::spawn(move || {
+                let mut taken = Vec::new();
+                for j in 0 .. 500 {
+                    heap.push(i * 500 + j);
+                    if j % 2 == 0 {
+                        taken.extend(if i % 2 == 0 { heap.pop_min() } else { heap.pop_max() });
+                    }
+                }
+                taken
+            })
+        }).collect();
+
+        let mut all: Vec<_> = workers.into_iter()
+                                     .flat_map(|worker| worker.join().unwrap())
+                                     .collect();
+        assert_eq!(2000, all.len());
+        assert_eq!(2000, heap.len());
+
+        while let Some(x) = heap.pop_min() {
+            all.push(x);
+        }
+        all.sort();
+        assert_eq!((0 .. 4000).collect::<Vec<_>>(), all);
+        assert!(heap.is_empty());
+    }
+
+    // Pops take the exact extremes, so the model can check them.
+    impl ModelHeap for ShardedMinMaxHeap<u32> {
+        fn push(&mut self, element: u32) { ShardedMinMaxHeap::push(self, element) }
+        fn pop_min(&mut self) -> Option<u32> { self.pop_min_exact() }
+        fn pop_max(&mut self) -> Option<u32> { self.pop_max_exact() }
+        fn len(&self) -> usize { ShardedMinMaxHeap::len(self) }
+
+        fn peek_min(&self) -> Option<u32> {
+            self.shards.iter().filter_map(|shard| shard.lock().unwrap().peek_min().copied()).min()
+        }
+
+        fn peek_max(&self) -> Option<u32> {
+            self.shards.iter().filter_map(|shard| shard.lock().unwrap().peek_max().copied()).max()
+        }
+    }
+
+    #[test]
+    fn exact() {
+        let mut heap = ShardedMinMaxHeap::with_shards(5);
+        let model = test_model::random(&mut heap, 2000, usize::MAX, |_| ());
+        assert_eq!(model.into_vec_asc(), heap.into_heap().into_vec_asc());
+    }
+
+    #[test]
+    fn relaxed() {
+        // Each pop takes the better of two shards’ extremes, so with two
+        // shards it is exact.
+        let heap = ShardedMinMaxHeap::from_heap((0 .. 100).collect(), 2);
+        for i in 0 .. 50 {
+            assert_eq!(Some(i), heap.pop_min());
+            assert_eq!(Some(99 - i), heap.pop_max());
+        }
+        assert_eq!(None, heap.pop_min());
+    }
+}
@@ -0,0 +1,60 @@
+//! A randomized test that runs a heap alongside a `MinMaxHeap` model.
+
+extern crate rand;
+
+use self::rand::Rng;
+use super::{DoubleEndedHeap, MinMaxHeap};
+
+/// The operations that `random` drives. Peeks return copies, so heaps
+/// that cannot lend out their elements can take part too.
+pub trait ModelHeap {
+    fn push(&mut self, element: u32);
+    fn peek_min(&self) -> Option<u32>;
+    fn peek_max(&self) -> Option<u32>;
+    fn pop_min(&mut self) -> Option<u32>;
+    fn pop_max(&mut self) -> Option<u32>;
+    fn len(&self) -> usize;
+}
+
+impl<H: DoubleEndedHeap<Item = u32>> ModelHeap for H {
+    fn push(&mut self, element: u32) { DoubleEndedHeap::push(self, element) }
+    fn peek_min(&self) -> Option<u32> { DoubleEndedHeap::peek_min(self).copied() }
+    fn peek_max(&self) -> Option<u32> { DoubleEndedHeap::peek_max(self).copied() }
+    fn pop_min(&mut self) -> Option<u32> { DoubleEndedHeap::pop_min(self) }
+    fn pop_max(&mut self) -> Option<u32> { DoubleEndedHeap::pop_max(self) }
+    fn len(&self) -> usize { DoubleEndedHeap::len(self) }
+}
+
+/// Does `steps` random pushes and pops on `heap` and on a `MinMaxHeap`
+/// model, checking after each that the two agree and that `check`
+/// accepts `heap`. While `heap` holds `capacity` elements, pushes
+/// become pops.
+///
+/// Returns the model, so the caller can compare what is left.
+pub fn random<H, F>(heap: &mut H, steps: usize, capacity: usize, mut check: F)
+        -> MinMaxHeap<u32>
+    where H: ModelHeap,
+          F: FnMut(&H)
+{
+    let mut rng = rand::thread_rng();
+    let mut model = MinMaxHeap::new();
+
+    for _ in 0 .. steps {
+        match rng.gen_range(0 .. 4) {
+            0 | 1 if heap.len() < capacity => {
+                let x = rng.gen_range(0 .. 50);
+                heap.push(x);
+                model.push(x);
+            }
+            0 | 2 => assert_eq!(model.pop_min(), heap.pop_min()),
+            _ => assert_eq!(model.pop_max(), heap.pop_max()),
+        }
+
+        check(heap);
+        assert_eq!(model.len(), heap.len());
+        assert_eq!(model.peek_min().copied(), heap.peek_min());
+        assert_eq!(model.peek_max().copied(), heap.peek_max());
+    }
+
+    model
+}
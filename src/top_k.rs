@@ -0,0 +1,198 @@
+use alloc::vec::Vec;
+
+use super::{Iter, IterAsc, MinMaxHeap};
+
+/// A collection that retains the `k` largest elements inserted into it.
+///
+/// This is a thin wrapper around a [`MinMaxHeap`] whose minimum is
+/// evicted whenever the length would exceed `k`.
+///
+/// # Examples
+///
+/// ```
+/// use min_max_heap::TopK;
+///
+/// let mut top = TopK::new(3);
+/// for score in vec![7, 2, 9, 4, 8, 1] {
+///     top.insert(score);
+/// }
+///
+/// assert_eq!(top.peek_min(), Some(&7));
+/// assert_eq!(top.into_sorted_vec(), [7, 8, 9]);
+/// ```
+///
+/// [`MinMaxHeap`]: struct.MinMaxHeap.html
+#[derive(Clone, Debug)]
+pub struct TopK<T> {
+    heap: MinMaxHeap<T>,
+    k:    usize,
+}
+
+impl<T> TopK<T> {
+    /// Creates a new, empty `TopK` that retains up to `k` elements.
+    ///
+    /// *O*(1).
+    pub fn new(k: usize) -> Self {
+        TopK {
+            heap: MinMaxHeap::new(),
+            k,
+        }
+    }
+
+    /// The maximum number of elements retained.
+    ///
+    /// *O*(1).
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// The number of elements currently retained.
+    ///
+    /// *O*(1).
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Is the collection empty?
+    ///
+    /// *O*(1).
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Has the collection reached `k` elements?
+    ///
+    /// *O*(1).
+    pub fn is_full(&self) -> bool {
+        self.heap.len() >= self.k
+    }
+
+    /// Drops all retained elements.
+    ///
+    /// *O*(*n*).
+    pub fn clear(&mut self) {
+        self.heap.clear()
+    }
+
+    /// Returns a borrowing iterator over the retained elements in
+    /// arbitrary order.
+    ///
+    /// *O*(1) on creation, and *O*(1) for each `next()` operation.
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.heap.iter()
+    }
+
+    /// Returns the underlying heap.
+    ///
+    /// *O*(1).
+    pub fn into_heap(self) -> MinMaxHeap<T> {
+        self.heap
+    }
+}
+
+impl<T: Ord> TopK<T> {
+    /// Offers an element to the collection.
+    ///
+    /// If the collection is full, the smallest of the retained elements
+    /// and `element` is evicted and returned.
+    ///
+    /// *O*(log *k*).
+    pub fn insert(&mut self, element: T) -> Option<T> {
+        self.heap.push_capped_min(self.k, element)
+    }
+
+    /// Gets a reference to the smallest retained element, if any.
+    ///
+    /// Once the collection is full, this is the threshold that a new
+    /// element must exceed in order to be retained.
+    ///
+    /// *O*(1).
+    pub fn peek_min(&self) -> Option<&T> {
+        self.heap.peek_min()
+    }
+
+    /// Gets a reference to the largest retained element, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_max(&self) -> Option<&T> {
+        self.heap.peek_max()
+    }
+
+    /// Returns an iterator over the retained elements in ascending
+    /// order.
+    ///
+    /// *O*(1) on creation, and *O*(log *k*) for each `next()` operation.
+    pub fn iter_sorted(&self) -> IterAsc<'_, T> {
+        self.heap.iter_asc()
+    }
+
+    /// Returns the retained elements as an ascending vector.
+    ///
+    /// *O*(*k* log *k*).
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        self.heap.into_vec_asc()
+    }
+}
+
+impl<T: Ord> Extend<T> for TopK<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for element in iter {
+            self.insert(element);
+        }
+    }
+}
+
+impl<T> IntoIterator for TopK<T> {
+    type Item = T;
+    type IntoIter = super::IntoIter<T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.heap.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a TopK<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.heap.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn insert() {
+        let mut top = TopK::new(3);
+        assert!(top.is_empty());
+
+        assert_eq!(None, top.insert(5));
+        assert_eq!(None, top.insert(1));
+        assert_eq!(None, top.insert(3));
+        assert!(top.is_full());
+
+        assert_eq!(Some(1), top.insert(4));
+        assert_eq!(Some(0), top.insert(0));
+        assert_eq!(Some(&3), top.peek_min());
+        assert_eq!(Some(&5), top.peek_max());
+
+        assert_eq!(vec![&3, &4, &5], top.iter_sorted().collect::<Vec<_>>());
+        assert_eq!(vec![3, 4, 5], top.into_sorted_vec());
+    }
+
+    #[test]
+    fn zero() {
+        let mut top = TopK::new(0);
+        assert_eq!(Some(1), top.insert(1));
+        assert!(top.is_empty());
+        assert!(top.is_full());
+    }
+
+    #[test]
+    fn extend() {
+        let mut top = TopK::new(10);
+        top.extend((0 .. 100).rev());
+        assert_eq!((90 .. 100).collect::<Vec<_>>(), top.into_sorted_vec());
+    }
+}
@@ -0,0 +1,307 @@
+use core::mem::{self, MaybeUninit};
+use core::{fmt, ptr, slice};
+
+use super::{Compare, Iter, Natural, find_max_slice};
+use super::hole::Hole;
+
+/// A min-max-heap built in a borrowed buffer of uninitialized memory.
+///
+/// This is like an [`ArrayMinMaxHeap`] whose slots belong to the caller:
+/// the heap never allocates, and pushing onto a full heap hands the
+/// element back. When the heap is dropped, its elements are dropped;
+/// alternatively, [`into_initialized`] gives back the initialized part
+/// of the buffer, in heap order, without dropping anything.
+///
+/// # Examples
+///
+/// ```
+/// use std::mem::MaybeUninit;
+/// use min_max_heap::UninitMinMaxHeap;
+///
+/// let mut scratch: [MaybeUninit<u32>; 8] = unsafe { MaybeUninit::uninit().assume_init() };
+///
+/// let mut heap = UninitMinMaxHeap::new(&mut scratch);
+/// for x in vec![6, 2, 9, 4] {
+///     heap.try_push(x).unwrap();
+/// }
+///
+/// assert_eq!(heap.pop_max(), Some(9));
+/// assert_eq!(heap.into_initialized().len(), 3);
+/// ```
+///
+/// [`ArrayMinMaxHeap`]: struct.ArrayMinMaxHeap.html
+/// [`into_initialized`]: struct.UninitMinMaxHeap.html#method.into_initialized
+pub struct UninitMinMaxHeap<'a, T, C = Natural> {
+    data: &'a mut [MaybeUninit<T>],
+    len:  usize,
+    cmp:  C,
+}
+
+impl<'a, T: Ord> UninitMinMaxHeap<'a, T> {
+    /// Creates a new, empty `UninitMinMaxHeap` in `buffer`.
+    ///
+    /// *O*(1).
+    pub fn new(buffer: &'a mut [MaybeUninit<T>]) -> Self {
+        UninitMinMaxHeap::with_comparator(buffer, Natural)
+    }
+}
+
+impl<'a, T, C> UninitMinMaxHeap<'a, T, C> {
+    /// Creates a new, empty `UninitMinMaxHeap` in `buffer`, ordered by
+    /// `cmp`.
+    ///
+    /// *O*(1).
+    pub fn with_comparator(buffer: &'a mut [MaybeUninit<T>], cmp: C) -> Self {
+        UninitMinMaxHeap {
+            data: buffer,
+            len:  0,
+            cmp,
+        }
+    }
+
+    /// The number of elements the heap can hold, which is the length of
+    /// the buffer.
+    ///
+    /// *O*(1).
+    pub fn capacity(&self) -> usize {
+        self.data.len()
+    }
+
+    /// The number of elements in the heap.
+    ///
+    /// *O*(1).
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Is the heap empty?
+    ///
+    /// *O*(1).
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Does the heap fill the buffer?
+    ///
+    /// *O*(1).
+    pub fn is_full(&self) -> bool {
+        self.len == self.data.len()
+    }
+
+    /// Drops all elements from the heap.
+    ///
+    /// *O*(*n*).
+    pub fn clear(&mut self) {
+        let elements: *mut [T] = self.as_mut_slice();
+        // Forget the elements before dropping them, in case a drop panics.
+        self.len = 0;
+        // SAFETY: the elements were initialized, and are no longer counted
+        unsafe {
+            ptr::drop_in_place(elements);
+        }
+    }
+
+    /// Returns an iterator over the elements of the heap in arbitrary
+    /// order.
+    ///
+    /// *O*(1) on creation, and *O*(1) for each `next()` operation.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter(self.as_slice().iter())
+    }
+
+    /// The elements of the heap, in heap order.
+    ///
+    /// *O*(1).
+    pub fn as_slice(&self) -> &[T] {
+        // SAFETY: the first `self.len` slots are initialized
+        unsafe { slice::from_raw_parts(self.data.as_ptr() as *const T, self.len) }
+    }
+
+    /// Gives up the heap, returning the initialized prefix of the buffer,
+    /// in heap order. The elements are not dropped.
+    ///
+    /// *O*(1).
+    pub fn into_initialized(mut self) -> &'a mut [T] {
+        // With the length zeroed, dropping `self` drops no elements.
+        let len = mem::replace(&mut self.len, 0);
+        let data = mem::take(&mut self.data);
+        // SAFETY: the first `len` slots are initialized
+        unsafe { slice::from_raw_parts_mut(data.as_mut_ptr() as *mut T, len) }
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        // SAFETY: the first `self.len` slots are initialized
+        unsafe { slice::from_raw_parts_mut(self.data.as_mut_ptr() as *mut T, self.len) }
+    }
+
+    fn pop_last(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        // SAFETY: the slot was initialized, and is no longer counted
+        Some(unsafe { self.data[self.len].as_ptr().read() })
+    }
+}
+
+impl<'a, T, C: Compare<T>> UninitMinMaxHeap<'a, T, C> {
+    /// Adds an element to the heap, or returns it if the heap is full.
+    ///
+    /// *O*(log *n*).
+    pub fn try_push(&mut self, element: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(element);
+        }
+
+        let pos = self.len;
+        self.data[pos] = MaybeUninit::new(element);
+        self.len += 1;
+
+        // SAFETY: `pos` is a valid index in the slice
+        unsafe {
+            let slice = slice::from_raw_parts_mut(self.data.as_mut_ptr() as *mut T, self.len);
+            Hole::new(slice, pos).bubble_up(&self.cmp);
+        }
+
+        Ok(())
+    }
+
+    /// Gets a reference to the minimum element, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_min(&self) -> Option<&T> {
+        self.as_slice().first()
+    }
+
+    /// Gets a reference to the maximum element, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_max(&self) -> Option<&T> {
+        let slice = self.as_slice();
+        find_max_slice(slice, &self.cmp).map(|max| &slice[max])
+    }
+
+    /// Removes the minimum element, if any.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_min(&mut self) -> Option<T> {
+        let mut item = self.pop_last()?;
+
+        if !self.is_empty() {
+            mem::swap(&mut item, &mut self.as_mut_slice()[0]);
+            self.trickle_down(0);
+        }
+
+        Some(item)
+    }
+
+    /// Removes the maximum element, if any.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_max(&mut self) -> Option<T> {
+        let max = find_max_slice(self.as_slice(), &self.cmp)?;
+        let mut item = self.pop_last().unwrap();
+
+        if max < self.len {
+            mem::swap(&mut item, &mut self.as_mut_slice()[max]);
+            self.trickle_down(max);
+        }
+
+        Some(item)
+    }
+
+    fn trickle_down(&mut self, pos: usize) {
+        // SAFETY: the callers ensure `pos < self.len`
+        unsafe {
+            let slice = slice::from_raw_parts_mut(self.data.as_mut_ptr() as *mut T, self.len);
+            Hole::new(slice, pos).trickle_down(&self.cmp);
+        }
+    }
+}
+
+impl<'a, T, C> Drop for UninitMinMaxHeap<'a, T, C> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<'a, T: fmt::Debug, C> fmt::Debug for UninitMinMaxHeap<'a, T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("UninitMinMaxHeap")
+         .field(&self.as_slice())
+         .finish()
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use std::rc::Rc;
+
+    use super::*;
+    use super::super::MinMaxHeap;
+    use super::super::test_model::{self, ModelHeap};
+
+    fn buffer<T, const N: usize>() -> [MaybeUninit<T>; N] {
+        // SAFETY: an array of `MaybeUninit` needs no initialization
+        unsafe { MaybeUninit::uninit().assume_init() }
+    }
+
+    impl<'a> ModelHeap for UninitMinMaxHeap<'a, u32> {
+        fn push(&mut self, element: u32) { assert_eq!(Ok(()), self.try_push(element)) }
+        fn peek_min(&self) -> Option<u32> { UninitMinMaxHeap::peek_min(self).copied() }
+        fn peek_max(&self) -> Option<u32> { UninitMinMaxHeap::peek_max(self).copied() }
+        fn pop_min(&mut self) -> Option<u32> { UninitMinMaxHeap::pop_min(self) }
+        fn pop_max(&mut self) -> Option<u32> { UninitMinMaxHeap::pop_max(self) }
+        fn len(&self) -> usize { UninitMinMaxHeap::len(self) }
+    }
+
+    #[test]
+    fn random() {
+        let mut storage = buffer::<u32, 20>();
+        let mut heap = UninitMinMaxHeap::new(&mut storage);
+        let model = test_model::random(&mut heap, 2000, 20, |heap| {
+            MinMaxHeap(heap.as_slice().to_vec(), Natural).assert_valid();
+        });
+
+        let mut expected = model.into_vec();
+        let mut initialized = heap.into_initialized().to_vec();
+        expected.sort();
+        initialized.sort();
+        assert_eq!(expected, initialized);
+    }
+
+    #[test]
+    fn full() {
+        let mut storage = buffer::<u32, 3>();
+        let mut heap = UninitMinMaxHeap::new(&mut storage);
+        for x in 0 .. 3 {
+            assert_eq!(Ok(()), heap.try_push(x));
+        }
+        assert_eq!(Err(7), heap.try_push(7));
+        assert_eq!(Some(2), heap.pop_max());
+    }
+
+    #[test]
+    fn drops_elements() {
+        let counter = Rc::new(());
+        let mut storage = buffer::<Rc<()>, 4>();
+
+        let mut heap = UninitMinMaxHeap::new(&mut storage);
+        for _ in 0 .. 3 {
+            heap.try_push(Rc::clone(&counter)).unwrap();
+        }
+        assert_eq!(4, Rc::strong_count(&counter));
+        drop(heap);
+        assert_eq!(1, Rc::strong_count(&counter));
+
+        let mut heap = UninitMinMaxHeap::new(&mut storage);
+        heap.try_push(Rc::clone(&counter)).unwrap();
+        let initialized = heap.into_initialized();
+        assert_eq!(2, Rc::strong_count(&counter));
+
+        // SAFETY: the element was handed over by `into_initialized`
+        unsafe { ptr::drop_in_place(initialized); }
+        assert_eq!(1, Rc::strong_count(&counter));
+    }
+}
@@ -0,0 +1,315 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use super::{Compare, Eviction, Iter, MinMaxHeap, Natural};
+
+/// A min-max-heap whose elements’ total weight is kept within a budget.
+///
+/// This is like a [`BoundedMinMaxHeap`], except that instead of counting
+/// elements it adds up their weights, as given by a function `F`, and
+/// applies its [`Eviction`] policy whenever a push takes the total over
+/// the budget. The weight function is called again for each element that
+/// leaves the heap, so it must give the same weight every time.
+///
+/// # Examples
+///
+/// A buffer of messages limited to 16 bytes, which keeps the newest
+/// messages when full:
+///
+/// ```
+/// use min_max_heap::{Eviction, WeightedMinMaxHeap};
+///
+/// let mut buffer = WeightedMinMaxHeap::new(16, Eviction::EvictMin,
+///                                          |msg: &(u32, String)| msg.1.len());
+///
+/// assert!(buffer.push((1, "hello".to_owned())).is_empty());
+/// assert!(buffer.push((2, "world".to_owned())).is_empty());
+/// assert_eq!(buffer.push((3, "goodbye".to_owned())), [(1, "hello".to_owned())]);
+///
+/// assert_eq!(buffer.total_weight(), 12);
+/// assert_eq!(buffer.len(), 2);
+/// ```
+///
+/// [`BoundedMinMaxHeap`]: struct.BoundedMinMaxHeap.html
+/// [`Eviction`]: enum.Eviction.html
+#[derive(Clone)]
+pub struct WeightedMinMaxHeap<T, F, C = Natural> {
+    heap:     MinMaxHeap<T, C>,
+    weigh:    F,
+    // Wide enough that a push cannot overflow it before eviction brings
+    // it back within `budget`.
+    total:    u128,
+    budget:   usize,
+    eviction: Eviction,
+}
+
+impl<T, F> WeightedMinMaxHeap<T, F>
+where
+    T: Ord,
+    F: Fn(&T) -> usize,
+{
+    /// Creates a new, empty `WeightedMinMaxHeap` that holds elements
+    /// weighing at most `budget` in total, as measured by `weigh`, and
+    /// applies `eviction` when over budget.
+    ///
+    /// *O*(1).
+    pub fn new(budget: usize, eviction: Eviction, weigh: F) -> Self {
+        WeightedMinMaxHeap::with_comparator(budget, eviction, weigh, Natural)
+    }
+}
+
+impl<T, F, C> WeightedMinMaxHeap<T, F, C> {
+    /// Creates a new, empty `WeightedMinMaxHeap` ordered by `cmp` that
+    /// holds elements weighing at most `budget` in total, as measured by
+    /// `weigh`, and applies `eviction` when over budget.
+    ///
+    /// *O*(1).
+    pub fn with_comparator(budget: usize, eviction: Eviction, weigh: F, cmp: C) -> Self {
+        WeightedMinMaxHeap {
+            heap:  MinMaxHeap::with_comparator(cmp),
+            weigh,
+            total: 0,
+            budget,
+            eviction,
+        }
+    }
+
+    /// The greatest total weight the heap holds.
+    ///
+    /// *O*(1).
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    /// The total weight of the elements in the heap, which is never more
+    /// than the budget.
+    ///
+    /// *O*(1).
+    pub fn total_weight(&self) -> usize {
+        self.total as usize
+    }
+
+    /// The policy applied when a push goes over budget.
+    ///
+    /// *O*(1).
+    pub fn eviction(&self) -> Eviction {
+        self.eviction
+    }
+
+    /// The number of elements in the heap.
+    ///
+    /// *O*(1).
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Is the heap empty?
+    ///
+    /// *O*(1).
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Drops all elements from the heap.
+    ///
+    /// *O*(*n*).
+    pub fn clear(&mut self) {
+        self.heap.clear();
+        self.total = 0;
+    }
+
+    /// Returns an iterator over the elements of the heap in arbitrary
+    /// order.
+    ///
+    /// *O*(1) on creation, and *O*(1) for each `next()` operation.
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.heap.iter()
+    }
+
+    /// Converts into the underlying, unbounded `MinMaxHeap`.
+    ///
+    /// *O*(1).
+    pub fn into_heap(self) -> MinMaxHeap<T, C> {
+        self.heap
+    }
+}
+
+impl<T, F, C> WeightedMinMaxHeap<T, F, C>
+where
+    F: Fn(&T) -> usize,
+    C: Compare<T>,
+{
+    /// Pushes an element, then applies the eviction policy until the
+    /// total weight is within budget. Returns the evicted elements, in
+    /// the order they were evicted, or the rejected element.
+    ///
+    /// An element heavier than the whole budget never stays in the heap:
+    /// it is either rejected or, after everything lighter on its side has
+    /// been evicted, evicted itself.
+    ///
+    /// *O*(*k* log *n*), where *k* is the number of elements evicted.
+    pub fn push(&mut self, element: T) -> Vec<T> {
+        let weight = (self.weigh)(&element) as u128;
+        let budget = self.budget as u128;
+
+        if self.eviction == Eviction::Reject {
+            return if self.total + weight > budget {
+                vec![element]
+            } else {
+                self.total += weight;
+                self.heap.push(element);
+                Vec::new()
+            };
+        }
+
+        self.total += weight;
+        self.heap.push(element);
+
+        let mut evicted = Vec::new();
+        while self.total > budget {
+            let next = match self.eviction {
+                Eviction::EvictMin => self.pop_min(),
+                _                  => self.pop_max(),
+            };
+            evicted.extend(next);
+        }
+        evicted
+    }
+
+    /// Gets a reference to the minimum element, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_min(&self) -> Option<&T> {
+        self.heap.peek_min()
+    }
+
+    /// Gets a reference to the maximum element, if any.
+    ///
+    /// *O*(1).
+    pub fn peek_max(&self) -> Option<&T> {
+        self.heap.peek_max()
+    }
+
+    /// Removes the minimum element, if any.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_min(&mut self) -> Option<T> {
+        let element = self.heap.pop_min()?;
+        self.total -= (self.weigh)(&element) as u128;
+        Some(element)
+    }
+
+    /// Removes the maximum element, if any.
+    ///
+    /// *O*(log *n*).
+    pub fn pop_max(&mut self) -> Option<T> {
+        let element = self.heap.pop_max()?;
+        self.total -= (self.weigh)(&element) as u128;
+        Some(element)
+    }
+
+    /// Returns an ascending (sorted) vector, reusing the heap’s storage.
+    ///
+    /// *O*(*n* log *n*).
+    pub fn into_vec_asc(self) -> Vec<T> {
+        self.heap.into_vec_asc()
+    }
+
+    /// Returns a descending (sorted) vector, reusing the heap’s storage.
+    ///
+    /// *O*(*n* log *n*).
+    pub fn into_vec_desc(self) -> Vec<T> {
+        self.heap.into_vec_desc()
+    }
+}
+
+impl<T: fmt::Debug, F, C> fmt::Debug for WeightedMinMaxHeap<T, F, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WeightedMinMaxHeap")
+         .field("heap", &self.heap)
+         .field("total", &self.total)
+         .field("budget", &self.budget)
+         .field("eviction", &self.eviction)
+         .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate rand;
+
+    use super::*;
+    use self::rand::Rng;
+
+    fn check(eviction: Eviction) {
+        let mut rng = rand::thread_rng();
+        let mut heap = WeightedMinMaxHeap::new(100, eviction, |&x: &usize| x);
+        let mut model: Vec<usize> = Vec::new();
+
+        for _ in 0 .. 1000 {
+            let x = rng.gen_range(0 .. 60);
+            let evicted = heap.push(x);
+
+            model.push(x);
+            model.sort();
+            let mut expected = Vec::new();
+            if eviction == Eviction::Reject {
+                if model.iter().sum::<usize>() > 100 {
+                    let pos = model.iter().position(|&y| y == x).unwrap();
+                    expected.push(model.remove(pos));
+                }
+            } else {
+                while model.iter().sum::<usize>() > 100 {
+                    expected.push(if eviction == Eviction::EvictMin {
+                        model.remove(0)
+                    } else {
+                        model.pop().unwrap()
+                    });
+                }
+            }
+
+            assert_eq!(expected, evicted);
+            assert_eq!(model.iter().sum::<usize>(), heap.total_weight());
+            assert_eq!(model.len(), heap.len());
+
+            if rng.gen_range(0 .. 4) == 0 {
+                assert_eq!(model.pop(), heap.pop_max());
+            }
+        }
+    }
+
+    #[test]
+    fn evict_max() {
+        check(Eviction::EvictMax);
+    }
+
+    #[test]
+    fn evict_min() {
+        check(Eviction::EvictMin);
+    }
+
+    #[test]
+    fn reject() {
+        check(Eviction::Reject);
+    }
+
+    #[test]
+    fn total_past_usize_max() {
+        let mut heap = WeightedMinMaxHeap::new(usize::MAX, Eviction::EvictMin, |&x: &usize| x);
+        assert!(heap.push(usize::MAX - 1).is_empty());
+        assert_eq!(vec![2], heap.push(2));
+        assert_eq!(usize::MAX - 1, heap.total_weight());
+
+        let mut heap = WeightedMinMaxHeap::new(usize::MAX, Eviction::EvictMax, |&x: &usize| x);
+        assert!(heap.push(usize::MAX - 1).is_empty());
+        assert_eq!(vec![usize::MAX - 1], heap.push(2));
+        assert_eq!(2, heap.total_weight());
+        assert_eq!(Some(2), heap.pop_min());
+        assert_eq!(0, heap.total_weight());
+
+        let mut heap = WeightedMinMaxHeap::new(usize::MAX, Eviction::Reject, |&x: &usize| x);
+        assert!(heap.push(usize::MAX).is_empty());
+        assert_eq!(vec![1], heap.push(1));
+        assert_eq!(usize::MAX, heap.total_weight());
+    }
+}
@@ -0,0 +1,47 @@
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use super::MinMaxHeap;
+
+// Moving elements within the heap, and popping them, leaves stale copies
+// of their bytes in the buffer, so zeroizing a heap clears the whole
+// capacity and not just the live elements. As with `Vec`, neither can
+// scrub buffers that the heap has already outgrown and freed; reserve
+// capacity up front to avoid that.
+
+impl<T: Zeroize, C> Zeroize for MinMaxHeap<T, C> {
+    /// Zeroes the elements and the spare capacity of the heap, leaving it
+    /// empty.
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+/// Dropping a heap drops its elements, which zeroize themselves; wrap
+/// the heap in `Zeroizing` to scrub its spare capacity on drop as well.
+impl<T: ZeroizeOnDrop, C> ZeroizeOnDrop for MinMaxHeap<T, C> { }
+
+// Reading the spare capacity back needs `unsafe`.
+#[cfg(all(test, not(feature = "safe")))]
+mod test {
+    use super::*;
+    use core::slice;
+
+    #[test]
+    fn zeroes_capacity() {
+        let mut heap = MinMaxHeap::with_capacity(64);
+        heap.extend((1 ..= 40u64).map(|i| i * 0x0101_0101));
+        for _ in 0 .. 20 {
+            heap.pop_max();
+        }
+
+        let capacity = heap.capacity();
+        heap.zeroize();
+
+        assert!(heap.is_empty());
+        assert_eq!(capacity, heap.capacity());
+        // SAFETY: the buffer is still allocated, and zeroize has written
+        // every element of it
+        let buffer = unsafe { slice::from_raw_parts(heap.0.as_ptr(), capacity) };
+        assert!(buffer.iter().all(|&word| word == 0));
+    }
+}
@@ -7,6 +7,7 @@ pub struct FakeHeap<T> {
 }
 
 impl<T: Clone + Ord> FakeHeap<T> {
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         Self {
             tree: BTreeMap::new(),